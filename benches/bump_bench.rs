@@ -0,0 +1,74 @@
+//! Criterion benchmarks for the bump, version-read and JSON-rewrite paths.
+//!
+//! All inputs are built in memory so disk I/O does not add noise. Save a
+//! baseline on the main branch and compare a change against it:
+//!
+//! ```sh
+//! cargo bench --bench bump_bench -- --save-baseline main
+//! cargo bench --bench bump_bench -- --baseline main
+//! ```
+
+use criterion::{BenchmarkId, Criterion, black_box, criterion_group, criterion_main};
+use semver::Version;
+use semver_cli::bump::BumpType;
+use semver_cli::json::{JsonIndent, replace_json_version};
+use semver_cli::sources::{
+    read_composer_json_version, read_package_json_version, read_version_file,
+};
+
+const JSON_KEYS: usize = 100;
+
+fn manifest(keys: usize) -> String {
+    let mut fields = vec![r#"  "version": "1.2.3""#.to_string()];
+    fields.extend((1..keys).map(|i| format!(r#"  "key-{}": "value-{}""#, i, i)));
+    format!("{{\n{}\n}}", fields.join(",\n"))
+}
+
+fn bump(c: &mut Criterion) {
+    let mut group = c.benchmark_group("bump_version");
+    let version = Version::parse("1.2.3").unwrap();
+    for bump in [BumpType::Major, BumpType::Minor, BumpType::Patch] {
+        group.bench_with_input(BenchmarkId::from_parameter(bump), &bump, |b, &bump| {
+            b.iter(|| {
+                let mut version = black_box(&version).clone();
+                bump.apply(&mut version);
+                version
+            })
+        });
+    }
+    group.finish();
+}
+
+fn read_version(c: &mut Criterion) {
+    let package = r#"{"name": "demo", "version": "1.2.3", "private": true}"#;
+    let composer = r#"{"name": "acme/demo", "type": "library", "version": "1.2.3"}"#;
+
+    let mut group = c.benchmark_group("get_current_version");
+    group.bench_function("package.json", |b| {
+        b.iter(|| read_package_json_version(black_box(package)))
+    });
+    group.bench_function("composer.json", |b| {
+        b.iter(|| read_composer_json_version(black_box(composer)))
+    });
+    group.bench_function("VERSION", |b| {
+        b.iter(|| read_version_file(black_box("1.2.3\n")))
+    });
+    group.finish();
+}
+
+fn update_json(c: &mut Criterion) {
+    let contents = manifest(JSON_KEYS);
+
+    let mut group = c.benchmark_group("update_json_version");
+    group.bench_with_input(
+        BenchmarkId::new("keys", JSON_KEYS),
+        &contents,
+        |b, contents| {
+            b.iter(|| replace_json_version(black_box(contents), "1.3.0", JsonIndent::default()))
+        },
+    );
+    group.finish();
+}
+
+criterion_group!(benches, bump, read_version, update_json);
+criterion_main!(benches);
@@ -0,0 +1,50 @@
+//! Compare sequential and concurrent writes of 20 version files.
+//!
+//! Run with `cargo bench --bench writes`.
+
+use semver_cli::writes::write_all_concurrent;
+use std::fs;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+const FILES: usize = 20;
+const ROUNDS: u32 = 50;
+
+fn updates(dir: &std::path::Path) -> Vec<(PathBuf, String)> {
+    (0..FILES)
+        .map(|i| {
+            let contents = format!(
+                "{{\n  \"name\": \"pkg-{}\",\n  \"version\": \"1.0.{}\"\n}}",
+                i, i
+            );
+            (dir.join(format!("package-{}.json", i)), contents)
+        })
+        .collect()
+}
+
+fn time(mut run: impl FnMut()) -> Duration {
+    let start = Instant::now();
+    for _ in 0..ROUNDS {
+        run();
+    }
+    start.elapsed() / ROUNDS
+}
+
+fn main() {
+    let dir = tempfile::tempdir().unwrap();
+    let updates = updates(dir.path());
+
+    let sequential = time(|| {
+        for (path, contents) in &updates {
+            fs::write(path, contents).unwrap();
+        }
+    });
+    let concurrent = time(|| {
+        for result in write_all_concurrent(updates.clone()) {
+            result.unwrap();
+        }
+    });
+
+    println!("sequential: {:?} per {} files", sequential, FILES);
+    println!("concurrent: {:?} per {} files", concurrent, FILES);
+}
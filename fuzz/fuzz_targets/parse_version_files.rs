@@ -0,0 +1,26 @@
+//! Feed arbitrary bytes to the version file readers; none of them may panic.
+//!
+//! Needs a nightly toolchain and `cargo install cargo-fuzz`:
+//!
+//! ```sh
+//! cargo +nightly fuzz run parse_version_files
+//! ```
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use semver_cli::sources::{
+    read_composer_json_version, read_package_json_version, read_version_file,
+    read_version_json_version,
+};
+
+fuzz_target!(|data: &[u8]| {
+    // Files are read with `fs::read_to_string`, so only UTF-8 reaches the
+    // readers; lossy decoding still exercises them with every input.
+    let contents = String::from_utf8_lossy(data);
+
+    let _ = read_package_json_version(&contents);
+    let _ = read_composer_json_version(&contents);
+    let _ = read_version_json_version(&contents);
+    let _ = read_version_file(&contents);
+});
@@ -0,0 +1,198 @@
+//! The `--log-file` audit trail: one JSON line when a run starts and one
+//! when it ends, appended to the same file across runs.
+
+use crate::timestamp::now_rfc3339;
+use serde::Serialize;
+use std::fs::{File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+
+static LOGGER: OnceLock<Mutex<StructuredLogger>> = OnceLock::new();
+
+#[derive(Serialize)]
+#[serde(tag = "event", rename_all = "lowercase")]
+enum LogEntry<'a> {
+    Start {
+        ts: String,
+        args: &'a [String],
+    },
+    End {
+        ts: String,
+        result: &'static str,
+        old: Option<&'a str>,
+        new: Option<&'a str>,
+        files: &'a [String],
+        #[serde(skip_serializing_if = "Option::is_none")]
+        error: Option<&'a str>,
+    },
+}
+
+/// Writes the start and end entries of one run, collecting what the run
+/// did in between.
+#[derive(Debug)]
+pub struct StructuredLogger {
+    file: File,
+    old: Option<String>,
+    new: Option<String>,
+    files: Vec<String>,
+    last_error: Option<String>,
+    ended: bool,
+}
+
+impl StructuredLogger {
+    /// Open `path` for appending, creating it if needed.
+    pub fn open(path: &Path) -> io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(StructuredLogger {
+            file,
+            old: None,
+            new: None,
+            files: Vec::new(),
+            last_error: None,
+            ended: false,
+        })
+    }
+
+    /// Log the start of a run with its command-line `args`.
+    pub fn log_start(&mut self, args: &[String]) -> io::Result<()> {
+        let entry = LogEntry::Start {
+            ts: now_rfc3339(),
+            args,
+        };
+        write_entry(&mut self.file, &entry)
+    }
+
+    /// Remember the versions of the bump for the end entry.
+    pub fn record_bump(&mut self, old: &str, new: &str) {
+        self.old = Some(old.to_string());
+        self.new = Some(new.to_string());
+    }
+
+    /// Remember the files the run updated for the end entry.
+    pub fn record_files(&mut self, files: &[PathBuf]) {
+        self.files = files
+            .iter()
+            .map(|path| path.display().to_string())
+            .collect();
+    }
+
+    /// Remember a message printed to stderr; the last one explains a failed
+    /// run.
+    pub fn record_stderr(&mut self, message: &str) {
+        self.last_error = Some(message.to_string());
+    }
+
+    /// Log the end of the run, as failed when `failed` is set. Only the
+    /// first call writes an entry.
+    pub fn log_end(&mut self, failed: bool) -> io::Result<()> {
+        if self.ended {
+            return Ok(());
+        }
+        self.ended = true;
+        let entry = LogEntry::End {
+            ts: now_rfc3339(),
+            result: if failed { "error" } else { "ok" },
+            old: self.old.as_deref(),
+            new: self.new.as_deref(),
+            files: &self.files,
+            error: failed.then(|| self.last_error.as_deref().unwrap_or("")),
+        };
+        write_entry(&mut self.file, &entry)
+    }
+}
+
+/// Write `entry` as one line with a single write, so concurrent runs never
+/// interleave partial entries.
+fn write_entry(file: &mut File, entry: &LogEntry) -> io::Result<()> {
+    let mut line = serde_json::to_string(entry)?;
+    line.push('\n');
+    file.write_all(line.as_bytes())
+}
+
+/// Make `logger` the run's logger for [`record`] and [`finish`]. Only the
+/// first call takes effect.
+pub fn install(logger: StructuredLogger) {
+    let _ = LOGGER.set(Mutex::new(logger));
+}
+
+/// Update the installed logger, if there is one.
+pub fn record(update: impl FnOnce(&mut StructuredLogger)) {
+    if let Some(logger) = LOGGER.get() {
+        update(&mut logger.lock().unwrap_or_else(|err| err.into_inner()));
+    }
+}
+
+/// Log the end of the run to the installed logger, if there is one.
+pub fn finish(failed: bool) {
+    record(|logger| {
+        if let Err(err) = logger.log_end(failed) {
+            eprintln!("Failed to write the log file: {}", err);
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{Value, json};
+    use std::fs;
+
+    fn entries(path: &Path) -> Vec<Value> {
+        fs::read_to_string(path)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn logs_start_and_successful_end() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("semver.log");
+
+        let mut logger = StructuredLogger::open(&path).unwrap();
+        let args = [
+            "semver".to_string(),
+            "--bump".to_string(),
+            "patch".to_string(),
+        ];
+        logger.log_start(&args).unwrap();
+        logger.record_bump("1.0.0", "1.0.1");
+        logger.record_files(&[PathBuf::from("VERSION")]);
+        logger.record_stderr("Warning: something harmless");
+        logger.log_end(false).unwrap();
+        logger.log_end(true).unwrap();
+
+        let entries = entries(&path);
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0]["event"], "start");
+        assert_eq!(entries[0]["args"], json!(["semver", "--bump", "patch"]));
+        assert!(entries[0]["ts"].as_str().unwrap().ends_with('Z'));
+        let mut end = entries[1].clone();
+        end.as_object_mut().unwrap().remove("ts");
+        assert_eq!(
+            end,
+            json!({"event": "end", "result": "ok", "old": "1.0.0", "new": "1.0.1", "files": ["VERSION"]})
+        );
+    }
+
+    #[test]
+    fn failed_end_carries_the_last_error() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("semver.log");
+        fs::write(&path, "{\"event\":\"start\"}\n").unwrap();
+
+        let mut logger = StructuredLogger::open(&path).unwrap();
+        logger.record_stderr("Warning: first");
+        logger.record_stderr("No version found");
+        logger.log_end(true).unwrap();
+
+        let entries = entries(&path);
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[1]["result"], "error");
+        assert_eq!(entries[1]["error"], "No version found");
+        assert_eq!(entries[1]["old"], Value::Null);
+        assert_eq!(entries[1]["files"], json!([]));
+    }
+}
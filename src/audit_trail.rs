@@ -0,0 +1,234 @@
+//! The `--audit-trail` file: a hash chain of every bump, one JSON line per
+//! entry, checked by `semver audit --verify`.
+//!
+//! Each line carries the SHA-256 of the line before it and of its own
+//! contents, so editing, removing or reordering entries breaks the chain.
+
+use crate::error::BumpError;
+use crate::sha256::sha256_hex;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::PathBuf;
+
+pub const AUDIT_TRAIL_FILE: &str = ".semver-audit";
+
+/// What `prev` holds on the first entry of a chain.
+pub const GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+/// One bump, as recorded in the audit trail.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditEntry {
+    #[serde(rename = "ts")]
+    pub timestamp: String,
+    pub old: String,
+    pub new: String,
+    #[serde(rename = "bump")]
+    pub bump_type: String,
+    pub user: String,
+    /// SHA-256 of each version file before the bump, by path.
+    pub before: BTreeMap<String, String>,
+    /// SHA-256 of each version file after the bump, by path.
+    pub after: BTreeMap<String, String>,
+}
+
+/// The hashed part of a line: the entry and the hash of the line before.
+#[derive(Serialize)]
+struct Linked<'a> {
+    #[serde(flatten)]
+    entry: &'a AuditEntry,
+    prev: &'a str,
+}
+
+#[derive(Deserialize)]
+struct Record {
+    #[serde(flatten)]
+    entry: AuditEntry,
+    prev: String,
+    hash: String,
+}
+
+impl Record {
+    fn expected_hash(&self) -> String {
+        link_hash(&self.entry, &self.prev)
+    }
+}
+
+fn link_hash(entry: &AuditEntry, prev: &str) -> String {
+    let linked = serde_json::to_string(&Linked { entry, prev }).unwrap_or_default();
+    sha256_hex(linked.as_bytes())
+}
+
+/// The audit trail at `path`.
+#[derive(Debug, Clone)]
+pub struct AuditChain {
+    path: PathBuf,
+}
+
+impl AuditChain {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        AuditChain { path: path.into() }
+    }
+
+    /// Append `entry`, linked to the last entry in the file, creating the
+    /// file if needed.
+    pub fn append(&self, entry: &AuditEntry) -> Result<(), BumpError> {
+        let records = match fs::read_to_string(&self.path) {
+            Ok(contents) => self.parse(&contents)?,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Vec::new(),
+            Err(err) => return Err(self.error(None, err.to_string())),
+        };
+        let prev = records
+            .last()
+            .map_or(GENESIS_HASH, |(_, record)| record.hash.as_str());
+
+        let mut line = serde_json::to_value(Linked { entry, prev })
+            .map_err(|err| self.error(None, err.to_string()))?;
+        line["hash"] = link_hash(entry, prev).into();
+        let mut line = line.to_string();
+        line.push('\n');
+
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .and_then(|mut file| file.write_all(line.as_bytes()))
+            .map_err(|source| BumpError::Write {
+                path: self.path.clone(),
+                source,
+            })
+    }
+
+    /// Check that every entry matches its hash and links to the one before.
+    pub fn verify(&self) -> Result<(), BumpError> {
+        let contents =
+            fs::read_to_string(&self.path).map_err(|err| self.error(None, err.to_string()))?;
+
+        let mut prev = GENESIS_HASH.to_string();
+        for (line, record) in self.parse(&contents)? {
+            if record.prev != prev {
+                let message = if prev == GENESIS_HASH {
+                    "the first entry does not start the chain".to_string()
+                } else {
+                    "entry does not link to the entry before it".to_string()
+                };
+                return Err(self.error(Some(line), message));
+            }
+            if record.hash != record.expected_hash() {
+                return Err(self.error(Some(line), "entry does not match its hash".to_string()));
+            }
+            prev = record.hash;
+        }
+        Ok(())
+    }
+
+    /// The entries of `contents` with their 1-based line numbers.
+    fn parse(&self, contents: &str) -> Result<Vec<(usize, Record)>, BumpError> {
+        contents
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(index, line)| {
+                serde_json::from_str(line)
+                    .map(|record| (index + 1, record))
+                    .map_err(|err| self.error(Some(index + 1), err.to_string()))
+            })
+            .collect()
+    }
+
+    fn error(&self, line: Option<usize>, message: String) -> BumpError {
+        BumpError::AuditTrail {
+            path: self.path.clone(),
+            line,
+            message,
+        }
+    }
+}
+
+/// SHA-256 of each of `paths` that can be read, keyed by its displayed path.
+pub fn file_digests(paths: &[PathBuf]) -> BTreeMap<String, String> {
+    paths
+        .iter()
+        .filter_map(|path| {
+            let contents = fs::read(path).ok()?;
+            Some((path.display().to_string(), sha256_hex(&contents)))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    fn entry(old: &str, new: &str) -> AuditEntry {
+        AuditEntry {
+            timestamp: "2024-03-05T14:07:09Z".to_string(),
+            old: old.to_string(),
+            new: new.to_string(),
+            bump_type: "patch".to_string(),
+            user: "Jane Doe <jane@example.com>".to_string(),
+            before: BTreeMap::from([("VERSION".to_string(), sha256_hex(old.as_bytes()))]),
+            after: BTreeMap::from([("VERSION".to_string(), sha256_hex(new.as_bytes()))]),
+        }
+    }
+
+    fn chain_of_three(dir: &Path) -> AuditChain {
+        let chain = AuditChain::new(dir.join(AUDIT_TRAIL_FILE));
+        chain.append(&entry("1.0.0", "1.0.1")).unwrap();
+        chain.append(&entry("1.0.1", "1.0.2")).unwrap();
+        chain.append(&entry("1.0.2", "1.0.3")).unwrap();
+        chain
+    }
+
+    fn line_of(err: BumpError) -> Option<usize> {
+        match err {
+            BumpError::AuditTrail { line, .. } => line,
+            other => panic!("unexpected error: {}", other),
+        }
+    }
+
+    #[test]
+    fn appended_entries_form_a_chain() {
+        let dir = tempfile::tempdir().unwrap();
+        let chain = chain_of_three(dir.path());
+
+        chain.verify().unwrap();
+        let contents = fs::read_to_string(dir.path().join(AUDIT_TRAIL_FILE)).unwrap();
+        let records: Vec<Record> = contents
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(records.len(), 3);
+        assert_eq!(records[0].prev, GENESIS_HASH);
+        assert_eq!(records[1].prev, records[0].hash);
+        assert_eq!(records[2].entry, entry("1.0.2", "1.0.3"));
+    }
+
+    #[test]
+    fn detects_tampering() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(AUDIT_TRAIL_FILE);
+        let chain = chain_of_three(dir.path());
+        let contents = fs::read_to_string(&path).unwrap();
+
+        fs::write(
+            &path,
+            contents.replacen("\"new\":\"1.0.2\"", "\"new\":\"2.0.0\"", 1),
+        )
+        .unwrap();
+        assert_eq!(line_of(chain.verify().unwrap_err()), Some(2));
+
+        let lines: Vec<&str> = contents.lines().collect();
+        fs::write(&path, format!("{}\n{}\n", lines[0], lines[2])).unwrap();
+        assert_eq!(line_of(chain.verify().unwrap_err()), Some(2));
+
+        fs::write(&path, format!("{}\n{}\n", lines[1], lines[2])).unwrap();
+        assert_eq!(line_of(chain.verify().unwrap_err()), Some(1));
+
+        fs::write(&path, format!("{}\nnot json\n", lines[0])).unwrap();
+        assert_eq!(line_of(chain.verify().unwrap_err()), Some(2));
+        assert!(chain.append(&entry("1.0.3", "1.0.4")).is_err());
+    }
+}
@@ -0,0 +1,124 @@
+//! Line-based editing of `variables.version` in `azure-pipelines.yml`.
+//!
+//! The file is never parsed as YAML so comments, anchors and layout are
+//! left exactly as they were. Both variable syntaxes are understood:
+//!
+//! ```yaml
+//! variables:
+//!   version: 1.2.3
+//! ```
+//!
+//! ```yaml
+//! variables:
+//! - name: version
+//!   value: 1.2.3
+//! ```
+
+use regex::Regex;
+use std::ops::Range;
+use std::sync::LazyLock;
+
+static MAP_ENTRY: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"^\s+version:\s*(?:"([^"]*)"|'([^']*)'|([^\s#'"]+))"#).unwrap());
+static SEQ_NAME: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"^\s*-\s*name:\s*["']?version["']?\s*(?:#.*)?$"#).unwrap());
+static SEQ_VALUE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"^\s*(?:-\s*)?value:\s*(?:"([^"]*)"|'([^']*)'|([^\s#'"]+))"#).unwrap()
+});
+
+/// Read `variables.version` from the pipeline definition.
+pub fn read_azure_version(contents: &str) -> Option<String> {
+    version_span(contents).map(|span| contents[span].to_string())
+}
+
+/// Replace `variables.version` in place, returning the new file contents.
+pub fn replace_azure_version(contents: &str, new_version: &str) -> Option<String> {
+    let span = version_span(contents)?;
+    let mut output = String::with_capacity(contents.len());
+    output.push_str(&contents[..span.start]);
+    output.push_str(new_version);
+    output.push_str(&contents[span.end..]);
+    Some(output)
+}
+
+/// Byte range of the version value inside the top-level `variables:` block.
+fn version_span(contents: &str) -> Option<Range<usize>> {
+    let mut offset = 0;
+    let mut in_variables = false;
+    let mut in_version_item = false;
+
+    for line in contents.split_inclusive('\n') {
+        let start = offset;
+        offset += line.len();
+        let text = line.trim_end_matches(['\r', '\n']);
+        let trimmed = text.trim_start();
+
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+
+        let top_level = !text.starts_with([' ', '\t']);
+        if top_level && !trimmed.starts_with('-') {
+            in_variables = text.trim_end() == "variables:";
+            in_version_item = false;
+            continue;
+        }
+
+        if !in_variables {
+            continue;
+        }
+
+        if trimmed.starts_with('-') {
+            in_version_item = SEQ_NAME.is_match(text);
+            continue;
+        }
+
+        let pattern = if in_version_item {
+            &*SEQ_VALUE
+        } else {
+            &*MAP_ENTRY
+        };
+
+        if let Some(caps) = pattern.captures(text) {
+            let value = caps.get(1).or(caps.get(2)).or(caps.get(3))?;
+            return Some(start + value.start()..start + value.end());
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_and_replaces_map_syntax() {
+        let yaml = "trigger:\n- main\n\nvariables:\n  # release version\n  version: '1.2.3'\n  buildConfiguration: Release\n";
+
+        assert_eq!(read_azure_version(yaml).as_deref(), Some("1.2.3"));
+        assert_eq!(
+            replace_azure_version(yaml, "1.3.0").unwrap(),
+            "trigger:\n- main\n\nvariables:\n  # release version\n  version: '1.3.0'\n  buildConfiguration: Release\n"
+        );
+    }
+
+    #[test]
+    fn reads_and_replaces_sequence_syntax() {
+        let yaml = "variables:\n- group: shared\n- name: version\n  value: 2.0.0 # bumped by semver\n- name: other\n  value: 9.9.9\n";
+
+        assert_eq!(read_azure_version(yaml).as_deref(), Some("2.0.0"));
+        assert_eq!(
+            replace_azure_version(yaml, "2.0.1").unwrap(),
+            "variables:\n- group: shared\n- name: version\n  value: 2.0.1 # bumped by semver\n- name: other\n  value: 9.9.9\n"
+        );
+    }
+
+    #[test]
+    fn ignores_version_keys_outside_variables() {
+        let yaml = "parameters:\n  version: 0.0.1\nsteps:\n- script: echo\n  version: 0.0.2\n";
+
+        assert_eq!(read_azure_version(yaml), None);
+        assert_eq!(replace_azure_version(yaml, "1.0.0"), None);
+    }
+}
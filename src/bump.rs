@@ -0,0 +1,285 @@
+//! The release bumps `--bump` accepts.
+
+use crate::error::BumpError;
+use semver::{BuildMetadata, Prerelease, Version};
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BumpType {
+    Major,
+    Minor,
+    Patch,
+}
+
+/// Which lower release numbers a bump resets to zero.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BumpOptions {
+    /// Zero the patch on minor and major bumps; off with `--no-reset-patch`.
+    pub reset_patch: bool,
+    /// Zero the minor on major bumps; off with `--no-reset-minor`.
+    pub reset_minor: bool,
+}
+
+impl Default for BumpOptions {
+    fn default() -> Self {
+        BumpOptions {
+            reset_patch: true,
+            reset_minor: true,
+        }
+    }
+}
+
+impl BumpType {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            BumpType::Major => "major",
+            BumpType::Minor => "minor",
+            BumpType::Patch => "patch",
+        }
+    }
+
+    /// Increment the matching release number, resetting the lower ones.
+    ///
+    /// Pre-release and build metadata are left for the caller to set.
+    pub fn apply(self, version: &mut Version) {
+        self.apply_with(version, BumpOptions::default());
+    }
+
+    /// Like [`BumpType::apply`], but only resetting the lower release
+    /// numbers `options` asks for.
+    pub fn apply_with(self, version: &mut Version, options: BumpOptions) {
+        match self {
+            BumpType::Major => version.major += 1,
+            BumpType::Minor => version.minor += 1,
+            BumpType::Patch => version.patch += 1,
+        }
+        if self == BumpType::Major && options.reset_minor {
+            version.minor = 0;
+        }
+        if self != BumpType::Patch && options.reset_patch {
+            version.patch = 0;
+        }
+    }
+}
+
+/// The release `bump` makes from `current`, without pre-release or build
+/// metadata; what `semver next` and `--print-next` print.
+pub fn compute_next_version(current: &Version, bump: BumpType) -> Version {
+    let mut next = current.clone();
+    bump.apply(&mut next);
+    next.pre = Prerelease::EMPTY;
+    next.build = BuildMetadata::EMPTY;
+    next
+}
+
+/// The two versions of `--bump-pre-then-release`: the first pre-release of
+/// the next `bump`, e.g. `1.1.0-rc.1`, and the release it leads to.
+///
+/// `pre_id` must be a valid pre-release identifier.
+pub fn perform_staged_bump(current: &Version, bump: BumpType, pre_id: &str) -> (Version, Version) {
+    let release = compute_next_version(current, bump);
+    let mut pre = release.clone();
+    pre.pre = Prerelease::new(&format!("{}.1", pre_id)).expect("validated pre-release id");
+    (pre, release)
+}
+
+/// `version` without its pre-release and build metadata, e.g. `1.2.3-beta.1`
+/// → `1.2.3`, for `--clean-pre-release`.
+pub fn clean_prerelease(version: &Version) -> Result<Version, BumpError> {
+    if version.pre.is_empty() && version.build.is_empty() {
+        return Err(BumpError::NotPrerelease {
+            version: version.to_string(),
+        });
+    }
+    let mut clean = version.clone();
+    clean.pre = Prerelease::EMPTY;
+    clean.build = BuildMetadata::EMPTY;
+    Ok(clean)
+}
+
+/// Read the steps of a `--batch-file`: one bump type per line, applied in
+/// order. Blank lines and lines starting with `#` are ignored.
+///
+/// Every line is checked before any step is returned, so a bad step never
+/// leaves a batch half applied.
+pub fn parse_batch_file(path: &Path) -> Result<Vec<BumpType>, BumpError> {
+    let invalid = |line: Option<usize>, message: String| BumpError::InvalidBatchFile {
+        path: path.to_path_buf(),
+        line,
+        message,
+    };
+
+    let contents = fs::read_to_string(path).map_err(|err| invalid(None, err.to_string()))?;
+    let mut steps = Vec::new();
+    for (index, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let step = line.parse::<BumpType>().map_err(|err| {
+            invalid(
+                Some(index + 1),
+                format!("{}; expected major, minor or patch", err),
+            )
+        })?;
+        steps.push(step);
+    }
+    if steps.is_empty() {
+        return Err(invalid(None, "no bump steps".to_string()));
+    }
+    Ok(steps)
+}
+
+impl fmt::Display for BumpType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for BumpType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "major" => Ok(BumpType::Major),
+            "minor" => Ok(BumpType::Minor),
+            "patch" => Ok(BumpType::Patch),
+            _ => Err(format!("invalid bump type '{}'", s)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_through_strings() {
+        for bump in [BumpType::Major, BumpType::Minor, BumpType::Patch] {
+            assert_eq!(bump.to_string().parse::<BumpType>(), Ok(bump));
+        }
+        assert_eq!("minor".parse::<BumpType>(), Ok(BumpType::Minor));
+        assert_eq!(format!("{}", BumpType::Major), "major");
+    }
+
+    #[test]
+    fn applies_release_bumps() {
+        let bumped = |bump: BumpType| {
+            let mut version = Version::new(1, 2, 3);
+            bump.apply(&mut version);
+            version
+        };
+
+        assert_eq!(bumped(BumpType::Major), Version::new(2, 0, 0));
+        assert_eq!(bumped(BumpType::Minor), Version::new(1, 3, 0));
+        assert_eq!(bumped(BumpType::Patch), Version::new(1, 2, 4));
+    }
+
+    #[test]
+    fn keeps_lower_numbers_when_resets_are_off() {
+        let bumped = |bump: BumpType, reset_patch: bool, reset_minor: bool| {
+            let mut version = Version::new(1, 2, 3);
+            let options = BumpOptions {
+                reset_patch,
+                reset_minor,
+            };
+            bump.apply_with(&mut version, options);
+            version
+        };
+
+        assert_eq!(bumped(BumpType::Minor, false, true), Version::new(1, 3, 3));
+        assert_eq!(bumped(BumpType::Minor, true, false), Version::new(1, 3, 0));
+        assert_eq!(bumped(BumpType::Minor, false, false), Version::new(1, 3, 3));
+        assert_eq!(bumped(BumpType::Major, false, true), Version::new(2, 0, 3));
+        assert_eq!(bumped(BumpType::Major, true, false), Version::new(2, 2, 0));
+        assert_eq!(bumped(BumpType::Major, false, false), Version::new(2, 2, 3));
+        assert_eq!(bumped(BumpType::Patch, false, false), Version::new(1, 2, 4));
+    }
+
+    #[test]
+    fn cleans_prerelease_and_build_metadata() {
+        let clean = |version: &str| clean_prerelease(&Version::parse(version).unwrap());
+
+        assert_eq!(clean("1.2.3-beta.1").unwrap(), Version::new(1, 2, 3));
+        assert_eq!(clean("1.2.3-rc.2+build.7").unwrap(), Version::new(1, 2, 3));
+        assert_eq!(clean("1.2.3+build.7").unwrap(), Version::new(1, 2, 3));
+
+        let err = clean("1.2.3").unwrap_err();
+        assert_eq!(err.exit_code(), 4);
+        assert_eq!(
+            err.to_string(),
+            "Version 1.2.3 has no pre-release or build metadata to clean"
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_names() {
+        assert_eq!(
+            "Major".parse::<BumpType>(),
+            Err("invalid bump type 'Major'".to_string())
+        );
+    }
+
+    #[test]
+    fn computes_next_release() {
+        let current = Version::parse("1.2.3-rc.1+build.5").unwrap();
+
+        assert_eq!(
+            compute_next_version(&current, BumpType::Minor),
+            Version::new(1, 3, 0)
+        );
+        assert_eq!(
+            compute_next_version(&Version::new(0, 9, 9), BumpType::Major),
+            Version::new(1, 0, 0)
+        );
+    }
+
+    #[test]
+    fn stages_pre_release_before_release() {
+        let (pre, release) = perform_staged_bump(&Version::new(1, 0, 0), BumpType::Minor, "rc");
+
+        assert_eq!(pre, Version::parse("1.1.0-rc.1").unwrap());
+        assert_eq!(release, Version::new(1, 1, 0));
+    }
+
+    #[test]
+    fn parses_batch_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("release.batch");
+        fs::write(
+            &path,
+            "# generated by release.sh\nminor\n\n  patch\n# hotfix\npatch\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            parse_batch_file(&path).unwrap(),
+            [BumpType::Minor, BumpType::Patch, BumpType::Patch]
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_batch_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("release.batch");
+
+        fs::write(&path, "minor\nbugfix\n").unwrap();
+        assert_eq!(
+            parse_batch_file(&path).unwrap_err().to_string(),
+            format!(
+                "Invalid batch file {}, line 2: invalid bump type 'bugfix'; expected major, minor or patch",
+                path.display()
+            )
+        );
+
+        fs::write(&path, "# nothing to do\n\n").unwrap();
+        assert!(matches!(
+            parse_batch_file(&path),
+            Err(BumpError::InvalidBatchFile { line: None, .. })
+        ));
+        assert!(parse_batch_file(&dir.path().join("missing.batch")).is_err());
+    }
+}
@@ -0,0 +1,89 @@
+//! Calendar versioning: `YYYY.M.MICRO` (or `YY.M.MICRO`).
+
+use chrono::{Datelike, NaiveDate};
+use semver::Version;
+
+/// Move `old` to the release period of `now`.
+///
+/// The major component becomes the year (`year_digits` of 2 or 4) and the
+/// minor the month. With `micro`, releases within the same month increment
+/// the patch component; otherwise it is always reset to zero.
+pub fn bump_calver(now: NaiveDate, old: &Version, micro: bool, year_digits: u8) -> Version {
+    let year = if year_digits == 2 {
+        (now.year() % 100) as u64
+    } else {
+        now.year() as u64
+    };
+    let month = u64::from(now.month());
+
+    let same_period = old.major == year && old.minor == month;
+    let patch = if micro && same_period {
+        old.patch + 1
+    } else {
+        0
+    };
+
+    Version::new(year, month, patch)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn increments_micro_within_the_same_month() {
+        let old = Version::parse("2024.3.5").unwrap();
+        assert_eq!(
+            bump_calver(date(2024, 3, 20), &old, true, 4),
+            Version::new(2024, 3, 6)
+        );
+    }
+
+    #[test]
+    fn resets_micro_in_a_new_month() {
+        let old = Version::parse("2024.3.5").unwrap();
+        assert_eq!(
+            bump_calver(date(2024, 4, 1), &old, true, 4),
+            Version::new(2024, 4, 0)
+        );
+        assert_eq!(
+            bump_calver(date(2025, 3, 1), &old, true, 4),
+            Version::new(2025, 3, 0)
+        );
+    }
+
+    #[test]
+    fn without_micro_patch_is_zero() {
+        let old = Version::parse("2024.3.5").unwrap();
+        assert_eq!(
+            bump_calver(date(2024, 3, 20), &old, false, 4),
+            Version::new(2024, 3, 0)
+        );
+    }
+
+    #[test]
+    fn supports_two_digit_years() {
+        let old = Version::parse("24.3.1").unwrap();
+        assert_eq!(
+            bump_calver(date(2024, 3, 9), &old, true, 2),
+            Version::new(24, 3, 2)
+        );
+        assert_eq!(
+            bump_calver(date(2030, 1, 9), &old, true, 2),
+            Version::new(30, 1, 0)
+        );
+    }
+
+    #[test]
+    fn moves_semver_projects_onto_calver() {
+        let old = Version::parse("1.4.2-rc.1").unwrap();
+        assert_eq!(
+            bump_calver(date(2024, 12, 31), &old, true, 4),
+            Version::new(2024, 12, 0)
+        );
+    }
+}
@@ -0,0 +1,250 @@
+//! The `[package]` version of a `Cargo.toml`, and the exact-version
+//! (`=1.2.3`) dependency references between the crates of a Cargo
+//! workspace, kept in step with `--update-deps`.
+//!
+//! Manifests are edited line by line, so comments and layout are kept.
+
+use crate::text::replace_toml_value;
+use regex::Regex;
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// The `[package]` name of a `Cargo.toml`, if it has one.
+pub fn read_package_name(contents: &str) -> Option<String> {
+    let manifest: toml::Table = toml::from_str(contents).ok()?;
+    manifest
+        .get("package")?
+        .get("name")?
+        .as_str()
+        .map(str::to_string)
+}
+
+/// The `[package]` version of a `Cargo.toml`. A version inherited with
+/// `version.workspace = true` is not the package's own, so it is `None`.
+pub fn read_package_version(contents: &str) -> Option<String> {
+    let manifest: toml::Table = toml::from_str(contents).ok()?;
+    manifest
+        .get("package")?
+        .get("version")?
+        .as_str()
+        .map(str::to_string)
+}
+
+/// Replace the `[package]` version, returning the new file contents.
+pub fn update_package_version(contents: &str, new_version: &str) -> Option<String> {
+    replace_toml_value(contents, "package", "version", new_version)
+}
+
+/// The nearest directory from `start` upwards whose `Cargo.toml` has a
+/// `[workspace]` table. `start` itself is returned as given, so a relative
+/// project root keeps paths relative.
+pub fn find_workspace_root(start: &Path) -> Option<PathBuf> {
+    if is_workspace_root(start) {
+        return Some(start.to_path_buf());
+    }
+    let absolute = if start.as_os_str().is_empty() {
+        env::current_dir().ok()?
+    } else {
+        start.canonicalize().ok()?
+    };
+    absolute
+        .ancestors()
+        .skip(1)
+        .find(|dir| is_workspace_root(dir))
+        .map(Path::to_path_buf)
+}
+
+fn is_workspace_root(dir: &Path) -> bool {
+    fs::read_to_string(dir.join("Cargo.toml"))
+        .ok()
+        .and_then(|contents| toml::from_str::<toml::Table>(&contents).ok())
+        .is_some_and(|manifest| manifest.contains_key("workspace"))
+}
+
+/// The `Cargo.toml` of every member listed in the `[workspace]` of
+/// `root/Cargo.toml`, sorted by path.
+///
+/// Members are literal paths or end in `/*`; excluded members are skipped.
+pub fn workspace_member_manifests(root: &Path) -> io::Result<Vec<PathBuf>> {
+    let contents = fs::read_to_string(root.join("Cargo.toml"))?;
+    let manifest: toml::Table =
+        toml::from_str(&contents).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+    let Some(workspace) = manifest.get("workspace") else {
+        return Ok(Vec::new());
+    };
+    let paths = |key: &str| -> Vec<&str> {
+        workspace
+            .get(key)
+            .and_then(|value| value.as_array())
+            .into_iter()
+            .flatten()
+            .filter_map(|value| value.as_str())
+            .collect()
+    };
+    let excluded: Vec<PathBuf> = paths("exclude").iter().map(|p| root.join(p)).collect();
+
+    let mut dirs = Vec::new();
+    for member in paths("members") {
+        match member.strip_suffix("/*") {
+            Some(parent) => {
+                for entry in fs::read_dir(root.join(parent))? {
+                    dirs.push(entry?.path());
+                }
+            }
+            None => dirs.push(root.join(member)),
+        }
+    }
+    let mut manifests: Vec<PathBuf> = dirs
+        .into_iter()
+        .filter(|dir| !excluded.contains(dir))
+        .map(|dir| dir.join("Cargo.toml"))
+        .filter(|path| path.is_file())
+        .collect();
+    manifests.sort();
+    Ok(manifests)
+}
+
+/// Replace every exact `=old` requirement on the dependency `name` with
+/// `=new`, returning the new contents and how many were replaced.
+///
+/// Covers `name = "=old"`, `name = { version = "=old", ... }` and a
+/// `[dependencies.name]` table, in any `*dependencies` table. Ranges such as
+/// `^1.2` are left alone: they do not go stale on a bump.
+pub fn replace_dependency_version(
+    contents: &str,
+    name: &str,
+    old: &str,
+    new: &str,
+) -> (String, usize) {
+    let entry = Regex::new(&format!(r#"^\s*"?{}"?\s*="#, regex::escape(name))).unwrap();
+    let version_key = Regex::new(r"^\s*version\s*=").unwrap();
+    let requirement = Regex::new(&format!(r#""=\s*{}""#, regex::escape(old))).unwrap();
+    let replacement = format!("\"={}\"", new);
+
+    let mut output = String::with_capacity(contents.len());
+    let mut count = 0;
+    // Whether the current table lists dependencies, and whether it is the
+    // `[dependencies.<name>]` table of `name` itself.
+    let (mut in_dependencies, mut in_own_table) = (false, false);
+    for line in contents.split_inclusive('\n') {
+        let trimmed = line.trim();
+        if let Some(header) = trimmed.strip_prefix('[') {
+            let header = header
+                .trim_start_matches('[')
+                .split(']')
+                .next()
+                .unwrap_or("");
+            let header = header.trim();
+            in_dependencies = header.ends_with("dependencies");
+            in_own_table = header.rsplit_once('.').is_some_and(|(table, dep)| {
+                table.ends_with("dependencies") && dep.trim_matches('"') == name
+            });
+        } else if (in_dependencies && entry.is_match(line))
+            || (in_own_table && version_key.is_match(line))
+        {
+            let replaced = requirement.replace_all(line, replacement.as_str());
+            if replaced != line {
+                count += requirement.find_iter(line).count();
+                output.push_str(&replaced);
+                continue;
+            }
+        }
+        output.push_str(line);
+    }
+    (output, count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn replaces_exact_requirements_only() {
+        let manifest = r#"[package]
+name = "demo-cli"
+version = "1.2.3"
+
+[dependencies]
+demo-core = { path = "../core", version = "=1.2.3" }
+demo-core-macros = "=1.2.3"
+serde = "=1.2.3"
+
+[dev-dependencies]
+demo-core = "=1.2.3" # pinned for tests
+
+[build-dependencies.demo-core]
+path = "../core"
+version = "=1.2.3"
+
+[target.'cfg(unix)'.dependencies]
+demo-core = { version = "^1.2.3", path = "../core" }
+"#;
+
+        let (updated, count) = replace_dependency_version(manifest, "demo-core", "1.2.3", "1.3.0");
+
+        assert_eq!(count, 3);
+        assert_eq!(
+            updated,
+            manifest
+                .replace(r#"version = "=1.2.3" }"#, r#"version = "=1.3.0" }"#)
+                .replace(r#"demo-core = "=1.2.3" #"#, r#"demo-core = "=1.3.0" #"#)
+                .replace(
+                    "path = \"../core\"\nversion = \"=1.2.3\"",
+                    "path = \"../core\"\nversion = \"=1.3.0\""
+                )
+        );
+        assert!(updated.contains(r#"demo-core-macros = "=1.2.3""#));
+        assert!(updated.contains(r#"version = "1.2.3""#));
+    }
+
+    #[test]
+    fn reads_and_replaces_the_package_version() {
+        let manifest = "[package]\nname = \"demo\"\nversion = \"1.2.3\"\n\n[dependencies]\nserde = { version = \"1.2.3\" }\n";
+
+        assert_eq!(read_package_version(manifest).as_deref(), Some("1.2.3"));
+        assert_eq!(
+            update_package_version(manifest, "1.3.0").unwrap(),
+            manifest.replacen("1.2.3", "1.3.0", 1)
+        );
+
+        let inherited = "[package]\nname = \"demo\"\nversion.workspace = true\n";
+        assert_eq!(read_package_version(inherited), None);
+        assert_eq!(update_package_version(inherited, "1.3.0"), None);
+    }
+
+    #[test]
+    fn finds_workspace_members() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        fs::write(
+            root.join("Cargo.toml"),
+            "[workspace]\nmembers = [\"cli\", \"crates/*\"]\nexclude = [\"crates/scratch\"]\n",
+        )
+        .unwrap();
+        for member in ["cli", "crates/core", "crates/macros", "crates/scratch"] {
+            fs::create_dir_all(root.join(member)).unwrap();
+            fs::write(root.join(member).join("Cargo.toml"), "[package]\n").unwrap();
+        }
+
+        assert_eq!(
+            workspace_member_manifests(root).unwrap(),
+            [
+                root.join("cli/Cargo.toml"),
+                root.join("crates/core/Cargo.toml"),
+                root.join("crates/macros/Cargo.toml"),
+            ]
+        );
+        assert_eq!(
+            read_package_name("[package]\nname = \"demo-core\"\n").as_deref(),
+            Some("demo-core")
+        );
+        assert_eq!(read_package_name("[workspace]\n"), None);
+        assert_eq!(
+            find_workspace_root(&root.join("crates/core")).unwrap(),
+            root.canonicalize().unwrap()
+        );
+        assert_eq!(find_workspace_root(root).unwrap(), root);
+    }
+}
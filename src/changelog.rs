@@ -0,0 +1,168 @@
+//! Markdown layout of changelog sections.
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// The changelog `--require-changelog-entry` checks, in the project root.
+pub const CHANGELOG_FILE: &str = "CHANGELOG.md";
+
+/// Heading depths used for changelog releases and their subsections.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChangelogConfig {
+    /// `#` count of a release heading such as `## [1.2.3]`.
+    pub header_level: u8,
+    /// `#` count of the feat/fix/chore subsections within a release.
+    pub subheader_level: u8,
+}
+
+impl Default for ChangelogConfig {
+    fn default() -> Self {
+        ChangelogConfig {
+            header_level: 2,
+            subheader_level: 3,
+        }
+    }
+}
+
+impl ChangelogConfig {
+    /// Levels for `header_level` and `subheader_level`, checked with
+    /// [`parse_heading_level`] and [`ChangelogConfig::validate`].
+    pub fn new(header_level: u8, subheader_level: u8) -> Result<Self, String> {
+        let config = ChangelogConfig {
+            header_level,
+            subheader_level,
+        };
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Check that both levels are valid Markdown headings and that
+    /// subsections nest below releases.
+    pub fn validate(&self) -> Result<(), String> {
+        for level in [self.header_level, self.subheader_level] {
+            parse_heading_level(&level.to_string())?;
+        }
+        if self.subheader_level <= self.header_level {
+            return Err(format!(
+                "changelog subheader level {} must be greater than header level {}",
+                self.subheader_level, self.header_level
+            ));
+        }
+        Ok(())
+    }
+
+    /// The heading opening the section for `version`, e.g. `## [1.2.3]`.
+    pub fn release_heading(&self, version: &str) -> String {
+        heading(self.header_level, &format!("[{}]", version))
+    }
+
+    /// Whether `contents` has a release heading for `version`, optionally
+    /// followed by more text such as ` - 2024-05-01`.
+    pub fn has_entry(&self, contents: &str, version: &str) -> bool {
+        let heading = self.release_heading(version);
+        contents.lines().any(|line| {
+            line.strip_prefix(&heading)
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with(char::is_whitespace))
+        })
+    }
+
+    /// Whether the changelog at `path` has a release heading for `version`.
+    /// A missing file has no entries.
+    pub fn file_has_entry(&self, path: &Path, version: &str) -> io::Result<bool> {
+        match fs::read_to_string(path) {
+            Ok(contents) => Ok(self.has_entry(&contents, version)),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(false),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// The heading of a subsection such as `### feat`.
+    pub fn section_heading(&self, name: &str) -> String {
+        heading(self.subheader_level, name)
+    }
+}
+
+/// Whether the changelog at `path` has a `## [version]` entry. A missing
+/// file has no entries.
+pub fn changelog_has_entry(path: &Path, version: &str) -> io::Result<bool> {
+    ChangelogConfig::default().file_has_entry(path, version)
+}
+
+/// Parse a Markdown heading level, 1 to 6.
+pub fn parse_heading_level(s: &str) -> Result<u8, String> {
+    match s.parse::<u8>() {
+        Ok(level @ 1..=6) => Ok(level),
+        _ => Err(format!(
+            "invalid heading level '{}': expected a number from 1 to 6",
+            s
+        )),
+    }
+}
+
+fn heading(level: u8, text: &str) -> String {
+    format!("{} {}", "#".repeat(level.into()), text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_headings_at_each_valid_level() {
+        for header_level in 1..=5u8 {
+            for subheader_level in header_level + 1..=6 {
+                let config = ChangelogConfig::new(header_level, subheader_level).unwrap();
+                let hashes = |level: u8| "#".repeat(level.into());
+
+                assert_eq!(
+                    config.release_heading("1.2.3"),
+                    format!("{} [1.2.3]", hashes(header_level))
+                );
+                assert_eq!(
+                    config.section_heading("fix"),
+                    format!("{} fix", hashes(subheader_level))
+                );
+            }
+        }
+        assert_eq!(
+            ChangelogConfig::default().release_heading("0.1.0"),
+            "## [0.1.0]"
+        );
+    }
+
+    #[test]
+    fn subheaders_must_nest_below_headers() {
+        assert_eq!(
+            ChangelogConfig::new(3, 3).unwrap_err(),
+            "changelog subheader level 3 must be greater than header level 3"
+        );
+        assert!(ChangelogConfig::new(4, 2).is_err());
+        assert!(ChangelogConfig::new(6, 7).is_err());
+    }
+
+    #[test]
+    fn finds_release_entries() {
+        let changelog = "# Changelog\n\n## [Unreleased]\n\n## [1.2.3] - 2026-01-05\n### fix\n- Crash on empty input\n\n## [1.2.0]\n";
+        let config = ChangelogConfig::default();
+
+        assert!(config.has_entry(changelog, "1.2.3"));
+        assert!(config.has_entry(changelog, "1.2.0"));
+        assert!(!config.has_entry(changelog, "1.2"));
+        assert!(!config.has_entry(changelog, "1.3.0"));
+        assert!(
+            !ChangelogConfig::new(3, 4)
+                .unwrap()
+                .has_entry(changelog, "1.2.3")
+        );
+    }
+
+    #[test]
+    fn parses_heading_levels() {
+        assert_eq!(parse_heading_level("1"), Ok(1));
+        assert_eq!(parse_heading_level("6"), Ok(6));
+        for invalid in ["0", "7", "-1", "h2"] {
+            assert!(parse_heading_level(invalid).is_err(), "{invalid}");
+        }
+    }
+}
@@ -0,0 +1,374 @@
+//! Guards evaluated after the new version is known but before anything is written.
+
+use crate::error::BumpError;
+use crate::glob::glob_match;
+use crate::sources::VersionSource;
+use regex::Regex;
+use semver::Version;
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+
+/// The pattern recommended by semver.org for semver 2.0.0.
+static STRICT_SEMVER: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(concat!(
+        r"^(0|[1-9]\d*)\.(0|[1-9]\d*)\.(0|[1-9]\d*)",
+        r"(?:-((?:0|[1-9]\d*|\d*[a-zA-Z-][0-9a-zA-Z-]*)(?:\.(?:0|[1-9]\d*|\d*[a-zA-Z-][0-9a-zA-Z-]*))*))?",
+        r"(?:\+([0-9a-zA-Z-]+(?:\.[0-9a-zA-Z-]+)*))?$",
+    ))
+    .unwrap()
+});
+
+/// Refuse to write more than `max` files; `None` means unlimited.
+pub fn validate_file_count(paths: &[PathBuf], max: Option<usize>) -> Result<(), BumpError> {
+    match max {
+        Some(max) if paths.len() > max => Err(BumpError::TooManyFiles {
+            count: paths.len(),
+            max,
+        }),
+        _ => Ok(()),
+    }
+}
+
+/// Require `version`, read from `path`, to match semver 2.0.0 exactly.
+pub fn validate_strict_semver(version: &str, path: &Path) -> Result<(), BumpError> {
+    if STRICT_SEMVER.is_match(version) {
+        Ok(())
+    } else {
+        Err(BumpError::NotStrictSemver {
+            path: path.to_path_buf(),
+            version: version.to_string(),
+        })
+    }
+}
+
+/// Require every source to hold a valid version equal to the first one's.
+/// With `strict`, versions must also pass [`validate_strict_semver`].
+pub fn validate_sources_agree(sources: &[VersionSource], strict: bool) -> Result<(), BumpError> {
+    for source in sources {
+        if strict {
+            validate_strict_semver(&source.version, &source.path)?;
+        } else if Version::parse(&source.version).is_err() {
+            return Err(BumpError::InvalidVersion {
+                path: source.path.clone(),
+                version: source.version.clone(),
+            });
+        }
+    }
+
+    let Some(expected) = sources.first() else {
+        return Ok(());
+    };
+    match sources
+        .iter()
+        .find(|source| source.version != expected.version)
+    {
+        Some(source) => Err(BumpError::VersionMismatch {
+            path: source.path.clone(),
+            version: source.version.clone(),
+            expected_path: expected.path.clone(),
+            expected: expected.version.clone(),
+        }),
+        None => Ok(()),
+    }
+}
+
+/// How pre-release to pre-release bumps are treated.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PrereleasePolicy {
+    /// Reject bumping a pre-release to another pre-release.
+    pub fail_on_prerelease: bool,
+    /// Allow it anyway, overriding `fail_on_prerelease`.
+    pub force: bool,
+}
+
+/// Enforce `policy` for a bump from `current` to `new`.
+pub fn validate_prerelease_policy(
+    current: &Version,
+    new: &Version,
+    policy: &PrereleasePolicy,
+) -> Result<(), BumpError> {
+    let blocked = policy.fail_on_prerelease && !policy.force;
+    if blocked && !current.pre.is_empty() && !new.pre.is_empty() {
+        return Err(BumpError::PrereleaseToPrerelease {
+            current: current.to_string(),
+            new: new.to_string(),
+        });
+    }
+    Ok(())
+}
+
+/// Whether going from `current` to `new` raises the major version.
+pub fn is_major_bump(current: &Version, new: &Version) -> bool {
+    new.major > current.major
+}
+
+/// Refuse a major bump from `current` to `new` unless `allow` is set.
+pub fn validate_major_bump(current: &Version, new: &Version, allow: bool) -> Result<(), BumpError> {
+    if is_major_bump(current, new) && !allow {
+        return Err(BumpError::MajorBumpNotAllowed {
+            current: current.to_string(),
+            new: new.to_string(),
+        });
+    }
+    Ok(())
+}
+
+/// Lowest allowed value of each release number, from `--min-major`,
+/// `--min-minor` and `--min-patch`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FloorConfig {
+    pub major: Option<u64>,
+    pub minor: Option<u64>,
+    pub patch: Option<u64>,
+}
+
+/// Check each release number of `v` against its floor in `config`,
+/// reporting the first one below it.
+pub fn validate_component_floors(v: &Version, config: &FloorConfig) -> Result<(), BumpError> {
+    let components = [
+        ("major", v.major, config.major),
+        ("minor", v.minor, config.minor),
+        ("patch", v.patch, config.patch),
+    ];
+    for (component, value, floor) in components {
+        if let Some(floor) = floor.filter(|&floor| value < floor) {
+            return Err(BumpError::BelowComponentFloor {
+                version: v.to_string(),
+                component,
+                floor,
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Require a Conventional Commits scope: ASCII letters, digits and hyphens.
+pub fn validate_commit_scope(scope: &str) -> Result<(), String> {
+    if !scope.is_empty() && scope.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+        Ok(())
+    } else {
+        Err(format!(
+            "invalid commit scope '{}': use letters, digits and hyphens only",
+            scope
+        ))
+    }
+}
+
+/// Require `current` to match the `--branch` glob, where `*` matches any
+/// run of characters and `?` a single one.
+pub fn validate_branch(current: &str, pattern: &str) -> Result<(), BumpError> {
+    if glob_match(pattern, current) {
+        Ok(())
+    } else {
+        Err(BumpError::WrongBranch {
+            current: current.to_string(),
+            expected: pattern.to_string(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn enforces_file_limit() {
+        let paths: Vec<PathBuf> = ["a", "b", "c"].iter().map(PathBuf::from).collect();
+
+        assert!(validate_file_count(&paths, None).is_ok());
+        assert!(validate_file_count(&paths, Some(3)).is_ok());
+        assert!(matches!(
+            validate_file_count(&paths, Some(2)),
+            Err(BumpError::TooManyFiles { count: 3, max: 2 })
+        ));
+    }
+
+    #[test]
+    fn strict_semver_accepts_full_versions() {
+        for version in [
+            "0.0.0",
+            "1.2.3",
+            "10.20.30",
+            "1.0.0-alpha.1",
+            "1.0.0-0.3.7",
+            "1.0.0+20130313144700",
+            "1.0.0-beta+exp.sha.5114f85",
+            "1.0.0-x-y-z.--",
+        ] {
+            assert!(
+                validate_strict_semver(version, Path::new("VERSION")).is_ok(),
+                "{version}"
+            );
+        }
+    }
+
+    #[test]
+    fn strict_semver_rejects_loose_versions() {
+        for version in [
+            "1.2",
+            "1",
+            "01.2.3",
+            "1.02.3",
+            "1.2.03",
+            "v1.2.3",
+            "1.2.3-01",
+            "1.2.3-",
+            "1.2.3+",
+            "1.2.3-a..b",
+            " 1.2.3",
+        ] {
+            assert!(
+                matches!(
+                    validate_strict_semver(version, Path::new("VERSION")),
+                    Err(BumpError::NotStrictSemver { .. })
+                ),
+                "{version}"
+            );
+        }
+    }
+
+    #[test]
+    fn major_bumps() {
+        let v = |s: &str| Version::parse(s).unwrap();
+
+        assert!(is_major_bump(&v("1.4.2"), &v("2.0.0")));
+        assert!(is_major_bump(&v("0.9.0"), &v("1.0.0-rc.1")));
+        assert!(!is_major_bump(&v("1.4.2"), &v("1.5.0")));
+        assert!(!is_major_bump(&v("2.0.0-rc.1"), &v("2.0.0")));
+        assert!(validate_major_bump(&v("1.0.0"), &v("2.0.0"), true).is_ok());
+        assert!(matches!(
+            validate_major_bump(&v("1.0.0"), &v("2.0.0"), false),
+            Err(BumpError::MajorBumpNotAllowed { .. })
+        ));
+    }
+
+    #[test]
+    fn component_floors() {
+        let v = Version::new(2, 3, 4);
+        let below = |config: FloorConfig| match validate_component_floors(&v, &config) {
+            Err(BumpError::BelowComponentFloor { component, .. }) => Some(component),
+            _ => None,
+        };
+
+        assert!(validate_component_floors(&v, &FloorConfig::default()).is_ok());
+        let at_floors = FloorConfig {
+            major: Some(2),
+            minor: Some(3),
+            patch: Some(4),
+        };
+        assert!(validate_component_floors(&v, &at_floors).is_ok());
+        for (config, component) in [
+            (
+                FloorConfig {
+                    major: Some(3),
+                    ..at_floors
+                },
+                "major",
+            ),
+            (
+                FloorConfig {
+                    minor: Some(4),
+                    ..at_floors
+                },
+                "minor",
+            ),
+            (
+                FloorConfig {
+                    patch: Some(5),
+                    ..at_floors
+                },
+                "patch",
+            ),
+        ] {
+            assert_eq!(below(config), Some(component));
+        }
+        assert_eq!(
+            validate_component_floors(
+                &v,
+                &FloorConfig {
+                    minor: Some(9),
+                    ..FloorConfig::default()
+                }
+            )
+            .unwrap_err()
+            .to_string(),
+            "New version 2.3.4 has a minor below the floor of 9 set by --min-minor"
+        );
+    }
+
+    #[test]
+    fn commit_scopes() {
+        assert!(validate_commit_scope("release").is_ok());
+        assert!(validate_commit_scope("api-v2").is_ok());
+        assert!(validate_commit_scope("").is_err());
+        assert!(validate_commit_scope("two words").is_err());
+        assert!(validate_commit_scope("deps)").is_err());
+    }
+
+    #[test]
+    fn branch_globs() {
+        assert!(validate_branch("main", "main").is_ok());
+        assert!(validate_branch("release/1.x", "release/*").is_ok());
+        assert!(validate_branch("release-2", "release-?").is_ok());
+        assert!(validate_branch("main", "release/*").is_err());
+        assert!(validate_branch("mainline", "main").is_err());
+        assert!(validate_branch("feature.x", "feature.?").is_ok());
+        assert!(validate_branch("featureXx", "feature.x").is_err());
+    }
+
+    #[test]
+    fn sources_must_agree() {
+        let source = VersionSource::new;
+
+        assert!(validate_sources_agree(&[], false).is_ok());
+        assert!(
+            validate_sources_agree(
+                &[source("package.json", "1.2.3"), source("VERSION", "1.2.3")],
+                false
+            )
+            .is_ok()
+        );
+        assert!(matches!(
+            validate_sources_agree(
+                &[source("package.json", "1.2.3"), source("VERSION", "1.2.2")],
+                false
+            ),
+            Err(BumpError::VersionMismatch { .. })
+        ));
+        assert!(matches!(
+            validate_sources_agree(&[source("VERSION", "1.2")], false),
+            Err(BumpError::InvalidVersion { .. })
+        ));
+        assert!(matches!(
+            validate_sources_agree(&[source("VERSION", "1.2.3+01")], true),
+            Ok(())
+        ));
+    }
+
+    #[test]
+    fn prerelease_policy_combinations() {
+        let v = |s: &str| Version::parse(s).unwrap();
+        let policy = |fail_on_prerelease, force| PrereleasePolicy {
+            fail_on_prerelease,
+            force,
+        };
+
+        for (current, new, fail, force, ok) in [
+            ("1.0.0-alpha.1", "1.0.0-alpha.2", true, false, false),
+            ("1.0.0-alpha.1", "1.1.0-beta.1", true, false, false),
+            ("1.0.0-alpha.1", "1.0.0-alpha.2", true, true, true),
+            ("1.0.0-alpha.1", "1.0.0-alpha.2", false, false, true),
+            ("1.0.0-alpha.1", "1.0.0-alpha.2", false, true, true),
+            ("1.0.0-alpha.1", "1.0.1", true, false, true),
+            ("1.0.0", "1.0.1-alpha.1", true, false, true),
+            ("1.0.0", "1.0.1", true, false, true),
+        ] {
+            let result = validate_prerelease_policy(&v(current), &v(new), &policy(fail, force));
+            assert_eq!(
+                result.is_ok(),
+                ok,
+                "{current} -> {new} fail={fail} force={force}"
+            );
+        }
+    }
+}
@@ -0,0 +1,232 @@
+//! Settings read from config files rather than the command line.
+//!
+//! A global file (`$XDG_CONFIG_HOME/semver/config.toml`, falling back to
+//! `~/.config/semver/config.toml`) is merged with the project's
+//! `.semverrc.toml`; project settings win.
+//!
+//! ```toml
+//! default_bump = "minor"
+//! ignore_files = ["packages/legacy/*"]
+//!
+//! [[file_hooks]]
+//! path = "package.json"
+//! post_update = "npm install --package-lock-only"
+//! ```
+
+use crate::bump::BumpType;
+use crate::error::BumpError;
+use serde::{Deserialize, Serialize};
+use std::env;
+use std::fmt;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+/// Project-level config file, looked up in the working directory.
+pub const PROJECT_CONFIG: &str = ".semverrc.toml";
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Config {
+    /// Bump applied when `--bump` is not given, instead of prompting.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_bump: Option<String>,
+    /// Globs of files never to update, added to `--ignore-files`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ignore_files: Option<Vec<String>>,
+    /// Shell commands run around the update of individual files.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file_hooks: Option<Vec<FileHookConfig>>,
+}
+
+/// Renders the config as TOML, in the layout the config files use.
+impl fmt::Display for Config {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let toml = toml::to_string(self).map_err(|_| fmt::Error)?;
+        f.write_str(&toml)
+    }
+}
+
+/// Commands run in the project directory before and after `path` is
+/// written, e.g. to regenerate a lockfile.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct FileHookConfig {
+    pub path: PathBuf,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pre_update: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub post_update: Option<String>,
+}
+
+impl FileHookConfig {
+    /// Whether the hook is for `path`, ignoring `./` components.
+    pub fn applies_to(&self, path: &Path) -> bool {
+        let normal = |p: &Path| {
+            p.components()
+                .filter(|c| *c != Component::CurDir)
+                .collect::<PathBuf>()
+        };
+        normal(&self.path) == normal(path)
+    }
+}
+
+impl Config {
+    /// Layer `over` on top of `self`, keeping `self`'s values where `over`
+    /// leaves them unset.
+    pub fn merge(self, over: Config) -> Config {
+        Config {
+            default_bump: over.default_bump.or(self.default_bump),
+            ignore_files: over.ignore_files.or(self.ignore_files),
+            file_hooks: over.file_hooks.or(self.file_hooks),
+        }
+    }
+}
+
+/// Where the global config is looked for when `--global-config` is not given.
+pub fn global_config_path() -> Option<PathBuf> {
+    let base = env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+    Some(base.join("semver").join("config.toml"))
+}
+
+/// Parse the config file at `path`.
+pub fn load_config(path: &Path) -> Result<Config, BumpError> {
+    let invalid = |message: String| BumpError::InvalidConfig {
+        path: path.to_path_buf(),
+        message,
+    };
+
+    let contents = fs::read_to_string(path).map_err(|err| invalid(err.to_string()))?;
+    let config: Config =
+        toml::from_str(&contents).map_err(|err| invalid(err.message().to_string()))?;
+
+    if let Some(bump) = &config.default_bump {
+        if bump.parse::<BumpType>().is_err() {
+            return Err(invalid(format!("invalid default_bump '{}'", bump)));
+        }
+    }
+    Ok(config)
+}
+
+/// Merge the global config with `.semverrc.toml` in `project_dir`.
+///
+/// An explicit `global` path must exist; the discovered global file and the
+/// project file are optional.
+pub fn resolve_config(global: Option<&Path>, project_dir: &Path) -> Result<Config, BumpError> {
+    let global = match global {
+        Some(path) => load_config(path)?,
+        None => match global_config_path().filter(|path| path.is_file()) {
+            Some(path) => load_config(&path)?,
+            None => Config::default(),
+        },
+    };
+
+    let project = project_dir.join(PROJECT_CONFIG);
+    let project = if project.is_file() {
+        load_config(&project)?
+    } else {
+        Config::default()
+    };
+
+    Ok(global.merge(project))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn project_settings_override_global_ones() {
+        let global = Config {
+            default_bump: Some("minor".to_string()),
+            ignore_files: Some(vec!["legacy/*".to_string()]),
+            file_hooks: None,
+        };
+
+        assert_eq!(global.clone().merge(Config::default()), global);
+        assert_eq!(
+            global
+                .clone()
+                .merge(Config {
+                    default_bump: Some("major".to_string()),
+                    ..Config::default()
+                })
+                .default_bump
+                .as_deref(),
+            Some("major")
+        );
+        assert_eq!(
+            global
+                .merge(Config {
+                    ignore_files: Some(vec![]),
+                    ..Config::default()
+                })
+                .ignore_files,
+            Some(vec![])
+        );
+    }
+
+    #[test]
+    fn explicit_global_config_must_exist() {
+        let dir = tempfile::tempdir().unwrap();
+
+        assert!(matches!(
+            resolve_config(Some(&dir.path().join("missing.toml")), dir.path()),
+            Err(BumpError::InvalidConfig { .. })
+        ));
+    }
+
+    #[test]
+    fn reads_file_hooks() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(".semverrc.toml");
+        fs::write(
+            &path,
+            "[[file_hooks]]\npath = \"package.json\"\npost_update = \"npm install\"\n",
+        )
+        .unwrap();
+
+        let hooks = load_config(&path).unwrap().file_hooks.unwrap();
+        assert_eq!(
+            hooks,
+            [FileHookConfig {
+                path: PathBuf::from("package.json"),
+                pre_update: None,
+                post_update: Some("npm install".to_string()),
+            }]
+        );
+        assert!(hooks[0].applies_to(Path::new("./package.json")));
+        assert!(!hooks[0].applies_to(Path::new("app/package.json")));
+    }
+
+    #[test]
+    fn displays_as_toml_that_loads_back() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        let config = Config {
+            default_bump: Some("minor".to_string()),
+            ignore_files: Some(vec!["legacy/*".to_string()]),
+            file_hooks: Some(vec![FileHookConfig {
+                path: PathBuf::from("package.json"),
+                pre_update: None,
+                post_update: Some("npm install".to_string()),
+            }]),
+        };
+
+        let rendered = config.to_string();
+        assert!(rendered.starts_with("default_bump = \"minor\"\n"));
+        assert!(rendered.contains("[[file_hooks]]\n"));
+        fs::write(&path, &rendered).unwrap();
+        assert_eq!(load_config(&path).unwrap(), config);
+        assert_eq!(Config::default().to_string(), "");
+    }
+
+    #[test]
+    fn rejects_unknown_bump() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        fs::write(&path, "default_bump = \"huge\"\n").unwrap();
+
+        assert!(load_config(&path).is_err());
+    }
+}
@@ -0,0 +1,115 @@
+//! Editing the package version of .NET projects in `*.csproj` and
+//! `Directory.Build.props` files.
+//!
+//! A `<Version>` element wins. Projects that split the version into
+//! `<VersionPrefix>` and an optional `<VersionSuffix>` are handled as well;
+//! the suffix carries the pre-release part. Only the element text is
+//! replaced, so the surrounding XML is kept byte for byte.
+
+use regex::Regex;
+use std::ops::Range;
+use std::sync::LazyLock;
+
+static VERSION: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"<Version>([^<]+)</Version>").unwrap());
+static VERSION_PREFIX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"<VersionPrefix>([^<]+)</VersionPrefix>").unwrap());
+static VERSION_SUFFIX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"<VersionSuffix>([^<]*)</VersionSuffix>").unwrap());
+
+/// Read the project version, joining `VersionPrefix` and `VersionSuffix`
+/// when there is no `<Version>`.
+pub fn read_csproj_version(contents: &str) -> Option<String> {
+    if let Some(span) = element_span(&VERSION, contents) {
+        return Some(contents[span].trim().to_string());
+    }
+
+    let prefix = contents[element_span(&VERSION_PREFIX, contents)?].trim();
+    match element_span(&VERSION_SUFFIX, contents).map(|span| contents[span].trim()) {
+        Some(suffix) if !suffix.is_empty() => Some(format!("{}-{}", prefix, suffix)),
+        _ => Some(prefix.to_string()),
+    }
+}
+
+/// Replace the project version, returning the new file contents.
+///
+/// With `VersionPrefix`, a pre-release needs a `<VersionSuffix>` element to
+/// go into; without one the file is left alone and `None` is returned.
+pub fn update_csproj_version(contents: &str, new_version: &str) -> Option<String> {
+    if let Some(span) = element_span(&VERSION, contents) {
+        return Some(splice(contents, span, new_version));
+    }
+
+    let (release, pre) = match new_version.split_once('-') {
+        Some((release, pre)) => (release, pre),
+        None => (new_version, ""),
+    };
+    let prefix = element_span(&VERSION_PREFIX, contents)?;
+    match element_span(&VERSION_SUFFIX, contents) {
+        // Replace the later element first so the earlier span stays valid.
+        Some(suffix) if suffix.start > prefix.start => {
+            Some(splice(&splice(contents, suffix, pre), prefix, release))
+        }
+        Some(suffix) => Some(splice(&splice(contents, prefix, release), suffix, pre)),
+        None if pre.is_empty() => Some(splice(contents, prefix, release)),
+        None => None,
+    }
+}
+
+fn element_span(element: &Regex, contents: &str) -> Option<Range<usize>> {
+    element
+        .captures(contents)
+        .and_then(|caps| caps.get(1))
+        .map(|m| m.range())
+}
+
+fn splice(contents: &str, span: Range<usize>, text: &str) -> String {
+    format!(
+        "{}{}{}",
+        &contents[..span.start],
+        text,
+        &contents[span.end..]
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn replaces_version_element() {
+        let csproj = "<Project Sdk=\"Microsoft.NET.Sdk\">\n  <PropertyGroup>\n    <Version>1.2.3</Version>\n  </PropertyGroup>\n</Project>\n";
+
+        assert_eq!(read_csproj_version(csproj).as_deref(), Some("1.2.3"));
+        assert_eq!(
+            update_csproj_version(csproj, "1.3.0").unwrap(),
+            csproj.replace("1.2.3", "1.3.0")
+        );
+    }
+
+    #[test]
+    fn splits_prefix_and_suffix() {
+        let props = "<Project>\n  <PropertyGroup>\n    <VersionPrefix>2.0.0</VersionPrefix>\n    <VersionSuffix>beta.1</VersionSuffix>\n  </PropertyGroup>\n</Project>\n";
+
+        assert_eq!(read_csproj_version(props).as_deref(), Some("2.0.0-beta.1"));
+        assert_eq!(
+            update_csproj_version(props, "2.0.0-beta.2").unwrap(),
+            props.replace("beta.1", "beta.2")
+        );
+        assert_eq!(
+            update_csproj_version(props, "2.1.0").unwrap(),
+            props
+                .replace("2.0.0", "2.1.0")
+                .replace("<VersionSuffix>beta.1<", "<VersionSuffix><")
+        );
+    }
+
+    #[test]
+    fn needs_suffix_element_for_prerelease() {
+        let props = "<Project><PropertyGroup><VersionPrefix>2.0.0</VersionPrefix></PropertyGroup></Project>";
+
+        assert_eq!(read_csproj_version(props).as_deref(), Some("2.0.0"));
+        assert_eq!(update_csproj_version(props, "2.1.0-rc.1"), None);
+        assert_eq!(read_csproj_version("<Project />"), None);
+    }
+}
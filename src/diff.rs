@@ -0,0 +1,110 @@
+use semver::Version;
+use std::cmp::Ordering;
+use std::fmt;
+
+/// Kind of change between two versions, as reported by `semver diff`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VersionDiff {
+    Major,
+    Minor,
+    Patch,
+    Prerelease,
+    None,
+    Downgrade,
+}
+
+impl fmt::Display for VersionDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            VersionDiff::Major => "major",
+            VersionDiff::Minor => "minor",
+            VersionDiff::Patch => "patch",
+            VersionDiff::Prerelease => "prerelease",
+            VersionDiff::None => "none",
+            VersionDiff::Downgrade => "downgrade",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Classify the change from `old` to `new`.
+///
+/// Ordering follows semver precedence, so build metadata is ignored and
+/// a pre-release sorts before its release (`1.0.0-rc.1` < `1.0.0`).
+pub fn classify_version_diff(old: &Version, new: &Version) -> VersionDiff {
+    match old.cmp_precedence(new) {
+        Ordering::Greater => return VersionDiff::Downgrade,
+        Ordering::Equal => return VersionDiff::None,
+        Ordering::Less => {}
+    }
+
+    if old.major != new.major {
+        VersionDiff::Major
+    } else if old.minor != new.minor {
+        VersionDiff::Minor
+    } else if old.patch != new.patch {
+        VersionDiff::Patch
+    } else {
+        VersionDiff::Prerelease
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn diff(old: &str, new: &str) -> VersionDiff {
+        classify_version_diff(&Version::parse(old).unwrap(), &Version::parse(new).unwrap())
+    }
+
+    #[test]
+    fn classifies_release_components() {
+        assert_eq!(diff("1.2.3", "2.0.0"), VersionDiff::Major);
+        assert_eq!(diff("1.2.3", "1.3.0"), VersionDiff::Minor);
+        assert_eq!(diff("1.2.3", "1.2.4"), VersionDiff::Patch);
+        assert_eq!(diff("1.2.3", "1.2.3"), VersionDiff::None);
+        assert_eq!(diff("1.2.3", "1.2.2"), VersionDiff::Downgrade);
+        assert_eq!(diff("2.0.0", "1.9.9"), VersionDiff::Downgrade);
+    }
+
+    #[test]
+    fn highest_changed_component_wins() {
+        assert_eq!(diff("1.2.3", "2.3.4"), VersionDiff::Major);
+        assert_eq!(diff("1.2.3", "1.3.4"), VersionDiff::Minor);
+    }
+
+    #[test]
+    fn classifies_prerelease_changes() {
+        assert_eq!(
+            diff("1.0.0-alpha", "1.0.0-alpha.1"),
+            VersionDiff::Prerelease
+        );
+        assert_eq!(diff("1.0.0-alpha.1", "1.0.0-beta"), VersionDiff::Prerelease);
+        assert_eq!(diff("1.0.0-rc.1", "1.0.0"), VersionDiff::Prerelease);
+        assert_eq!(
+            diff("1.0.0-alpha.2", "1.0.0-alpha.10"),
+            VersionDiff::Prerelease
+        );
+        assert_eq!(diff("1.0.0", "1.0.0-rc.1"), VersionDiff::Downgrade);
+        assert_eq!(diff("1.0.0-beta", "1.0.0-alpha"), VersionDiff::Downgrade);
+        assert_eq!(
+            diff("1.0.0-alpha.beta", "1.0.0-alpha.1"),
+            VersionDiff::Downgrade
+        );
+    }
+
+    #[test]
+    fn prerelease_of_next_release_counts_as_component_bump() {
+        assert_eq!(diff("1.2.3", "2.0.0-rc.1"), VersionDiff::Major);
+        assert_eq!(diff("1.2.3", "1.3.0-alpha"), VersionDiff::Minor);
+        assert_eq!(diff("1.2.3-rc.1", "1.2.4"), VersionDiff::Patch);
+        assert_eq!(diff("1.2.3", "1.2.4-0"), VersionDiff::Patch);
+    }
+
+    #[test]
+    fn ignores_build_metadata() {
+        assert_eq!(diff("1.2.3+build.1", "1.2.3+build.2"), VersionDiff::None);
+        assert_eq!(diff("1.2.3+build.9", "1.2.4+build.1"), VersionDiff::Patch);
+        assert_eq!(diff("1.0.0-rc.1+a", "1.0.0-rc.1+b"), VersionDiff::None);
+    }
+}
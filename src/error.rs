@@ -0,0 +1,301 @@
+use std::error::Error;
+use std::fmt;
+use std::io;
+use std::path::PathBuf;
+
+/// Errors that abort a bump, each mapped to a distinct process exit code.
+#[derive(Debug)]
+pub enum BumpError {
+    /// More files would be written than `--max-files` allows.
+    TooManyFiles { count: usize, max: usize },
+    /// A version file exists but could not be parsed.
+    ParseError { path: PathBuf, message: String },
+    /// A configuration file is missing, unreadable or malformed.
+    InvalidConfig { path: PathBuf, message: String },
+    /// Creating a GitHub Release failed.
+    GithubRelease(String),
+    /// Writing an updated file failed.
+    Write { path: PathBuf, source: io::Error },
+    /// A version source does not hold a valid semantic version.
+    InvalidVersion { path: PathBuf, version: String },
+    /// Two version sources disagree.
+    VersionMismatch {
+        path: PathBuf,
+        version: String,
+        expected_path: PathBuf,
+        expected: String,
+    },
+    /// `--fail-on-prerelease` refused to move one pre-release to another.
+    PrereleaseToPrerelease { current: String, new: String },
+    /// `--clean-pre-release` found no pre-release or build metadata to strip.
+    NotPrerelease { version: String },
+    /// A git command failed.
+    Git(String),
+    /// `--require-git` was given outside a git repository.
+    NotAGitRepository(PathBuf),
+    /// The current branch does not match `--branch`.
+    WrongBranch { current: String, expected: String },
+    /// `--strict-semver` found a version that is not exactly semver 2.0.0.
+    NotStrictSemver { path: PathBuf, version: String },
+    /// Signing or verifying a file with GPG failed.
+    Signing(String),
+    /// `--require-tag` found no tag for the current version.
+    UntaggedVersion { version: String, pattern: String },
+    /// A version file lacks a field that was asked to be bumped.
+    MissingField { path: PathBuf, field: String },
+    /// `--squash-commits` would reach past the branch's merge base.
+    SquashPastMergeBase { requested: usize, available: usize },
+    /// A `file_hooks` command failed.
+    HookFailed {
+        path: PathBuf,
+        command: String,
+        message: String,
+    },
+    /// `--warn-major-bump` in `--ci` mode without `--allow-major-bump`.
+    MajorBumpNotAllowed { current: String, new: String },
+    /// A component of the new version is below its `--min-major`,
+    /// `--min-minor` or `--min-patch` floor.
+    BelowComponentFloor {
+        version: String,
+        component: &'static str,
+        floor: u64,
+    },
+    /// A `--template-file` could not be read, compiled or rendered.
+    Template { path: PathBuf, message: String },
+    /// `--tag-verify` could not verify the GPG signature of an existing tag.
+    TagVerification { tag: String, message: String },
+    /// `--require-changelog-entry` found no entry for the new version.
+    MissingChangelogEntry { path: PathBuf, heading: String },
+    /// The `--audit-trail` file could not be read or its hash chain is
+    /// broken; `line` is 1-based.
+    AuditTrail {
+        path: PathBuf,
+        line: Option<usize>,
+        message: String,
+    },
+    /// A `--batch-file` could not be read or has an invalid step; `line` is
+    /// 1-based.
+    InvalidBatchFile {
+        path: PathBuf,
+        line: Option<usize>,
+        message: String,
+    },
+}
+
+impl BumpError {
+    /// Exit code the CLI uses for this error.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            BumpError::TooManyFiles { .. } => 15,
+            BumpError::ParseError { .. } => 1,
+            BumpError::InvalidConfig { .. } => 1,
+            BumpError::GithubRelease(_) => 1,
+            BumpError::NotStrictSemver { .. } => 3,
+            BumpError::Write { .. } => 1,
+            BumpError::InvalidVersion { .. } => 1,
+            BumpError::VersionMismatch { .. } => 1,
+            BumpError::PrereleaseToPrerelease { .. } => 18,
+            BumpError::NotPrerelease { .. } => 4,
+            BumpError::Git(_) => 1,
+            BumpError::NotAGitRepository(_) => 17,
+            BumpError::WrongBranch { .. } => 24,
+            BumpError::Signing(_) => 1,
+            BumpError::UntaggedVersion { .. } => 19,
+            BumpError::MissingField { .. } => 1,
+            BumpError::SquashPastMergeBase { .. } => 1,
+            BumpError::HookFailed { .. } => 1,
+            BumpError::MajorBumpNotAllowed { .. } => 20,
+            BumpError::BelowComponentFloor { .. } => 26,
+            BumpError::Template { .. } => 1,
+            BumpError::InvalidBatchFile { .. } => 1,
+            BumpError::TagVerification { .. } => 22,
+            BumpError::MissingChangelogEntry { .. } => 23,
+            BumpError::AuditTrail { .. } => 25,
+        }
+    }
+}
+
+impl fmt::Display for BumpError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BumpError::TooManyFiles { count, max } => write!(
+                f,
+                "{} files would be updated, more than the limit of {}",
+                count, max
+            ),
+            BumpError::ParseError { path, message } => {
+                write!(f, "Invalid JSON in {}: {}", path.display(), message)
+            }
+            BumpError::InvalidConfig { path, message } => {
+                write!(f, "Invalid config {}: {}", path.display(), message)
+            }
+            BumpError::GithubRelease(msg) => write!(f, "Could not create GitHub release: {}", msg),
+            BumpError::Write { path, source } => {
+                write!(f, "Failed to write {}: {}", path.display(), source)
+            }
+            BumpError::InvalidVersion { path, version } => write!(
+                f,
+                "Invalid semantic version in {}: {}",
+                path.display(),
+                version
+            ),
+            BumpError::VersionMismatch {
+                path,
+                version,
+                expected_path,
+                expected,
+            } => write!(
+                f,
+                "{} has version {} but {} has {}",
+                path.display(),
+                version,
+                expected_path.display(),
+                expected
+            ),
+            BumpError::PrereleaseToPrerelease { current, new } => write!(
+                f,
+                "Refusing to bump pre-release {} to another pre-release {}; use --force-prerelease to override",
+                current, new
+            ),
+            BumpError::NotPrerelease { version } => write!(
+                f,
+                "Version {} has no pre-release or build metadata to clean",
+                version
+            ),
+            BumpError::Git(msg) => write!(f, "{}", msg),
+            BumpError::NotAGitRepository(dir) => {
+                write!(f, "{} is not inside a git repository", dir.display())
+            }
+            BumpError::WrongBranch { current, expected } => write!(
+                f,
+                "Refusing to bump on branch {}; expected {}",
+                current, expected
+            ),
+            BumpError::NotStrictSemver { path, version } => write!(
+                f,
+                "Version {:?} in {} is not a strict semver 2.0.0 version",
+                version,
+                path.display()
+            ),
+            BumpError::Signing(msg) => write!(f, "Signing failed: {}", msg),
+            BumpError::UntaggedVersion { version, pattern } => write!(
+                f,
+                "Current version {} is not tagged: no git tag matches {}; tag it first, e.g. with --git-tag-annotated",
+                version, pattern
+            ),
+            BumpError::MissingField { path, field } => {
+                write!(f, "{} has no {} field", path.display(), field)
+            }
+            BumpError::SquashPastMergeBase {
+                requested,
+                available,
+            } => write!(
+                f,
+                "Cannot squash {} commits: only {} are above the merge base",
+                requested, available
+            ),
+            BumpError::HookFailed {
+                path,
+                command,
+                message,
+            } => write!(
+                f,
+                "Hook `{}` for {} failed: {}",
+                command,
+                path.display(),
+                message
+            ),
+            BumpError::MajorBumpNotAllowed { current, new } => write!(
+                f,
+                "Refusing major bump from {} to {} in CI; pass --allow-major-bump to override",
+                current, new
+            ),
+            BumpError::BelowComponentFloor {
+                version,
+                component,
+                floor,
+            } => write!(
+                f,
+                "New version {} has a {} below the floor of {} set by --min-{}",
+                version, component, floor, component
+            ),
+            BumpError::Template { path, message } => {
+                write!(f, "Invalid template {}: {}", path.display(), message)
+            }
+            BumpError::TagVerification { tag, message } => write!(
+                f,
+                "Could not verify the signature of git tag {}: {}",
+                tag, message
+            ),
+            BumpError::MissingChangelogEntry { path, heading } => write!(
+                f,
+                "{} has no entry for the new version; add a `{}` section before bumping",
+                path.display(),
+                heading
+            ),
+            BumpError::InvalidBatchFile {
+                path,
+                line: Some(line),
+                message,
+            } => write!(
+                f,
+                "Invalid batch file {}, line {}: {}",
+                path.display(),
+                line,
+                message
+            ),
+            BumpError::InvalidBatchFile {
+                path,
+                line: None,
+                message,
+            } => write!(f, "Invalid batch file {}: {}", path.display(), message),
+            BumpError::AuditTrail {
+                path,
+                line: Some(line),
+                message,
+            } => write!(
+                f,
+                "Audit trail {} is broken at line {}: {}",
+                path.display(),
+                line,
+                message
+            ),
+            BumpError::AuditTrail {
+                path,
+                line: None,
+                message,
+            } => write!(
+                f,
+                "Could not read audit trail {}: {}",
+                path.display(),
+                message
+            ),
+        }
+    }
+}
+
+impl Error for BumpError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            BumpError::Write { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn write_errors_chain_their_io_error() {
+        let err = BumpError::Write {
+            path: PathBuf::from("VERSION"),
+            source: io::Error::new(io::ErrorKind::PermissionDenied, "denied"),
+        };
+
+        assert_eq!(err.to_string(), "Failed to write VERSION: denied");
+        assert_eq!(err.source().unwrap().to_string(), "denied");
+        assert!(BumpError::Git("boom".to_string()).source().is_none());
+    }
+}
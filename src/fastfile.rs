@@ -0,0 +1,139 @@
+//! Editing `version = "..."` assignments in a fastlane `Fastfile`.
+//!
+//! Lanes frequently define their own local `version`, so by default only
+//! an assignment outside every `lane ... do` block is considered.
+
+use regex::Regex;
+use std::ops::Range;
+use std::sync::LazyLock;
+
+static ASSIGNMENT: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"(?:^|[^\w.@:])version\s*=\s*(?:"([^"]+)"|'([^']+)')"#).unwrap());
+static LANE_START: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^\s*(?:private_)?lane\s+:\w+.*\bdo\b").unwrap());
+static BLOCK_START: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?:\bdo\s*(?:\|[^|]*\|)?\s*$|^\s*(?:def|if|unless|case|begin|while|until|class|module)\b)")
+        .unwrap()
+});
+static BLOCK_END: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^\s*end\b").unwrap());
+
+struct Assignment {
+    span: Range<usize>,
+    in_lane: bool,
+}
+
+fn assignments(contents: &str) -> Vec<Assignment> {
+    // One entry per open block: whether it is a lane.
+    let mut blocks: Vec<bool> = Vec::new();
+    let mut found = Vec::new();
+    let mut offset = 0;
+
+    for line in contents.split_inclusive('\n') {
+        let start = offset;
+        offset += line.len();
+        let code = line.split('#').next().unwrap_or("");
+
+        if BLOCK_END.is_match(code) {
+            blocks.pop();
+            continue;
+        }
+
+        let in_lane = blocks.iter().any(|&lane| lane);
+        if let Some(caps) = ASSIGNMENT.captures(code) {
+            if let Some(value) = caps.get(1).or(caps.get(2)) {
+                found.push(Assignment {
+                    span: start + value.start()..start + value.end(),
+                    in_lane,
+                });
+            }
+        }
+
+        if LANE_START.is_match(code) {
+            blocks.push(true);
+        } else if BLOCK_START.is_match(code) {
+            blocks.push(false);
+        }
+    }
+
+    found
+}
+
+/// Read the first top-level `version = "..."`.
+pub fn read_fastfile_version(contents: &str) -> Option<String> {
+    assignments(contents)
+        .into_iter()
+        .find(|a| !a.in_lane)
+        .map(|a| contents[a.span].to_string())
+}
+
+/// Replace the first top-level `version = "..."`.
+pub fn replace_fastfile_version(contents: &str, new_version: &str) -> Option<String> {
+    let first = assignments(contents).into_iter().find(|a| !a.in_lane)?;
+    Some(replace_spans(contents, &[first.span], new_version))
+}
+
+/// Replace every `version = "..."`, including those inside lanes.
+pub fn replace_all_fastfile_versions(contents: &str, new_version: &str) -> Option<String> {
+    let spans: Vec<_> = assignments(contents).into_iter().map(|a| a.span).collect();
+    if spans.is_empty() {
+        return None;
+    }
+    Some(replace_spans(contents, &spans, new_version))
+}
+
+fn replace_spans(contents: &str, spans: &[Range<usize>], replacement: &str) -> String {
+    let mut output = String::with_capacity(contents.len());
+    let mut last = 0;
+    for span in spans {
+        output.push_str(&contents[last..span.start]);
+        output.push_str(replacement);
+        last = span.end;
+    }
+    output.push_str(&contents[last..]);
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FASTFILE: &str = r#"default_platform(:ios)
+
+version = "2.4.0"
+
+platform :ios do
+  lane :beta do
+    version = "2.4.0-beta"
+    build_version = "99"
+    increment_version_number(version_number: version)
+  end
+end
+"#;
+
+    #[test]
+    fn reads_top_level_assignment() {
+        assert_eq!(read_fastfile_version(FASTFILE).as_deref(), Some("2.4.0"));
+    }
+
+    #[test]
+    fn replaces_only_top_level_by_default() {
+        let updated = replace_fastfile_version(FASTFILE, "2.5.0").unwrap();
+        assert!(updated.contains("\nversion = \"2.5.0\"\n"));
+        assert!(updated.contains("version = \"2.4.0-beta\""));
+        assert!(updated.contains("build_version = \"99\""));
+    }
+
+    #[test]
+    fn replaces_all_occurrences_when_requested() {
+        let updated = replace_all_fastfile_versions(FASTFILE, "2.5.0").unwrap();
+        assert_eq!(updated.matches("version = \"2.5.0\"").count(), 2);
+        assert!(updated.contains("build_version = \"99\""));
+    }
+
+    #[test]
+    fn ignores_versions_only_inside_lanes() {
+        let fastfile = "lane :release do\n  version = '1.0.0'\nend\n";
+        assert_eq!(read_fastfile_version(fastfile), None);
+        assert_eq!(replace_fastfile_version(fastfile, "1.0.1"), None);
+    }
+}
@@ -0,0 +1,67 @@
+//! Best-effort editing of `spec.version = "..."` in Ruby gemspec files.
+
+use regex::Regex;
+use std::ops::Range;
+use std::sync::LazyLock;
+
+static BLOCK_VAR: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"Gem::Specification\.new\s*(?:do|\{)\s*\|\s*(\w+)\s*\|").unwrap());
+
+/// Read the literal version assigned in the gemspec.
+///
+/// Versions taken from a constant (`spec.version = Foo::VERSION`) are not
+/// literals and yield `None`.
+pub fn read_gemspec_version(contents: &str) -> Option<String> {
+    version_span(contents).map(|span| contents[span].to_string())
+}
+
+/// Replace the literal gemspec version, returning the new file contents.
+pub fn replace_gemspec_version(contents: &str, new_version: &str) -> Option<String> {
+    let span = version_span(contents)?;
+    Some(format!(
+        "{}{}{}",
+        &contents[..span.start],
+        new_version,
+        &contents[span.end..]
+    ))
+}
+
+fn version_span(contents: &str) -> Option<Range<usize>> {
+    let receiver = BLOCK_VAR
+        .captures(contents)
+        .and_then(|caps| caps.get(1))
+        .map(|m| regex::escape(m.as_str()))
+        .unwrap_or_else(|| r"\w+".to_string());
+    let assignment = Regex::new(&format!(
+        r#"(?m)^\s*{}\.version\s*=\s*(?:"([^"]+)"|'([^']+)')"#,
+        receiver
+    ))
+    .ok()?;
+
+    let caps = assignment.captures(contents)?;
+    caps.get(1).or(caps.get(2)).map(|m| m.range())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn uses_block_variable_name() {
+        let gemspec =
+            "Gem::Specification.new do |s|\n  s.name = \"demo\"\n  s.version = '0.4.1'\nend\n";
+
+        assert_eq!(read_gemspec_version(gemspec).as_deref(), Some("0.4.1"));
+        assert_eq!(
+            replace_gemspec_version(gemspec, "0.5.0").unwrap(),
+            "Gem::Specification.new do |s|\n  s.name = \"demo\"\n  s.version = '0.5.0'\nend\n"
+        );
+    }
+
+    #[test]
+    fn skips_constant_versions() {
+        let gemspec = "Gem::Specification.new do |spec|\n  spec.version = Demo::VERSION\nend\n";
+
+        assert_eq!(read_gemspec_version(gemspec), None);
+    }
+}
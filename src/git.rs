@@ -0,0 +1,471 @@
+//! Thin wrappers around the `git` command line.
+
+use crate::error::BumpError;
+use crate::glob::glob_match;
+use semver::Version;
+use std::ffi::OsStr;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output, Stdio};
+use std::sync::OnceLock;
+
+/// Environment variable the `--github-token` credential helper reads the
+/// token from, so it never appears on a command line.
+const TOKEN_ENV: &str = "SEMVER_GIT_CREDENTIAL_TOKEN";
+
+static OPTIONS: OnceLock<GitOptions> = OnceLock::new();
+
+/// Settings applied to every git invocation, e.g. credential helpers for
+/// remote operations in CI.
+#[derive(Debug, Clone, Default)]
+pub struct GitOptions {
+    /// `-c key=value` pairs placed before the subcommand.
+    config: Vec<(String, String)>,
+    token: Option<String>,
+}
+
+impl GitOptions {
+    /// Use `command` as `credential.helper`, as `git -c credential.helper=<command>`.
+    pub fn credential_helper(mut self, command: &str) -> Self {
+        self.config
+            .push(("credential.helper".to_string(), command.to_string()));
+        self
+    }
+
+    /// Authenticate HTTPS GitHub remotes with `token`.
+    pub fn github_token(mut self, token: &str) -> Self {
+        self.config.push((
+            "credential.https://github.com.helper".to_string(),
+            format!(
+                "!f() {{ test \"$1\" = get && echo username=x-access-token && echo \"password=${}\"; }}; f",
+                TOKEN_ENV
+            ),
+        ));
+        self.token = Some(token.to_string());
+        self
+    }
+
+    /// The global arguments passed to git before the subcommand.
+    pub fn args(&self) -> Vec<String> {
+        self.config
+            .iter()
+            .flat_map(|(key, value)| ["-c".to_string(), format!("{}={}", key, value)])
+            .collect()
+    }
+
+    /// The global arguments and token for logs, with the token masked.
+    pub fn describe(&self) -> String {
+        let mut description = self.args().join(" ");
+        if self.token.is_some() {
+            description.push_str(" (GitHub token: ****)");
+        }
+        description
+    }
+}
+
+/// Apply `options` to every later git invocation in this process. Only the
+/// first call has an effect.
+pub fn configure(options: GitOptions) {
+    let _ = OPTIONS.set(options);
+}
+
+/// A `git <args>` command for `dir` with the configured options applied,
+/// ready to run.
+pub fn command<S: AsRef<OsStr>>(dir: &Path, args: &[S]) -> Command {
+    let mut command = Command::new("git");
+    if let Some(options) = OPTIONS.get() {
+        command.args(options.args());
+        if let Some(token) = &options.token {
+            command.env(TOKEN_ENV, token);
+        }
+    }
+    command.args(args).current_dir(dir).stdin(Stdio::null());
+    command
+}
+
+/// Run `git <args>` in `dir`, capturing its output.
+pub fn git(dir: &Path, args: &[&str]) -> io::Result<Output> {
+    command(dir, args).output()
+}
+
+/// Run `git <args>` in `dir` and return its trimmed stdout, failing on a
+/// non-zero exit status.
+pub fn git_stdout(dir: &Path, args: &[&str]) -> io::Result<String> {
+    let output = git(dir, args)?;
+    if !output.status.success() {
+        return Err(io::Error::other(format!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Whether `remote` is configured for the repository in `dir`.
+pub fn has_remote(remote: &str, dir: &Path) -> bool {
+    git(dir, &["remote", "get-url", remote]).is_ok_and(|output| output.status.success())
+}
+
+/// Whether `tag` exists in the local repository.
+pub fn tag_exists_locally(tag: &str, dir: &Path) -> io::Result<bool> {
+    let tags = git_stdout(dir, &["tag", "--list", tag])?;
+    Ok(tags.lines().any(|line| line == tag))
+}
+
+/// Check the GPG signature of the existing tag `tag` with `git tag -v`,
+/// failing with gpg's own report when it is missing or bad.
+pub fn verify_tag_signature(tag: &str, dir: &Path) -> Result<(), BumpError> {
+    let output = git(dir, &["tag", "-v", tag]).map_err(|err| BumpError::Git(err.to_string()))?;
+    if output.status.success() {
+        return Ok(());
+    }
+    Err(BumpError::TagVerification {
+        tag: tag.to_string(),
+        message: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+    })
+}
+
+/// Whether a local tag matches `pattern`, where `*` and `?` are wildcards.
+pub fn has_tag(pattern: &str, dir: &Path) -> Result<bool, BumpError> {
+    let tags =
+        git_stdout(dir, &["tag", "--list"]).map_err(|err| BumpError::Git(err.to_string()))?;
+    Ok(tags.lines().any(|tag| glob_match(pattern, tag)))
+}
+
+/// Whether `tag` exists on `remote`.
+pub fn tag_exists_remote(tag: &str, remote: &str, dir: &Path) -> io::Result<bool> {
+    let refname = format!("refs/tags/{}", tag);
+    let refs = git_stdout(dir, &["ls-remote", "--tags", remote, &refname])?;
+    Ok(refs
+        .lines()
+        .any(|line| line.split_whitespace().nth(1) == Some(refname.as_str())))
+}
+
+/// Paths with staged changes, relative to `dir`.
+pub fn staged_files(dir: &Path) -> io::Result<Vec<PathBuf>> {
+    let names = git_stdout(dir, &["diff", "--cached", "--name-only", "--relative"])?;
+    Ok(names.lines().map(PathBuf::from).collect())
+}
+
+/// Stage `paths` in the index.
+pub fn add(paths: &[PathBuf], dir: &Path) -> io::Result<()> {
+    git_stdout(dir, &add_args(paths)).map(|_| ())
+}
+
+/// Arguments for `git add -- <paths>`.
+pub fn add_args(paths: &[PathBuf]) -> Vec<&str> {
+    let mut args = vec!["add", "--"];
+    args.extend(paths.iter().filter_map(|path| path.to_str()));
+    args
+}
+
+/// Paths with unstaged changes in the working tree, relative to `dir`.
+pub fn modified_files(dir: &Path) -> io::Result<Vec<PathBuf>> {
+    let names = git_stdout(dir, &["diff", "--name-only", "--relative"])?;
+    Ok(names.lines().map(PathBuf::from).collect())
+}
+
+/// Create the lightweight tag `name` on HEAD.
+pub fn create_lightweight_tag(name: &str, dir: &Path) -> io::Result<()> {
+    git_stdout(dir, &["tag", name]).map(|_| ())
+}
+
+/// Attach `note` to the object `tag` points at, under `refs/notes/commits`.
+pub fn attach_git_note(tag: &str, note: &str, dir: &Path) -> io::Result<()> {
+    git_stdout(dir, &["notes", "add", "-m", note, tag]).map(|_| ())
+}
+
+/// Push `refs/notes/commits` to `remote`; git does not push notes by itself.
+pub fn push_notes(remote: &str, dir: &Path) -> io::Result<()> {
+    git_stdout(dir, &["push", "-q", remote, "refs/notes/commits"]).map(|_| ())
+}
+
+/// Create the annotated tag `name` on HEAD with `message`. With `force`, an
+/// existing tag of that name is moved instead of failing.
+pub fn create_annotated_tag(name: &str, message: &str, force: bool, dir: &Path) -> io::Result<()> {
+    git_stdout(dir, &tag_args(name, &["-m", message], force)).map(|_| ())
+}
+
+/// Create the annotated tag `name` on HEAD, reading its message from
+/// `msg_file` as `git tag -F` does.
+pub fn create_annotated_tag_from_file(
+    name: &str,
+    msg_file: &Path,
+    force: bool,
+    dir: &Path,
+) -> io::Result<()> {
+    if !msg_file.is_file() {
+        return Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("tag message file {} does not exist", msg_file.display()),
+        ));
+    }
+    let msg_file = msg_file.to_str().ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "tag message file path is not valid UTF-8",
+        )
+    })?;
+    git_stdout(dir, &tag_args(name, &["-F", msg_file], force)).map(|_| ())
+}
+
+/// Arguments for `git tag -a [-f] <name> <message...>`, where `message` is
+/// `["-m", text]` or `["-F", file]`.
+pub fn tag_args<'a>(name: &'a str, message: &[&'a str], force: bool) -> Vec<&'a str> {
+    let mut args = vec!["tag", "-a"];
+    if force {
+        args.push("-f");
+    }
+    args.push(name);
+    args.extend_from_slice(message);
+    args
+}
+
+/// Stage every modified tracked file, as `git add -u` does.
+pub fn add_tracked(dir: &Path) -> io::Result<()> {
+    git_stdout(dir, &["add", "-u"]).map(|_| ())
+}
+
+/// Stage every change in the working tree, untracked files included, as
+/// `git add -A` does.
+pub fn add_all(dir: &Path) -> io::Result<()> {
+    git_stdout(dir, &["add", "-A"]).map(|_| ())
+}
+
+/// Number of commits in `range`, as counted by `git rev-list --count`.
+pub fn count_commits(range: &str, dir: &Path) -> Result<usize, BumpError> {
+    let count = git_stdout(dir, &["rev-list", "--count", range])
+        .map_err(|err| BumpError::Git(err.to_string()))?;
+    count
+        .parse()
+        .map_err(|_| BumpError::Git(format!("unexpected commit count {:?}", count)))
+}
+
+/// How many commits HEAD can be squashed over without rewriting history
+/// shared with its upstream: the commits above the merge base with
+/// `@{upstream}`, or every commit but the root when there is no upstream.
+pub fn squashable_commits(dir: &Path) -> Result<usize, BumpError> {
+    match git_stdout(dir, &["merge-base", "HEAD", "@{upstream}"]) {
+        Ok(base) => count_commits(&format!("{}..HEAD", base), dir),
+        Err(_) => count_commits("HEAD", dir).map(|count| count.saturating_sub(1)),
+    }
+}
+
+/// Move HEAD back `count` commits, keeping their changes staged, as
+/// `git reset --soft HEAD~<count>` does.
+pub fn reset_soft(count: usize, dir: &Path) -> io::Result<()> {
+    git_stdout(dir, &["reset", "-q", "--soft", &format!("HEAD~{}", count)]).map(|_| ())
+}
+
+/// Move HEAD and the index back to `commit`, leaving the working tree alone.
+pub fn reset_to(commit: &str, dir: &Path) -> io::Result<()> {
+    git_stdout(dir, &["reset", "-q", commit]).map(|_| ())
+}
+
+/// Commit the index with `message`.
+pub fn commit(message: &str, dir: &Path) -> io::Result<()> {
+    git_stdout(dir, &["commit", "-q", "-m", message]).map(|_| ())
+}
+
+/// Commit only `paths` with `message`, as `git commit --only` does; other
+/// changes already in the index stay staged and out of the commit.
+pub fn commit_paths(message: &str, paths: &[PathBuf], dir: &Path) -> io::Result<()> {
+    git_stdout(dir, &commit_paths_args(message, paths)).map(|_| ())
+}
+
+/// Arguments for [`commit_paths`].
+pub fn commit_paths_args<'a>(message: &'a str, paths: &'a [PathBuf]) -> Vec<&'a str> {
+    let mut args = vec!["commit", "-q", "-m", message, "--only", "--"];
+    args.extend(paths.iter().filter_map(|path| path.to_str()));
+    args
+}
+
+/// Full hash of the commit HEAD points at.
+pub fn head_sha(dir: &Path) -> Result<String, BumpError> {
+    git_stdout(dir, &["rev-parse", "HEAD"]).map_err(|err| BumpError::Git(err.to_string()))
+}
+
+/// The configured `user.name <user.email>`, or whichever of the two is set.
+pub fn user_identity(dir: &Path) -> Option<String> {
+    let config = |key| {
+        git_stdout(dir, &["config", key])
+            .ok()
+            .filter(|v| !v.is_empty())
+    };
+    match (config("user.name"), config("user.email")) {
+        (Some(name), Some(email)) => Some(format!("{} <{}>", name, email)),
+        (name, email) => name.or(email),
+    }
+}
+
+/// Whether `dir` is inside a git repository.
+pub fn is_git_repo(dir: &Path) -> bool {
+    git_stdout(dir, &["rev-parse", "--git-dir"]).is_ok()
+}
+
+/// Name of the checked-out branch, or `HEAD` when detached.
+pub fn get_current_branch(dir: &Path) -> Result<String, BumpError> {
+    git_stdout(dir, &["rev-parse", "--abbrev-ref", "HEAD"])
+        .map_err(|err| BumpError::Git(err.to_string()))
+}
+
+/// The `git log` pretty-format used for template commits unless
+/// `--git-log-format` is given: the subject alone.
+pub const DEFAULT_LOG_FORMAT: &str = "%s";
+
+/// Characters refused in a `git log` pretty-format string.
+///
+/// The format is passed to git as a single argument, never through a shell,
+/// but rejecting shell metacharacters keeps a format copied from a config
+/// file from doing anything surprising if it ever is.
+const UNSAFE_LOG_FORMAT_CHARS: &[char] =
+    &[';', '|', '&', '$', '`', '<', '>', '\\', '\n', '\r', '\0'];
+
+/// Check that `format` is a non-empty pretty-format without shell
+/// metacharacters.
+pub fn validate_log_format(format: &str) -> Result<(), BumpError> {
+    if format.is_empty() {
+        return Err(BumpError::Git(
+            "git log format must not be empty".to_string(),
+        ));
+    }
+    match format.chars().find(|c| UNSAFE_LOG_FORMAT_CHARS.contains(c)) {
+        Some(c) => Err(BumpError::Git(format!(
+            "git log format {:?} contains disallowed character {:?}",
+            format, c
+        ))),
+        None => Ok(()),
+    }
+}
+
+/// One line per commit after `since` (a tag or other revision; empty for
+/// the whole history), newest first, rendered with the `git log`
+/// pretty-format `format`.
+pub fn collect_formatted_commits(
+    since: &str,
+    format: &str,
+    dir: &Path,
+) -> Result<Vec<String>, BumpError> {
+    validate_log_format(format)?;
+    let range = if since.is_empty() {
+        "HEAD".to_string()
+    } else {
+        format!("{}..HEAD", since)
+    };
+    // `-z` separates commits with NUL so formats spanning lines stay whole.
+    let log = git_stdout(dir, &["log", "-z", &format!("--format={}", format), &range])
+        .map_err(|err| BumpError::Git(err.to_string()))?;
+    Ok(log
+        .split('\0')
+        .map(|line| line.trim_end().to_string())
+        .filter(|line| !line.is_empty())
+        .collect())
+}
+
+/// Whether any tracked file differs from `tag`, as `git diff <tag>
+/// --name-only` reports, counting uncommitted changes too.
+pub fn has_changes_since_tag(tag: &str, dir: &Path) -> Result<bool, BumpError> {
+    git_stdout(dir, &["diff", tag, "--name-only", "--"])
+        .map(|names| !names.is_empty())
+        .map_err(|err| BumpError::Git(err.to_string()))
+}
+
+/// Versions of the tags named `<prefix><semver>`, latest first.
+pub fn list_semver_tags(prefix: &str, dir: &Path) -> Result<Vec<Version>, BumpError> {
+    let tags =
+        git_stdout(dir, &["tag", "--list"]).map_err(|err| BumpError::Git(err.to_string()))?;
+    let mut versions: Vec<Version> = tags
+        .lines()
+        .filter_map(|tag| tag.strip_prefix(prefix))
+        .filter_map(|version| Version::parse(version).ok())
+        .collect();
+    versions.sort_by(|a, b| b.cmp_precedence(a).then_with(|| b.cmp(a)));
+    versions.dedup();
+    Ok(versions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn run(dir: &Path, args: &[&str]) {
+        let output = Command::new("git")
+            .args(args)
+            .current_dir(dir)
+            .env("GIT_AUTHOR_NAME", "Jane")
+            .env("GIT_AUTHOR_EMAIL", "jane@example.com")
+            .env("GIT_COMMITTER_NAME", "Jane")
+            .env("GIT_COMMITTER_EMAIL", "jane@example.com")
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "git {:?} failed", args);
+    }
+
+    #[test]
+    fn collects_commits_since_a_tag() {
+        let dir = tempfile::tempdir().unwrap();
+        let dir = dir.path();
+        run(dir, &["init", "-q"]);
+        run(dir, &["commit", "-q", "--allow-empty", "-m", "initial"]);
+        run(dir, &["tag", "v1.0.0"]);
+        run(dir, &["commit", "-q", "--allow-empty", "-m", "Add feature"]);
+        run(dir, &["commit", "-q", "--allow-empty", "-m", "Fix bug"]);
+
+        assert_eq!(
+            collect_formatted_commits("v1.0.0", "%an: %s", dir).unwrap(),
+            ["Jane: Fix bug", "Jane: Add feature"]
+        );
+        assert_eq!(collect_formatted_commits("", "%s", dir).unwrap().len(), 3);
+    }
+
+    #[test]
+    fn detects_changes_since_a_tag() {
+        let dir = tempfile::tempdir().unwrap();
+        let dir = dir.path();
+        run(dir, &["init", "-q"]);
+        fs::write(dir.join("VERSION"), "1.0.0").unwrap();
+        run(dir, &["add", "VERSION"]);
+        run(dir, &["commit", "-q", "-m", "initial"]);
+        run(dir, &["tag", "v1.0.0"]);
+
+        assert!(!has_changes_since_tag("v1.0.0", dir).unwrap());
+        run(dir, &["commit", "-q", "--allow-empty", "-m", "Empty"]);
+        assert!(!has_changes_since_tag("v1.0.0", dir).unwrap());
+        fs::write(dir.join("VERSION"), "1.0.1").unwrap();
+        assert!(has_changes_since_tag("v1.0.0", dir).unwrap());
+        assert!(has_changes_since_tag("v9.9.9", dir).is_err());
+    }
+
+    #[test]
+    fn passes_credential_helpers_as_config() {
+        let options = GitOptions::default().credential_helper("store --file=/tmp/creds");
+
+        assert_eq!(
+            options.args(),
+            ["-c", "credential.helper=store --file=/tmp/creds"]
+        );
+    }
+
+    #[test]
+    fn keeps_github_token_out_of_arguments() {
+        let options = GitOptions::default().github_token("ghp_secret");
+
+        let args = options.args().join(" ");
+        assert!(args.starts_with("-c credential.https://github.com.helper=!f()"));
+        assert!(!args.contains("ghp_secret"));
+        assert!(!options.describe().contains("ghp_secret"));
+        assert!(options.describe().ends_with("(GitHub token: ****)"));
+    }
+
+    #[test]
+    fn rejects_shell_metacharacters() {
+        assert!(validate_log_format("%H %s").is_ok());
+        assert!(validate_log_format("%C(auto)%h %s").is_ok());
+        assert!(validate_log_format("").is_err());
+        for format in ["%s; rm -rf /", "%s | cat", "$(whoami)", "`id`", "%s > out"] {
+            assert!(validate_log_format(format).is_err(), "{:?}", format);
+        }
+    }
+}
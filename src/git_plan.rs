@@ -0,0 +1,146 @@
+//! Git operations planned as a small dependency graph, so independent steps
+//! can run concurrently with `--parallel-git-ops`.
+
+use crate::error::BumpError;
+use crate::git;
+use std::path::Path;
+use std::process::Output;
+
+/// One git invocation and the steps that must finish before it starts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GitStep {
+    pub args: Vec<String>,
+    pub after: Vec<usize>,
+}
+
+/// Git steps in the order they were planned. A step may only depend on
+/// steps planned before it, so the graph can never contain a cycle.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GitPlan {
+    steps: Vec<GitStep>,
+}
+
+impl GitPlan {
+    /// Plan `git <args>` to run once every step in `after` has finished,
+    /// returning its index for later steps to depend on.
+    ///
+    /// # Panics
+    ///
+    /// If `after` names a step that has not been planned yet.
+    pub fn step<S: AsRef<str>>(&mut self, args: &[S], after: &[usize]) -> usize {
+        let index = self.steps.len();
+        assert!(
+            after.iter().all(|&dep| dep < index),
+            "git step {} depends on a later step",
+            index
+        );
+        self.steps.push(GitStep {
+            args: args.iter().map(|arg| arg.as_ref().to_string()).collect(),
+            after: after.to_vec(),
+        });
+        index
+    }
+
+    /// Group the steps into waves: every step's dependencies sit in earlier
+    /// waves, so the steps within one wave are independent of each other.
+    pub fn waves(&self) -> Vec<Vec<usize>> {
+        let mut depth = vec![0; self.steps.len()];
+        let mut waves: Vec<Vec<usize>> = Vec::new();
+        for (index, step) in self.steps.iter().enumerate() {
+            depth[index] = step
+                .after
+                .iter()
+                .map(|&dep| depth[dep] + 1)
+                .max()
+                .unwrap_or(0);
+            if waves.len() <= depth[index] {
+                waves.push(Vec::new());
+            }
+            waves[depth[index]].push(index);
+        }
+        waves
+    }
+
+    /// Run every step one after another in `dir`, stopping at the first failure.
+    pub fn run(&self, dir: &Path) -> Result<(), BumpError> {
+        for step in &self.steps {
+            check(step, git::command(dir, &step.args).output())?;
+        }
+        Ok(())
+    }
+
+    /// Run the plan in `dir` wave by wave, starting the steps of each wave
+    /// concurrently. Later waves are skipped once a step fails.
+    #[cfg(feature = "parallel-git")]
+    pub fn run_parallel(&self, dir: &Path) -> Result<(), BumpError> {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_io()
+            .build()
+            .map_err(|err| BumpError::Git(err.to_string()))?;
+
+        runtime.block_on(async {
+            for wave in self.waves() {
+                let mut running = tokio::task::JoinSet::new();
+                for index in wave {
+                    let mut command =
+                        tokio::process::Command::from(git::command(dir, &self.steps[index].args));
+                    running.spawn(async move { (index, command.output().await) });
+                }
+                while let Some(joined) = running.join_next().await {
+                    let (index, output) = joined.map_err(|err| BumpError::Git(err.to_string()))?;
+                    check(&self.steps[index], output)?;
+                }
+            }
+            Ok(())
+        })
+    }
+}
+
+fn check(step: &GitStep, output: std::io::Result<Output>) -> Result<(), BumpError> {
+    let output = output.map_err(|err| BumpError::Git(err.to_string()))?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(BumpError::Git(format!(
+            "git {} failed: {}",
+            step.args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn waves_follow_dependencies() {
+        let mut plan = GitPlan::default();
+        let stage = plan.step(&["add", "--", "VERSION"], &[]);
+        let commit = plan.step(&["commit", "-m", "bump"], &[stage]);
+        let tag = plan.step(&["tag", "-a", "v1.0.1", "-m", "Release"], &[commit]);
+        let push = plan.step(&["push", "origin", "HEAD"], &[commit]);
+        let push_tag = plan.step(&["push", "origin", "v1.0.1"], &[tag]);
+
+        assert_eq!(
+            plan.waves(),
+            [vec![stage], vec![commit], vec![tag, push], vec![push_tag]]
+        );
+    }
+
+    #[test]
+    fn independent_steps_share_the_first_wave() {
+        let mut plan = GitPlan::default();
+        plan.step(&["fetch", "--tags"], &[]);
+        plan.step(&["status"], &[]);
+
+        assert_eq!(plan.waves(), [vec![0, 1]]);
+        assert!(GitPlan::default().waves().is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "depends on a later step")]
+    fn rejects_forward_dependencies() {
+        GitPlan::default().step(&["status"], &[1]);
+    }
+}
@@ -0,0 +1,121 @@
+//! Creating GitHub Releases through the REST API.
+
+use crate::error::BumpError;
+use serde::{Deserialize, Serialize};
+
+pub const DEFAULT_API_URL: &str = "https://api.github.com";
+
+/// Everything needed to create one release.
+#[derive(Debug, Clone)]
+pub struct GithubReleaseConfig {
+    /// API root, e.g. `https://api.github.com` or a GitHub Enterprise URL.
+    pub api_url: String,
+    pub token: String,
+    pub owner: String,
+    pub repo: String,
+    pub tag: String,
+    pub name: String,
+    pub body: String,
+    pub draft: bool,
+    pub prerelease: bool,
+}
+
+#[derive(Serialize)]
+struct CreateRelease<'a> {
+    tag_name: &'a str,
+    name: &'a str,
+    body: &'a str,
+    draft: bool,
+    prerelease: bool,
+}
+
+#[derive(Deserialize)]
+struct Release {
+    html_url: String,
+}
+
+/// Create the release and return its `html_url`.
+pub fn create_github_release(config: &GithubReleaseConfig) -> Result<String, BumpError> {
+    let url = format!(
+        "{}/repos/{}/{}/releases",
+        config.api_url.trim_end_matches('/'),
+        config.owner,
+        config.repo
+    );
+    let payload = CreateRelease {
+        tag_name: &config.tag,
+        name: &config.name,
+        body: &config.body,
+        draft: config.draft,
+        prerelease: config.prerelease,
+    };
+
+    let response = ureq::post(&url)
+        .set("Accept", "application/vnd.github+json")
+        .set("Authorization", &format!("Bearer {}", config.token))
+        .set("X-GitHub-Api-Version", "2022-11-28")
+        .send_json(&payload)
+        .map_err(|err| match err {
+            ureq::Error::Status(code, response) => BumpError::GithubRelease(format!(
+                "GitHub responded {}: {}",
+                code,
+                response.into_string().unwrap_or_default()
+            )),
+            other => BumpError::GithubRelease(other.to_string()),
+        })?;
+
+    let release: Release = response
+        .into_json()
+        .map_err(|err| BumpError::GithubRelease(format!("unexpected response: {}", err)))?;
+    Ok(release.html_url)
+}
+
+/// Extract `(owner, repo)` from a GitHub remote URL.
+///
+/// Handles `https://github.com/o/r(.git)`, `git@github.com:o/r(.git)` and
+/// `ssh://git@github.com/o/r(.git)`.
+pub fn parse_github_remote(url: &str) -> Option<(String, String)> {
+    let url = url.trim();
+    let path = if let Some(rest) = url.strip_prefix("git@") {
+        rest.split_once(':')?.1
+    } else {
+        let without_scheme = url.split_once("://")?.1;
+        without_scheme.split_once('/')?.1
+    };
+
+    let path = path.trim_end_matches('/');
+    let path = path.strip_suffix(".git").unwrap_or(path);
+    let (owner, repo) = path.split_once('/')?;
+    if owner.is_empty() || repo.is_empty() || repo.contains('/') {
+        return None;
+    }
+    Some((owner.to_string(), repo.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_remote_urls() {
+        let expected = Some(("acme".to_string(), "widgets".to_string()));
+
+        assert_eq!(
+            parse_github_remote("https://github.com/acme/widgets.git"),
+            expected
+        );
+        assert_eq!(
+            parse_github_remote("https://github.com/acme/widgets"),
+            expected
+        );
+        assert_eq!(
+            parse_github_remote("git@github.com:acme/widgets.git"),
+            expected
+        );
+        assert_eq!(
+            parse_github_remote("ssh://git@github.com/acme/widgets.git"),
+            expected
+        );
+        assert_eq!(parse_github_remote("https://github.com/acme"), None);
+    }
+}
@@ -0,0 +1,107 @@
+//! Minimal `*` / `?` glob matching for branch names and file names.
+
+use regex::Regex;
+use std::fs;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+
+/// Whether `text` matches `pattern` as a whole, where `*` matches any run of
+/// characters and `?` a single one.
+pub fn glob_match(pattern: &str, text: &str) -> bool {
+    let mut glob = String::from("^");
+    for c in pattern.chars() {
+        match c {
+            '*' => glob.push_str(".*"),
+            '?' => glob.push('.'),
+            c => glob.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    glob.push('$');
+
+    Regex::new(&glob).is_ok_and(|re| re.is_match(text))
+}
+
+/// Whether `path` matches `pattern` with [`glob_match`], ignoring `./`
+/// components and comparing with `/` separators.
+pub fn glob_match_path(pattern: &str, path: &Path) -> bool {
+    let path = path
+        .components()
+        .filter(|c| *c != Component::CurDir)
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/");
+    glob_match(pattern.trim_start_matches("./"), &path)
+}
+
+/// Files matching `pattern`, sorted by path.
+///
+/// Only the file name may contain wildcards, e.g. `package.*.json` or
+/// `app/package*.json`; the directory part is taken literally.
+pub fn expand_file_glob(pattern: &str) -> io::Result<Vec<PathBuf>> {
+    let pattern = Path::new(pattern);
+    let name = pattern
+        .file_name()
+        .and_then(|name| name.to_str())
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "glob has no file name"))?;
+    let dir = pattern.parent().unwrap_or(Path::new(""));
+    if dir.to_string_lossy().contains(['*', '?']) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "wildcards are only supported in the file name",
+        ));
+    }
+
+    let read_dir = if dir.as_os_str().is_empty() {
+        Path::new(".")
+    } else {
+        dir
+    };
+    let mut found = Vec::new();
+    for entry in fs::read_dir(read_dir)? {
+        let entry = entry?;
+        let matches = entry
+            .file_name()
+            .to_str()
+            .is_some_and(|file| glob_match(name, file));
+        if matches && entry.file_type()?.is_file() {
+            found.push(dir.join(entry.file_name()));
+        }
+    }
+    found.sort();
+    Ok(found)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_wildcards() {
+        assert!(glob_match("package.*.json", "package.renderer.json"));
+        assert!(glob_match("package*.json", "package.json"));
+        assert!(glob_match("v?.json", "v1.json"));
+        assert!(!glob_match("package.*.json", "package.json"));
+        assert!(!glob_match("*.json", "composer.lock"));
+    }
+
+    #[test]
+    fn matches_paths() {
+        let path = Path::new("./packages/legacy/package.json");
+
+        assert!(glob_match_path("packages/legacy/*", path));
+        assert!(glob_match_path("./packages/legacy/package.json", path));
+        assert!(glob_match_path("*/legacy/*", path));
+        assert!(!glob_match_path("packages/app/*", path));
+        assert!(!glob_match_path("legacy/*", path));
+    }
+
+    #[test]
+    fn rejects_wildcard_directories() {
+        assert_eq!(
+            expand_file_glob("packages/*/package.json")
+                .unwrap_err()
+                .kind(),
+            io::ErrorKind::InvalidInput
+        );
+    }
+}
@@ -0,0 +1,144 @@
+//! Line-based editing of the `version` and `appVersion` fields of a Helm
+//! `Chart.yaml`.
+//!
+//! The two fields are bumped independently, each from its own current
+//! value: the chart version tracks the packaging, `appVersion` the
+//! application inside it. The file is never parsed as YAML, so comments and
+//! layout are kept.
+
+use crate::bump::BumpType;
+use crate::error::BumpError;
+use regex::Regex;
+use semver::Version;
+use std::ops::Range;
+use std::path::Path;
+use std::sync::LazyLock;
+
+static CHART_VERSION: LazyLock<Regex> = LazyLock::new(|| top_level_field("version"));
+static APP_VERSION: LazyLock<Regex> = LazyLock::new(|| top_level_field("appVersion"));
+
+fn top_level_field(name: &str) -> Regex {
+    Regex::new(&format!(
+        r#"(?m)^{}:[ \t]*(?:"([^"]*)"|'([^']*)'|([^\s#'"]+))"#,
+        name
+    ))
+    .unwrap()
+}
+
+/// Which `Chart.yaml` fields to bump, and how. `None` leaves a field alone.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct HelmBumpConfig {
+    /// Bump for the chart's own `version`.
+    pub chart: Option<BumpType>,
+    /// Bump for `appVersion`.
+    pub app: Option<BumpType>,
+}
+
+impl HelmBumpConfig {
+    /// Bump the requested fields of the chart at `path`, returning the new
+    /// file contents, or `None` when no field was requested.
+    ///
+    /// Either every requested field is bumped or an error is returned and
+    /// nothing should be written.
+    pub fn apply(&self, contents: &str, path: &Path) -> Result<Option<String>, BumpError> {
+        let mut edits = Vec::new();
+        for (field, regex, bump) in [
+            ("version", &*CHART_VERSION, self.chart),
+            ("appVersion", &*APP_VERSION, self.app),
+        ] {
+            let Some(bump) = bump else { continue };
+            let span = value_span(regex, contents).ok_or_else(|| BumpError::MissingField {
+                path: path.to_path_buf(),
+                field: field.to_string(),
+            })?;
+            let current = &contents[span.clone()];
+            let mut version = Version::parse(current).map_err(|_| BumpError::InvalidVersion {
+                path: path.to_path_buf(),
+                version: current.to_string(),
+            })?;
+            bump.apply(&mut version);
+            version.pre = semver::Prerelease::EMPTY;
+            version.build = semver::BuildMetadata::EMPTY;
+            edits.push((span, version.to_string()));
+        }
+        if edits.is_empty() {
+            return Ok(None);
+        }
+
+        // Splice from the end so earlier spans stay valid.
+        edits.sort_by_key(|(span, _)| std::cmp::Reverse(span.start));
+        let mut output = contents.to_string();
+        for (span, version) in edits {
+            output.replace_range(span, &version);
+        }
+        Ok(Some(output))
+    }
+}
+
+/// Read the chart's own `version`.
+pub fn read_chart_version(contents: &str) -> Option<String> {
+    value_span(&CHART_VERSION, contents).map(|span| contents[span].to_string())
+}
+
+/// Read the chart's `appVersion`.
+pub fn read_app_version(contents: &str) -> Option<String> {
+    value_span(&APP_VERSION, contents).map(|span| contents[span].to_string())
+}
+
+fn value_span(field: &Regex, contents: &str) -> Option<Range<usize>> {
+    let caps = field.captures(contents)?;
+    caps.get(1)
+        .or(caps.get(2))
+        .or(caps.get(3))
+        .map(|m| m.range())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CHART: &str = "apiVersion: v2\nname: demo\n# chart packaging version\nversion: 0.3.2\nappVersion: \"1.4.0\"\ndependencies:\n  - name: redis\n    version: 17.0.0\n";
+
+    #[test]
+    fn reads_top_level_fields_only() {
+        assert_eq!(read_chart_version(CHART).as_deref(), Some("0.3.2"));
+        assert_eq!(read_app_version(CHART).as_deref(), Some("1.4.0"));
+    }
+
+    #[test]
+    fn bumps_fields_independently() {
+        let config = HelmBumpConfig {
+            chart: Some(BumpType::Minor),
+            app: Some(BumpType::Patch),
+        };
+
+        assert_eq!(
+            config
+                .apply(CHART, Path::new("Chart.yaml"))
+                .unwrap()
+                .unwrap(),
+            CHART
+                .replace("version: 0.3.2", "version: 0.4.0")
+                .replace("1.4.0", "1.4.1")
+        );
+        assert_eq!(
+            HelmBumpConfig::default()
+                .apply(CHART, Path::new("Chart.yaml"))
+                .unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn fails_when_a_requested_field_is_missing() {
+        let config = HelmBumpConfig {
+            chart: Some(BumpType::Patch),
+            app: Some(BumpType::Patch),
+        };
+
+        assert!(matches!(
+            config.apply("version: 1.0.0\n", Path::new("Chart.yaml")),
+            Err(BumpError::MissingField { field, .. }) if field == "appVersion"
+        ));
+    }
+}
@@ -0,0 +1,130 @@
+//! The `VERSION.history` log: one `<timestamp> <old> → <new> (<bump>)` line per bump.
+
+use std::fmt;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::Path;
+
+pub const HISTORY_FILE: &str = "VERSION.history";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HistoryEntry {
+    pub timestamp: String,
+    pub old: String,
+    pub new: String,
+    pub bump_type: String,
+}
+
+impl HistoryEntry {
+    /// Parse a line written by [`append_history`].
+    pub fn parse(line: &str) -> Option<HistoryEntry> {
+        let (timestamp, rest) = line.split_once(' ')?;
+        let (old, rest) = rest.split_once(" → ")?;
+        let (new, bump) = rest.split_once(' ')?;
+        let bump_type = bump.strip_prefix('(')?.strip_suffix(')')?;
+
+        Some(HistoryEntry {
+            timestamp: timestamp.to_string(),
+            old: old.to_string(),
+            new: new.to_string(),
+            bump_type: bump_type.to_string(),
+        })
+    }
+}
+
+impl fmt::Display for HistoryEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {} → {} ({})",
+            self.timestamp, self.old, self.new, self.bump_type
+        )
+    }
+}
+
+/// Append `entry` to the history file, creating it if needed.
+pub fn append_history(path: &Path, entry: &HistoryEntry) -> io::Result<()> {
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    file.write_all(format!("{}\n", entry).as_bytes())
+}
+
+/// Render the history file as aligned columns, oldest first.
+///
+/// Lines that are not in the expected format are passed through unchanged.
+pub fn format_history(contents: &str) -> String {
+    let lines: Vec<_> = contents
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| (line, HistoryEntry::parse(line)))
+        .collect();
+
+    let width = |get: fn(&HistoryEntry) -> &str| {
+        lines
+            .iter()
+            .filter_map(|(_, entry)| entry.as_ref().map(|e| get(e).chars().count()))
+            .max()
+            .unwrap_or(0)
+    };
+    let old_width = width(|e| &e.old);
+    let new_width = width(|e| &e.new);
+
+    let mut output = String::new();
+    for (line, entry) in lines {
+        match entry {
+            Some(e) => output.push_str(&format!(
+                "{}  {:<old_width$} → {:<new_width$}  {}\n",
+                e.timestamp.replacen('T', " ", 1).trim_end_matches('Z'),
+                e.old,
+                e.new,
+                e.bump_type
+            )),
+            None => output.push_str(&format!("{}\n", line)),
+        }
+    }
+    output
+}
+
+/// Read and format the history file at `path`.
+pub fn read_history(path: &Path) -> io::Result<String> {
+    fs::read_to_string(path).map(|contents| format_history(&contents))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(old: &str, new: &str, bump: &str) -> HistoryEntry {
+        HistoryEntry {
+            timestamp: "2024-03-05T14:07:09Z".to_string(),
+            old: old.to_string(),
+            new: new.to_string(),
+            bump_type: bump.to_string(),
+        }
+    }
+
+    #[test]
+    fn round_trips_lines() {
+        let e = entry("1.2.3", "1.3.0-rc.1", "minor");
+        assert_eq!(
+            e.to_string(),
+            "2024-03-05T14:07:09Z 1.2.3 → 1.3.0-rc.1 (minor)"
+        );
+        assert_eq!(HistoryEntry::parse(&e.to_string()), Some(e));
+    }
+
+    #[test]
+    fn aligns_columns() {
+        let contents = format!(
+            "{}\n{}\nhand-written note\n",
+            entry("0.9.9", "0.10.0", "minor"),
+            entry("0.10.0", "1.0.0", "major")
+        );
+
+        assert_eq!(
+            format_history(&contents),
+            "2024-03-05 14:07:09  0.9.9  → 0.10.0  minor\n\
+             2024-03-05 14:07:09  0.10.0 → 1.0.0   major\n\
+             hand-written note\n"
+        );
+    }
+}
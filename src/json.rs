@@ -0,0 +1,266 @@
+//! Rewriting the `version` field of JSON manifests and npm lockfiles, and
+//! the `major`, `minor` and `patch` fields of a structured `VERSION.json`.
+
+use indexmap::IndexMap;
+use semver::Version;
+use serde::Serialize;
+use serde_json::Value;
+use serde_json::ser::{PrettyFormatter, Serializer};
+use std::fmt;
+use std::str::FromStr;
+
+/// Indentation used when re-serializing a JSON file, set by `--json-indent`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JsonIndent {
+    /// Between 0 and 8 spaces per level.
+    Spaces(u8),
+    Tab,
+}
+
+impl JsonIndent {
+    fn as_string(self) -> String {
+        match self {
+            JsonIndent::Spaces(n) => " ".repeat(n as usize),
+            JsonIndent::Tab => "\t".to_string(),
+        }
+    }
+}
+
+impl Default for JsonIndent {
+    fn default() -> Self {
+        JsonIndent::Spaces(2)
+    }
+}
+
+impl fmt::Display for JsonIndent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            JsonIndent::Spaces(n) => write!(f, "{}", n),
+            JsonIndent::Tab => f.write_str("tab"),
+        }
+    }
+}
+
+impl FromStr for JsonIndent {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "tab" => Ok(JsonIndent::Tab),
+            _ => match s.parse::<u8>() {
+                Ok(n) if n <= 8 => Ok(JsonIndent::Spaces(n)),
+                _ => Err(format!(
+                    "invalid JSON indent '{}': expected 0-8 spaces or 'tab'",
+                    s
+                )),
+            },
+        }
+    }
+}
+
+/// Replace the top-level `version` string, keeping the other keys in order.
+///
+/// Returns `Ok(None)` when the document has no string `version` field. The
+/// output is pretty-printed with `indent` per level and `\n` line endings.
+pub fn replace_json_version(
+    contents: &str,
+    new_version: &str,
+    indent: JsonIndent,
+) -> Result<Option<String>, serde_json::Error> {
+    let mut map: IndexMap<String, Value> = serde_json::from_str(contents)?;
+
+    match map.get_mut("version") {
+        Some(Value::String(version)) => *version = new_version.to_string(),
+        _ => return Ok(None),
+    }
+
+    to_pretty_string(&map, indent).map(Some)
+}
+
+/// npm lockfiles kept beside a `package.json`, bumped along with it.
+pub const NPM_LOCKFILES: [&str; 2] = ["package-lock.json", "npm-shrinkwrap.json"];
+
+/// Replace the version of an npm lockfile: the top-level `version` and, from
+/// lockfile version 2, the root package's under `packages[""]`.
+///
+/// Returns `Ok(None)` when the document has no string `version` field.
+pub fn replace_lockfile_version(
+    contents: &str,
+    new_version: &str,
+    indent: JsonIndent,
+) -> Result<Option<String>, serde_json::Error> {
+    let mut map: IndexMap<String, Value> = serde_json::from_str(contents)?;
+
+    match map.get_mut("version") {
+        Some(Value::String(version)) => *version = new_version.to_string(),
+        _ => return Ok(None),
+    }
+    if let Some(Value::String(version)) = map
+        .get_mut("packages")
+        .and_then(|packages| packages.get_mut(""))
+        .and_then(|root| root.get_mut("version"))
+    {
+        *version = new_version.to_string();
+    }
+
+    to_pretty_string(&map, indent).map(Some)
+}
+
+/// Structured version file auto-detected in the project root.
+pub const STRUCTURED_VERSION_FILE: &str = "VERSION.json";
+
+/// Fields of a structured version file, in order.
+const COMPONENTS: [&str; 3] = ["major", "minor", "patch"];
+
+/// The version held in separate `major`, `minor` and `patch` integer
+/// fields, e.g. `{"major": 1, "minor": 2, "patch": 3}`.
+pub fn read_structured_version(contents: &str) -> Option<Version> {
+    let map: IndexMap<String, Value> = serde_json::from_str(contents).ok()?;
+    let [major, minor, patch] = COMPONENTS.map(|field| map.get(field).and_then(Value::as_u64));
+    Some(Version::new(major?, minor?, patch?))
+}
+
+/// Set each of the `major`, `minor` and `patch` fields to `new_version`'s,
+/// keeping the other keys in order.
+///
+/// Returns `Ok(None)` unless the document has all three integer fields.
+pub fn replace_structured_version(
+    contents: &str,
+    new_version: &Version,
+    indent: JsonIndent,
+) -> Result<Option<String>, serde_json::Error> {
+    let mut map: IndexMap<String, Value> = serde_json::from_str(contents)?;
+    if read_structured_version(contents).is_none() {
+        return Ok(None);
+    }
+
+    let values = [new_version.major, new_version.minor, new_version.patch];
+    for (field, value) in COMPONENTS.into_iter().zip(values) {
+        map.insert(field.to_string(), Value::from(value));
+    }
+    to_pretty_string(&map, indent).map(Some)
+}
+
+fn to_pretty_string(
+    map: &IndexMap<String, Value>,
+    indent: JsonIndent,
+) -> Result<String, serde_json::Error> {
+    let indent = indent.as_string();
+    let mut output = Vec::new();
+    let mut serializer =
+        Serializer::with_formatter(&mut output, PrettyFormatter::with_indent(indent.as_bytes()));
+    map.serialize(&mut serializer)?;
+    Ok(String::from_utf8(output).expect("serde_json writes valid UTF-8"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn replaces_version_in_place() {
+        let json = r#"{"name": "demo", "version": "1.2.3", "private": true}"#;
+
+        assert_eq!(
+            replace_json_version(json, "1.3.0", JsonIndent::default())
+                .unwrap()
+                .unwrap(),
+            "{\n  \"name\": \"demo\",\n  \"version\": \"1.3.0\",\n  \"private\": true\n}"
+        );
+    }
+
+    #[test]
+    fn reports_missing_version_and_invalid_json() {
+        let indent = JsonIndent::default();
+
+        assert_eq!(
+            replace_json_version(r#"{"version": 1}"#, "1.0.0", indent).unwrap(),
+            None
+        );
+        assert!(replace_json_version("{", "1.0.0", indent).is_err());
+    }
+
+    #[test]
+    fn indents_with_spaces_or_tabs() {
+        let json = r#"{"version": "1.0.0"}"#;
+        let replaced = |indent| {
+            replace_json_version(json, "1.0.1", indent)
+                .unwrap()
+                .unwrap()
+        };
+
+        for n in 0..=8 {
+            let indent: JsonIndent = n.to_string().parse().unwrap();
+            assert_eq!(
+                replaced(indent),
+                format!("{{\n{}\"version\": \"1.0.1\"\n}}", " ".repeat(n))
+            );
+        }
+        assert_eq!(
+            replaced("tab".parse().unwrap()),
+            "{\n\t\"version\": \"1.0.1\"\n}"
+        );
+    }
+
+    #[test]
+    fn replaces_both_lockfile_versions() {
+        let lock = r#"{"name": "demo", "version": "1.2.3", "lockfileVersion": 3, "packages": {"": {"name": "demo", "version": "1.2.3"}, "node_modules/zod": {"version": "1.2.3", "integrity": "sha512-x"}}}"#;
+
+        let replaced = replace_lockfile_version(lock, "1.3.0", JsonIndent::Spaces(0))
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            replaced.replace('\n', ""),
+            r#"{"name": "demo","version": "1.3.0","lockfileVersion": 3,"packages": {"": {"name": "demo","version": "1.3.0"},"node_modules/zod": {"version": "1.2.3","integrity": "sha512-x"}}}"#
+        );
+        assert_eq!(
+            replace_lockfile_version(r#"{"lockfileVersion": 3}"#, "1.3.0", JsonIndent::default())
+                .unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn reads_structured_versions() {
+        assert_eq!(
+            read_structured_version(r#"{"major": 1, "minor": 2, "patch": 3}"#),
+            Some(Version::new(1, 2, 3))
+        );
+        assert_eq!(read_structured_version(r#"{"major": 1, "minor": 2}"#), None);
+        assert_eq!(
+            read_structured_version(r#"{"major": "1", "minor": 2, "patch": 3}"#),
+            None
+        );
+        assert_eq!(read_structured_version(r#"{"version": "1.2.3"}"#), None);
+    }
+
+    #[test]
+    fn replaces_each_structured_field() {
+        let json = r#"{"name": "demo", "major": 1, "minor": 9, "patch": 3, "build": 7}"#;
+
+        assert_eq!(
+            replace_structured_version(json, &Version::new(2, 0, 0), JsonIndent::default())
+                .unwrap()
+                .unwrap(),
+            "{\n  \"name\": \"demo\",\n  \"major\": 2,\n  \"minor\": 0,\n  \"patch\": 0,\n  \"build\": 7\n}"
+        );
+        assert_eq!(
+            replace_structured_version(
+                r#"{"version": "1.0.0"}"#,
+                &Version::new(1, 0, 1),
+                JsonIndent::default()
+            )
+            .unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn rejects_indent_above_eight() {
+        assert_eq!(
+            "9".parse::<JsonIndent>(),
+            Err("invalid JSON indent '9': expected 0-8 spaces or 'tab'".to_string())
+        );
+        assert!("-1".parse::<JsonIndent>().is_err());
+    }
+}
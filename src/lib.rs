@@ -0,0 +1,43 @@
+//! Version handling shared by the `semver` CLI.
+
+pub mod audit;
+pub mod audit_trail;
+pub mod azure;
+pub mod bump;
+#[cfg(feature = "calver")]
+pub mod calver;
+pub mod cargo;
+pub mod changelog;
+pub mod checks;
+pub mod config;
+pub mod csproj;
+pub mod diff;
+pub mod error;
+pub mod fastfile;
+pub mod gemspec;
+pub mod git;
+pub mod git_plan;
+pub mod github;
+pub mod glob;
+pub mod helm;
+pub mod history;
+pub mod json;
+pub mod lock;
+pub mod makefile;
+pub mod mix;
+pub mod output;
+pub mod patch;
+pub mod podspec;
+pub mod prerelease;
+pub mod pyproject;
+pub mod report;
+pub mod rollback;
+pub mod sha256;
+pub mod sign;
+pub mod snapcraft;
+pub mod sources;
+pub mod template;
+pub mod text;
+pub mod timestamp;
+pub mod workspace;
+pub mod writes;
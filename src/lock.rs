@@ -0,0 +1,48 @@
+use fs2::FileExt;
+use std::fs::{File, OpenOptions};
+use std::io;
+use std::path::Path;
+use std::thread;
+use std::time::{Duration, Instant};
+
+const RETRY_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Exclusive advisory lock held for the lifetime of the guard.
+#[derive(Debug)]
+pub struct LockGuard {
+    file: File,
+}
+
+impl LockGuard {
+    /// Acquire an exclusive lock on `path`, creating the file if needed.
+    ///
+    /// Retries until `timeout` elapses, then fails with `ErrorKind::TimedOut`.
+    pub fn acquire(path: &Path, timeout: Duration) -> io::Result<LockGuard> {
+        let file = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(path)?;
+        let deadline = Instant::now() + timeout;
+
+        loop {
+            match file.try_lock_exclusive() {
+                Ok(()) => return Ok(LockGuard { file }),
+                Err(err) if err.kind() != fs2::lock_contended_error().kind() => return Err(err),
+                Err(_) if Instant::now() >= deadline => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::TimedOut,
+                        format!("timed out waiting for lock on {}", path.display()),
+                    ));
+                }
+                Err(_) => thread::sleep(RETRY_INTERVAL),
+            }
+        }
+    }
+}
+
+impl Drop for LockGuard {
+    fn drop(&mut self) {
+        let _ = FileExt::unlock(&self.file);
+    }
+}
@@ -1,200 +1,3784 @@
-use clap::Parser;
-use indexmap::IndexMap;
-use inquire::{Select, error::InquireError};
+use clap::{ArgGroup, Parser, Subcommand, ValueEnum};
+use inquire::{Confirm, Select, error::InquireError};
+use owo_colors::OwoColorize;
 use semver::Version;
-use serde::{Deserialize, Serialize};
+use semver_cli::audit::{self, StructuredLogger};
+use semver_cli::audit_trail::{AUDIT_TRAIL_FILE, AuditChain, AuditEntry, file_digests};
+use semver_cli::azure::{read_azure_version, replace_azure_version};
+use semver_cli::bump::{
+    BumpOptions, BumpType, clean_prerelease, compute_next_version, parse_batch_file,
+    perform_staged_bump,
+};
+#[cfg(feature = "calver")]
+use semver_cli::calver::bump_calver;
+use semver_cli::cargo::{
+    find_workspace_root, read_package_name, read_package_version, replace_dependency_version,
+    update_package_version, workspace_member_manifests,
+};
+use semver_cli::changelog::{CHANGELOG_FILE, ChangelogConfig, parse_heading_level};
+use semver_cli::checks::{
+    FloorConfig, PrereleasePolicy, is_major_bump, validate_branch, validate_commit_scope,
+    validate_component_floors, validate_file_count, validate_major_bump,
+    validate_prerelease_policy, validate_sources_agree, validate_strict_semver,
+};
+use semver_cli::config::{Config, FileHookConfig, resolve_config};
+use semver_cli::csproj::{read_csproj_version, update_csproj_version};
+use semver_cli::diff::classify_version_diff;
+use semver_cli::error::BumpError;
+use semver_cli::fastfile::{
+    read_fastfile_version, replace_all_fastfile_versions, replace_fastfile_version,
+};
+use semver_cli::gemspec::{read_gemspec_version, replace_gemspec_version};
+use semver_cli::git::{
+    self, DEFAULT_LOG_FORMAT, GitOptions, collect_formatted_commits, get_current_branch,
+    has_remote, list_semver_tags, modified_files, staged_files, tag_exists_locally,
+    tag_exists_remote, validate_log_format,
+};
+#[cfg(feature = "parallel-git")]
+use semver_cli::git_plan::GitPlan;
+use semver_cli::github::{
+    DEFAULT_API_URL, GithubReleaseConfig, create_github_release, parse_github_remote,
+};
+use semver_cli::glob::{expand_file_glob, glob_match_path};
+use semver_cli::helm::HelmBumpConfig;
+use semver_cli::history::{HISTORY_FILE, HistoryEntry, append_history, read_history};
+use semver_cli::json::{
+    JsonIndent, NPM_LOCKFILES, STRUCTURED_VERSION_FILE, read_structured_version,
+    replace_json_version, replace_lockfile_version, replace_structured_version,
+};
+use semver_cli::lock::LockGuard;
+use semver_cli::makefile::{MAKEFILE_NAMES, read_makefile_version, update_makefile_version};
+use semver_cli::mix::{read_mix_version, replace_mix_version};
+use semver_cli::output;
+use semver_cli::patch::{apply_patch_file, replace_placeholder};
+use semver_cli::podspec::{
+    read_podspec_json_name, read_podspec_json_version, read_podspec_name, read_podspec_version,
+    update_podfile_lock_version, update_podspec_version,
+};
+use semver_cli::prerelease::{
+    DEFAULT_PRERELEASE_FORMAT, PRERELEASE_SEPARATORS, format_prerelease, prerelease_counter,
+    prerelease_counter_any_separator, prerelease_id_from_branch, separator_format,
+    validate_prerelease_format,
+};
+use semver_cli::pyproject::{read_pyproject_version, update_pyproject_version};
+use semver_cli::report::{ReportEntry, append_report_entry, write_github_step_summary};
+use semver_cli::rollback::RollbackGuard;
+use semver_cli::sign::{sign_file, verify_signature};
+use semver_cli::snapcraft::{
+    SNAPCRAFT_PATHS, read_snapcraft_version, update_snapcraft_version, uses_adopt_info,
+    validate_snapcraft_version,
+};
+use semver_cli::sources::{
+    InputFormat, VersionFileFormat, VersionScope, VersionSource, read_composer_json_version,
+    read_input_version, read_package_json_version, read_version_file, read_version_json_version,
+    validate_json_file,
+};
+use semver_cli::template::{
+    CommitLine, MessageContext, MessageTemplate, append_trailers, parse_trailer,
+};
+use semver_cli::text::{normalize_line_endings, uses_crlf};
+use semver_cli::timestamp::now_rfc3339;
+use semver_cli::workspace::{WorkspaceMember, read_workspace_config};
+use semver_cli::writes::{mirror_file, write_all_concurrent, write_file};
 use serde_json::Value;
-use std::io::{self, Write};
-use std::{fs, path::Path, process::exit};
+use similar::TextDiff;
+use std::collections::HashMap;
+use std::io::{self, IsTerminal, Write};
+use std::{
+    fs,
+    path::{Component, Path, PathBuf},
+    sync::Once,
+    time::Duration,
+};
 
-#[derive(Deserialize, Serialize)]
-struct PackageJson {
-    version: String,
-    #[serde(flatten)]
-    other: serde_json::Value,
+/// `std::println!`, but honouring `--output-file`.
+macro_rules! println {
+    () => {
+        output::write_line(format_args!(""))
+    };
+    ($($arg:tt)*) => {
+        output::write_line(format_args!($($arg)*))
+    };
+}
+
+/// `std::eprintln!`, but remembered as the error of a failed run for
+/// `--log-file`.
+macro_rules! eprintln {
+    ($($arg:tt)*) => {{
+        let message = format!($($arg)*);
+        audit::record(|logger| logger.record_stderr(&message));
+        std::eprintln!("{}", message);
+    }};
+}
+
+/// `std::process::exit`, logging the end of the run to `--log-file` first.
+fn exit(code: i32) -> ! {
+    audit::finish(code != 0);
+    std::process::exit(code)
+}
+
+/// Report every failed write and exit with the first one's code, so a run
+/// that left some files behind does not look successful.
+fn exit_on_write_failures(failures: &[BumpError]) {
+    let Some(first) = failures.first() else {
+        return;
+    };
+    for err in failures {
+        eprintln!("{}", err);
+    }
+    exit(first.exit_code());
+}
+
+/// CLI tool to bump semantic version
+#[derive(Parser)]
+#[command(author, version, about)]
+#[command(group(
+    ArgGroup::new("file_replacement").args(["version_placeholder", "replace_old_version"])
+))]
+struct Args {
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    /// Optional version bump type: major, minor, patch
+    #[arg(long)]
+    bump: Option<String>,
+
+    /// Global config file to use instead of $XDG_CONFIG_HOME/semver/config.toml
+    #[arg(long, value_name = "PATH")]
+    global_config: Option<PathBuf>,
+
+    /// Print the current version and exit
+    #[arg(long)]
+    print_version: bool,
+
+    /// Print the configuration resolved from the global config,
+    /// .semverrc.toml and the command line as TOML, and exit
+    #[arg(long)]
+    print_config: bool,
+
+    /// Omit the trailing newline from --print-version, --print-next and
+    /// `next` output
+    #[arg(long, global = true)]
+    no_newline: bool,
+
+    /// Compute the new version without writing any files
+    #[arg(long)]
+    dry_run: bool,
+
+    /// Record the bump in VERSION.history
+    #[arg(long)]
+    append_version_history: bool,
+
+    /// Record the bump in the .semver-audit hash chain, with the user and
+    /// the SHA-256 of the version files before and after
+    #[arg(long)]
+    audit_trail: bool,
+
+    /// Exit code to use when a dry run completes successfully
+    #[arg(long, value_name = "N", default_value_t = 0)]
+    dry_run_exit_code: i32,
+
+    /// Like --dry-run, but also print a unified diff of every file change
+    #[arg(long)]
+    simulate: bool,
+
+    /// Write the updated files in parallel instead of one after another
+    #[arg(long)]
+    concurrent_writes: bool,
+
+    /// Print a warning before a major bump and require typing `yes` to go on;
+    /// with --ci, fail with exit code 20 unless --allow-major-bump is given
+    #[arg(long)]
+    warn_major_bump: bool,
+
+    /// With --warn-major-bump, allow a major bump without asking
+    #[arg(long, requires = "warn_major_bump")]
+    allow_major_bump: bool,
+
+    /// Run non-interactively: --warn-major-bump fails with exit code 20
+    /// instead of asking for confirmation
+    #[arg(long)]
+    ci: bool,
+
+    /// Fail with exit code 26 if the new major version is below N
+    #[arg(long, value_name = "N")]
+    min_major: Option<u64>,
+
+    /// Fail with exit code 26 if the new minor version is below N
+    #[arg(long, value_name = "N")]
+    min_minor: Option<u64>,
+
+    /// Fail with exit code 26 if the new patch version is below N
+    #[arg(long, value_name = "N")]
+    min_patch: Option<u64>,
+
+    /// After the bump type is chosen, show the next version and ask for
+    /// confirmation before anything is written
+    #[arg(long, conflicts_with = "sanitize")]
+    interactive_bump_preview: bool,
+
+    /// Show the diff of every file change and ask for confirmation before writing
+    #[arg(long, conflicts_with_all = ["dry_run", "simulate"])]
+    interactive_diff_confirm: bool,
+
+    /// Report each file operation on stderr
+    #[arg(short, long)]
+    verbose: bool,
+
+    /// Write regular output to this file instead of stdout; errors and
+    /// warnings stay on stderr. The file is truncated unless --append is set
+    #[arg(long, value_name = "PATH")]
+    output_file: Option<PathBuf>,
+
+    /// With --output-file, append to the file instead of truncating it
+    #[arg(long, requires = "output_file")]
+    append: bool,
+
+    /// Append a JSON line to this file when the run starts and another when
+    /// it ends, with the outcome, versions and updated files
+    #[arg(long, value_name = "PATH")]
+    log_file: Option<PathBuf>,
+
+    /// When to color the output
+    #[arg(long, value_enum, value_name = "WHEN", default_value_t = ColorChoice::Auto)]
+    color: ColorChoice,
+
+    /// How VERSION is written: `plain` (bare version), `semver` (strict semver
+    /// and a trailing newline) or `auto` (keep the existing `v` prefix and newline)
+    #[arg(long, value_name = "FORMAT", default_value_t = VersionFileFormat::default())]
+    version_file_format: VersionFileFormat,
+
+    /// Glob of JSON files whose `version` field is bumped, e.g. 'package*.json';
+    /// replaces the automatic package.json update
+    #[arg(long, value_name = "GLOB")]
+    json_files: Option<String>,
+
+    /// Also update wasm-pack's generated pkg/package.json, which is never
+    /// auto-detected
+    #[arg(long)]
+    sync_wasm_pkg: bool,
+
+    /// Also update exact `=<old version>` requirements on the Cargo.toml
+    /// package from the other members of its Cargo workspace; the package's
+    /// own version must be bumped too
+    #[arg(long)]
+    update_deps: bool,
+
+    /// Rewrite version files even when their contents would not change, e.g.
+    /// to reapply JSON formatting
+    #[arg(long)]
+    force_write: bool,
+
+    /// Only read and update the files of one project type: `node` (package.json
+    /// and its lockfile), `php` (composer.json), `rust` (Cargo.toml), `python`
+    /// (pyproject.toml) or `all`
+    #[arg(long, value_name = "SCOPE", default_value_t = VersionScope::default())]
+    bump_scope: VersionScope,
+
+    /// Only read and update the version files given explicitly (--version-json,
+    /// --gemspec, --json-files, ...), never the auto-detected ones
+    #[arg(long)]
+    no_auto_detect: bool,
+
+    /// Never update files matching this glob, e.g. 'packages/legacy/*';
+    /// repeatable, and added to `ignore_files` in .semverrc.toml
+    #[arg(long = "ignore-files", value_name = "GLOB")]
+    ignore_files: Vec<String>,
+
+    /// Additional `.version.json`-style file to read and update
+    #[arg(long, value_name = "PATH")]
+    version_json: Option<PathBuf>,
+
+    /// Additional `VERSION.json`-style file holding integer major, minor and
+    /// patch fields, each updated in place
+    #[arg(long, value_name = "PATH")]
+    version_json_structured: Option<PathBuf>,
+
+    /// Azure Pipelines file whose `variables.version` is also bumped
+    #[arg(long, value_name = "PATH")]
+    azure_pipelines: Option<PathBuf>,
+
+    /// Ruby gemspec to bump; detected when the project has exactly one
+    #[arg(long, value_name = "PATH")]
+    gemspec: Option<PathBuf>,
+
+    /// CocoaPods podspec (`.podspec` or `.podspec.json`) to bump, along with
+    /// its entries in `Podfile.lock`; detected when the project has exactly one
+    #[arg(long, value_name = "PATH")]
+    podspec: Option<PathBuf>,
+
+    /// .NET project file whose `<Version>` (or `<VersionPrefix>`) is bumped
+    /// [default: the first *.csproj with a version, else Directory.Build.props]
+    #[arg(long, value_name = "PATH")]
+    csproj: Option<PathBuf>,
+
+    /// fastlane Fastfile whose top-level `version = "..."` is bumped
+    #[arg(long, value_name = "PATH")]
+    fastfile: Option<PathBuf>,
+
+    /// Also replace `version` assignments inside Fastfile lanes
+    #[arg(long, requires = "fastfile")]
+    all_occurrences: bool,
+
+    /// Helm chart whose `version` and `appVersion` are bumped, each from its
+    /// own value [default: Chart.yaml when present]
+    #[arg(long, value_name = "PATH")]
+    helm_chart: Option<PathBuf>,
+
+    /// Bump only the chart's own `version`, not `appVersion`
+    #[arg(long, conflicts_with_all = ["helm_app_version_only", "helm_app_bump"])]
+    helm_chart_version_only: bool,
+
+    /// Bump only the chart's `appVersion`, not its own `version`
+    #[arg(long, conflicts_with = "helm_chart_bump")]
+    helm_app_version_only: bool,
+
+    /// Bump type for the chart's `version` [default: the --bump type]
+    #[arg(long, value_name = "TYPE")]
+    helm_chart_bump: Option<BumpType>,
+
+    /// Bump type for the chart's `appVersion` [default: the --bump type]
+    #[arg(long, value_name = "TYPE")]
+    helm_app_bump: Option<BumpType>,
+
+    /// Elixir project file to bump [default: mix.exs when present]
+    #[arg(long, value_name = "PATH")]
+    mix_exs: Option<PathBuf>,
+
+    /// Makefile with a `VERSION` assignment [default: GNUmakefile, makefile or Makefile when present]
+    #[arg(long, value_name = "PATH")]
+    makefile: Option<PathBuf>,
+
+    /// Snap project file to bump [default: snapcraft.yaml or snap/snapcraft.yaml when present]
+    #[arg(long, value_name = "PATH")]
+    snapcraft: Option<PathBuf>,
+
+    /// Cargo manifest whose [package] version to bump [default: Cargo.toml when present, with --bump-scope rust or --update-deps]
+    #[arg(long, value_name = "PATH")]
+    cargo_toml: Option<PathBuf>,
+
+    /// Python project file to bump [default: pyproject.toml when present, with --bump-scope python]
+    #[arg(long, value_name = "PATH")]
+    pyproject: Option<PathBuf>,
+
+    /// Indentation of rewritten JSON files: 0-8 spaces or `tab`
+    #[arg(long, value_name = "N|tab", default_value_t = JsonIndent::default())]
+    json_indent: JsonIndent,
+
+    /// After writing, GPG-sign this file into `<PATH>.asc`; with --git-commit the
+    /// signature is committed too
+    #[arg(long, value_name = "PATH")]
+    sign_file: Option<PathBuf>,
+
+    /// Key to sign --sign-file with instead of GPG's default key
+    #[arg(long, value_name = "KEY_ID", requires = "sign_file")]
+    sign_key: Option<String>,
+
+    /// Verify the existing signature of --sign-file before bumping
+    #[arg(long, requires = "sign_file")]
+    verify_sign: bool,
+
+    /// After writing, copy each updated file to the same relative path under
+    /// this existing directory
+    #[arg(long, value_name = "DEST_DIR")]
+    mirror: Option<PathBuf>,
+
+    /// Workspace definition (TOML) listing member directories to bump as well
+    #[arg(long, value_name = "PATH")]
+    workspace_file: Option<PathBuf>,
+
+    /// With --workspace-file, list the members and their versions but only
+    /// bump the root
+    #[arg(long, requires = "workspace_file")]
+    workspace_root_only: bool,
+
+    /// With --workspace-file, choose the bump type of each member
+    /// interactively, or `skip` to leave it alone, then confirm them together
+    #[arg(
+        long,
+        requires = "workspace_file",
+        conflicts_with = "workspace_root_only"
+    )]
+    interactive_workspace_bump: bool,
+
+    /// Choose interactively which of the detected version files is read from
+    #[arg(long)]
+    interactive_source_priority: bool,
+
+    /// Read the current version from this file, or from stdin with `-`,
+    /// instead of the detected version files
+    #[arg(
+        long,
+        value_name = "PATH",
+        conflicts_with = "interactive_source_priority"
+    )]
+    from: Option<PathBuf>,
+
+    /// Read the current version from this environment variable instead of
+    /// the detected version files; the files are still updated
+    #[arg(long, value_name = "VAR_NAME", conflicts_with_all = ["from", "interactive_source_priority"])]
+    version_in_env_var: Option<String>,
+
+    /// Use the value of this environment variable as the new version's build
+    /// metadata, e.g. BUILD_NUMBER=42 gives 1.2.3+42; skipped when unset or empty
+    #[arg(
+        long,
+        value_name = "VAR",
+        conflicts_with_all = ["sanitize", "clean_pre_release"]
+    )]
+    build_metadata_from_env: Option<String>,
+
+    /// Prepend this to the --build-metadata-from-env value, e.g. `build.`
+    #[arg(long, value_name = "PREFIX", requires = "build_metadata_from_env")]
+    build_metadata_prefix: Option<String>,
+
+    /// How the --from input is parsed: `raw` (a bare version), `json`,
+    /// `toml` or `yaml`
+    #[arg(long, value_name = "FORMAT", default_value_t = InputFormat::default(), requires = "from")]
+    input_format: InputFormat,
+
+    /// Dotted key of the version in --input-format json input
+    #[arg(long, value_name = "KEY", default_value = "version")]
+    json_key: String,
+
+    /// Dotted key of the version in --input-format toml input
+    #[arg(long, value_name = "KEY", default_value = "version")]
+    toml_key: String,
+
+    /// Dotted key of the version in --input-format yaml input
+    #[arg(long, value_name = "KEY", default_value = "version")]
+    yaml_key: String,
+
+    /// Write the primary source's version to every other version file, without bumping
+    #[arg(long, conflicts_with_all = ["bump", "pre_release", "check"])]
+    sanitize: bool,
+
+    /// Print the version the given bump would produce and exit without
+    /// changing anything; the same as `semver next <TYPE>`
+    #[arg(long, value_name = "TYPE")]
+    print_next: Option<BumpType>,
+
+    /// Strip the pre-release and build metadata from the current version,
+    /// e.g. 1.2.3-beta.1 → 1.2.3, without bumping; exits 4 if there are none
+    #[arg(long, conflicts_with_all = ["bump", "pre_release", "sanitize", "check"])]
+    clean_pre_release: bool,
+
+    /// Apply the bump types listed in this file one after another, one per
+    /// line; `#` starts a comment line
+    #[arg(
+        long,
+        value_name = "PATH",
+        conflicts_with_all = ["bump", "pre_release", "sanitize", "check", "clean_pre_release"]
+    )]
+    batch_file: Option<PathBuf>,
+
+    /// Check that every detected version source is valid and agrees, then exit
+    #[arg(long)]
+    check: bool,
+
+    /// With --check, only validate the primary (first detected) source
+    #[arg(long, requires = "check")]
+    check_only_primary: bool,
+
+    /// List the version files that differ from the primary source, then exit;
+    /// exits 7 if any differ
+    #[arg(long, conflicts_with = "check")]
+    diff_files: bool,
+
+    /// Exit with code 18 when a pre-release would be bumped to another pre-release
+    #[arg(long)]
+    fail_on_prerelease: bool,
+
+    /// Allow a pre-release to pre-release bump despite --fail-on-prerelease
+    #[arg(long)]
+    force_prerelease: bool,
+
+    /// Reject versions that are not exactly semver 2.0.0 (exit code 3)
+    #[arg(long)]
+    strict_semver: bool,
+
+    /// YAML list of {file, pattern, replacement} regex rewrites to apply as well
+    #[arg(long, value_name = "PATH")]
+    patch_file: Option<PathBuf>,
+
+    /// File in which --version-placeholder (or, with --replace-old-version,
+    /// the current version) is replaced with the new version; repeatable
+    #[arg(long = "file", value_name = "PATH", requires = "file_replacement")]
+    file: Vec<PathBuf>,
+
+    /// Literal string such as %%VERSION%% to replace with the new version in
+    /// every --file
+    #[arg(long, value_name = "PLACEHOLDER", requires = "file", value_parser = parse_placeholder)]
+    version_placeholder: Option<String>,
+
+    /// Replace every occurrence of the current version in each --file with
+    /// the new one
+    #[arg(long, requires = "file")]
+    replace_old_version: bool,
+
+    /// Give the new version this pre-release identifier, e.g. alpha
+    #[arg(long, value_name = "ID")]
+    pre_release: Option<String>,
+
+    /// Use the last component of the current git branch as the pre-release
+    /// identifier, e.g. release/alpha → alpha; --pre-release is the fallback on
+    /// main, master, develop or names with no usable characters
+    #[arg(
+        long,
+        conflicts_with_all = ["clean_pre_release", "batch_file", "bump_pre_then_release", "sanitize"]
+    )]
+    pre_release_from_branch: bool,
+
+    /// Bump to the first pre-release of the next version, then to the release
+    /// itself, e.g. 1.0.0 → 1.1.0-rc.1 → 1.1.0; with --git-commit each step is
+    /// its own commit, and both are undone if either commit fails
+    #[arg(
+        long,
+        value_name = "ID",
+        num_args = 0..=1,
+        default_missing_value = "rc",
+        value_parser = parse_prerelease_id,
+        conflicts_with_all = [
+            "pre_release",
+            "batch_file",
+            "sanitize",
+            "clean_pre_release",
+            "check",
+            "squash_commits",
+            "workspace_file",
+            "helm_chart_bump",
+            "helm_app_bump"
+        ]
+    )]
+    bump_pre_then_release: Option<String>,
+
+    /// Layout of the pre-release: {id} is the identifier, {n} the counter ({n:03} pads)
+    #[arg(
+        long,
+        value_name = "FORMAT",
+        default_value = DEFAULT_PRERELEASE_FORMAT,
+        value_parser = parse_prerelease_format
+    )]
+    pre_release_format: String,
+
+    /// Separator between pre-release identifier and counter: '.', '-' or ''
+    /// (alpha.1, alpha-1, alpha1); existing counters are read in any style
+    #[arg(
+        long,
+        value_name = "CHAR",
+        conflicts_with = "pre_release_format",
+        value_parser = parse_prerelease_separator
+    )]
+    pre_release_separator: Option<String>,
+
+    /// Keep the patch number on minor and major bumps, e.g. 1.2.3 → 1.3.3
+    #[arg(long)]
+    no_reset_patch: bool,
+
+    /// Keep the minor number on major bumps, e.g. 1.2.3 → 2.2.0
+    #[arg(long)]
+    no_reset_minor: bool,
+
+    /// Switch to calendar versioning: major becomes the year, minor the month
+    #[cfg(feature = "calver")]
+    #[arg(long)]
+    bump_date: bool,
+
+    /// Digits of the year used by --bump-date (2 or 4)
+    #[cfg(feature = "calver")]
+    #[arg(
+        long,
+        value_name = "N",
+        default_value_t = 4,
+        value_parser = parse_year_digits
+    )]
+    calver_year_digits: u8,
+
+    /// With --bump-date, increment patch for further releases in the same month
+    #[cfg(feature = "calver")]
+    #[arg(long, requires = "bump_date")]
+    calver_micro: bool,
+
+    /// Append a JSON line describing the bump to this file
+    #[arg(long, value_name = "PATH")]
+    report: Option<PathBuf>,
+
+    /// Append a Markdown summary of the bump to $GITHUB_STEP_SUMMARY when
+    /// running in GitHub Actions
+    #[arg(long)]
+    github_actions_summary: bool,
+
+    /// Prefix placed before the version in git tag names
+    #[arg(long, value_name = "PREFIX", default_value = "v", global = true)]
+    tag_prefix: String,
+
+    /// Exit with code 19 unless a git tag matches PATTERN (`*` and `?` are
+    /// wildcards, `{version}` is the current version) [default: <prefix><current-version>]
+    #[arg(long, value_name = "PATTERN")]
+    require_tag: Option<Option<String>>,
+
+    /// Only bump when a tracked file changed since the latest semver tag;
+    /// otherwise print a note and exit 0, e.g. when CI runs on every push
+    #[arg(long)]
+    bump_if_changed: bool,
+
+    /// Fail if a git tag for the new version already exists locally or on origin
+    #[arg(long)]
+    check_git_tag_exists: bool,
+
+    /// Run this shell command after writing; if it fails, restore the files and exit 16
+    #[arg(long, value_name = "COMMAND")]
+    verify_after: Option<String>,
+
+    /// Commit the updated version files
+    #[arg(long)]
+    git_commit: bool,
+
+    /// With --git-commit, commit only the files this run wrote (the default).
+    /// Anything you staged beforehand, e.g. with `git add -p`, stays staged
+    /// and out of the bump commit
+    #[arg(
+        long,
+        requires = "git_commit",
+        conflicts_with_all = ["include_all_modified", "commit_all", "squash_commits"]
+    )]
+    commit_only_version_files: bool,
+
+    /// With --git-commit, stage every modified tracked file (`git add -u`),
+    /// not just the version files; unrelated local changes are committed too
+    #[arg(long, visible_alias = "commit-everything", requires = "git_commit")]
+    include_all_modified: bool,
+
+    /// With --git-commit, stage every change including untracked files (`git add -A`).
+    /// Dangerous: anything in the working tree is committed; preview with --dry-run
+    #[arg(long, requires = "git_commit", conflicts_with = "include_all_modified")]
+    commit_all: bool,
+
+    /// With --git-commit, add this scope to the commit message,
+    /// e.g. `chore(release): bump version to 1.2.4`
+    #[arg(
+        long,
+        value_name = "SCOPE",
+        requires = "git_commit",
+        value_parser = parse_commit_scope
+    )]
+    message_scope: Option<String>,
+
+    /// With --git-commit, squash the last N commits (or `auto`: every commit
+    /// since the latest semver tag) into the bump commit
+    #[arg(
+        long,
+        value_name = "N|auto",
+        requires = "git_commit",
+        value_parser = parse_squash_commits
+    )]
+    squash_commits: Option<SquashCommits>,
+
+    /// Handlebars template for the commit and tag messages, rendered with
+    /// old_version, new_version, bump_type, files_updated, date, branch and
+    /// git_sha; unknown variables warn and render empty
+    #[arg(
+        long,
+        value_name = "PATH",
+        conflicts_with_all = ["message_scope", "tag_message_file"]
+    )]
+    template_file: Option<PathBuf>,
+
+    /// With --template-file, the `git log` pretty-format of each entry of
+    /// `commits`, e.g. '%an: %s'; rendered as `log_line` (default: '%s')
+    #[arg(
+        long,
+        value_name = "FORMAT",
+        requires = "template_file",
+        value_parser = parse_log_format
+    )]
+    git_log_format: Option<String>,
+
+    /// With --git-commit, append a git trailer such as
+    /// 'Signed-off-by: Jane <jane@example.com>' to the message; repeatable
+    #[arg(
+        long,
+        value_name = "KEY: VALUE",
+        requires = "git_commit",
+        value_parser = parse_trailer
+    )]
+    commit_trailer: Vec<(String, String)>,
+
+    /// Create an annotated git tag for the new version on HEAD
+    #[arg(long)]
+    git_tag_annotated: bool,
+
+    /// Read the annotated tag message from this file (like `git tag -F`)
+    #[arg(long, value_name = "PATH", requires = "git_tag_annotated")]
+    tag_message_file: Option<PathBuf>,
+
+    /// Create a lightweight tag for the new version and attach this text to it
+    /// as a git note (`git notes add`); notes are not pushed unless --push-notes
+    #[arg(long, value_name = "TEXT", conflicts_with = "git_tag_annotated")]
+    tag_description: Option<String>,
+
+    /// With --tag-description, push the notes to origin (`refs/notes/commits`)
+    #[arg(long, requires = "tag_description")]
+    push_notes: bool,
+
+    /// With --git-tag-annotated, move the tag if it already exists (`git tag -f`),
+    /// e.g. for floating `dev` or `nightly` tags
+    #[arg(
+        long,
+        requires = "git_tag_annotated",
+        conflicts_with = "check_git_tag_exists"
+    )]
+    tag_force: bool,
+
+    /// Fail with exit code 23 unless CHANGELOG.md has a `## [<new version>]`
+    /// entry, at --changelog-header-level, checked before anything is written
+    #[arg(long)]
+    require_changelog_entry: bool,
+
+    /// Markdown heading level (1-6) of a changelog release, e.g. 3 for
+    /// `### [1.2.3]`
+    #[arg(long, value_name = "N", default_value_t = 2, value_parser = parse_heading_level)]
+    changelog_header_level: u8,
+
+    /// Markdown heading level (1-6) of the feat/fix/chore subsections of a
+    /// changelog release; must be deeper than --changelog-header-level
+    #[arg(long, value_name = "N", default_value_t = 3, value_parser = parse_heading_level)]
+    changelog_subheader_level: u8,
+
+    /// With --tag-force, verify the GPG signature of the existing tag (`git tag -v`)
+    /// before anything is written; exits 22 if it cannot be verified
+    #[arg(long, requires = "tag_force")]
+    tag_verify: bool,
+
+    /// Run the --git-commit and --git-tag-annotated steps as a dependency
+    /// graph, starting git steps that do not depend on each other concurrently
+    #[cfg(feature = "parallel-git")]
+    #[arg(
+        long,
+        conflicts_with_all = ["squash_commits", "commit_all", "include_all_modified"]
+    )]
+    parallel_git_ops: bool,
+
+    /// Credential helper for git's remote operations, passed as
+    /// `git -c credential.helper=<CMD>` to every git command
+    #[arg(long, value_name = "CMD")]
+    git_credential_helper: Option<String>,
+
+    /// Token for HTTPS GitHub remotes [default: $SEMVER_GITHUB_TOKEN or $GITHUB_TOKEN]
+    #[arg(long, value_name = "TOKEN")]
+    github_token: Option<String>,
+
+    /// Abort with exit code 17 unless run inside a git repository
+    #[arg(long)]
+    require_git: bool,
+
+    /// Only bump on a branch matching this name or glob, e.g. 'release/*'
+    #[arg(long, value_name = "NAME")]
+    branch: Option<String>,
+
+    /// Only update version files that already have staged changes, then stage them again
+    #[arg(long)]
+    staged_only: bool,
+
+    /// After writing, fail if git reports modified files other than the updated ones
+    #[arg(long)]
+    assert_clean_tree_after: bool,
+
+    /// Abort before writing if more than this many files would be updated
+    #[arg(long, value_name = "N")]
+    max_files: Option<usize>,
+
+    /// Create a GitHub Release for the new tag (needs GITHUB_TOKEN)
+    #[arg(long)]
+    github_release: bool,
+
+    /// Create the GitHub Release as a draft
+    #[arg(long, requires = "github_release")]
+    github_release_draft: bool,
+
+    /// Mark the GitHub Release as a pre-release
+    #[arg(long, requires = "github_release")]
+    github_release_prerelease: bool,
+
+    /// Hold an exclusive lock on this file while reading and writing versions
+    #[arg(long, value_name = "PATH", num_args = 0..=1, default_missing_value = ".semver.lock")]
+    lockfile: Option<PathBuf>,
+
+    /// Seconds to wait for the lock before giving up
+    #[arg(long, value_name = "SECONDS", default_value_t = 30)]
+    lock_timeout: u64,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Print the kind of change between two versions
+    Diff {
+        /// Version before the change
+        old: String,
+        /// Version after the change
+        new: String,
+    },
+    /// Print the version a bump would produce, without changing anything
+    Next {
+        /// Bump to preview: major, minor or patch
+        bump: BumpType,
+    },
+    /// Print the bumps recorded in VERSION.history
+    History,
+    /// Check the .semver-audit hash chain written by --audit-trail
+    Audit {
+        /// Verify that no entry was changed, removed or reordered
+        #[arg(long, required = true)]
+        verify: bool,
+    },
+    /// List the git tags that are semver versions, latest first
+    Tags {
+        /// Only show the N most recent tags
+        #[arg(long, value_name = "N")]
+        limit: Option<usize>,
+        /// Print a JSON array instead of one tag per line
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+fn parse_prerelease_format(fmt: &str) -> Result<String, String> {
+    validate_prerelease_format(fmt).map(|_| fmt.to_string())
+}
+
+fn parse_log_format(format: &str) -> Result<String, String> {
+    validate_log_format(format)
+        .map(|_| format.to_string())
+        .map_err(|err| err.to_string())
+}
+
+fn parse_prerelease_separator(separator: &str) -> Result<String, String> {
+    separator_format(separator).map(|_| separator.to_string())
+}
+
+#[cfg(feature = "calver")]
+fn parse_year_digits(digits: &str) -> Result<u8, String> {
+    match digits {
+        "2" => Ok(2),
+        "4" => Ok(4),
+        _ => Err("expected 2 or 4".to_string()),
+    }
+}
+
+fn parse_prerelease_id(id: &str) -> Result<String, String> {
+    semver::Prerelease::new(&format!("{}.1", id))
+        .map(|_| id.to_string())
+        .map_err(|_| format!("invalid pre-release identifier '{}'", id))
+}
+
+fn parse_placeholder(placeholder: &str) -> Result<String, String> {
+    if placeholder.is_empty() {
+        return Err("the placeholder must not be empty".to_string());
+    }
+    Ok(placeholder.to_string())
+}
+
+fn parse_commit_scope(scope: &str) -> Result<String, String> {
+    validate_commit_scope(scope).map(|_| scope.to_string())
+}
+
+/// How many commits `--squash-commits` folds into the bump commit.
+#[derive(Clone, Copy)]
+enum SquashCommits {
+    Count(usize),
+    /// Every commit since the latest semver tag.
+    Auto,
+}
+
+fn parse_squash_commits(value: &str) -> Result<SquashCommits, String> {
+    match value {
+        "auto" => Ok(SquashCommits::Auto),
+        _ => value
+            .parse()
+            .map(SquashCommits::Count)
+            .map_err(|_| "expected a number of commits or 'auto'".to_string()),
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum ColorChoice {
+    Auto,
+    Always,
+    Never,
+}
+
+impl ColorChoice {
+    /// Resolve `auto` against the terminal, `NO_COLOR` and `TERM=dumb`.
+    fn enabled(self) -> bool {
+        match self {
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+            ColorChoice::Auto => {
+                io::stdout().is_terminal()
+                    && std::env::var_os("NO_COLOR").is_none_or(|v| v.is_empty())
+                    && std::env::var("TERM").map_or(true, |term| term != "dumb")
+            }
+        }
+    }
+}
+
+/// How much detail to report about individual file operations.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Verbosity {
+    Normal,
+    Verbose,
+}
+
+/// Result of a single `update_*` call, used for the verbose log.
+enum FileOutcome {
+    Updated,
+    Skipped(&'static str),
+}
+
+/// Decide the bump type for the root project, prompting when none was given.
+fn select_bump_type(args: &Args, config: &Config) -> String {
+    #[cfg(feature = "calver")]
+    if args.bump_date {
+        if let Some(bump @ ("major" | "minor")) = args.bump.as_deref() {
+            eprintln!(
+                "--bump-date sets major and minor itself; it cannot be combined with --bump {bump}."
+            );
+            exit(1);
+        }
+        return "calver".to_string();
+    }
+    args.bump
+        .clone()
+        .or_else(|| config.default_bump.clone())
+        .unwrap_or_else(prompt_bump_type)
+}
+
+/// `config` with the command-line flags that override or extend it applied.
+fn resolved_config(args: &Args, config: Config) -> Config {
+    let ignore_files: Vec<String> = config
+        .ignore_files
+        .into_iter()
+        .flatten()
+        .chain(args.ignore_files.iter().cloned())
+        .collect();
+    Config {
+        default_bump: args.bump.clone().or(config.default_bump),
+        ignore_files: (!ignore_files.is_empty()).then_some(ignore_files),
+        ..config
+    }
+}
+
+/// Append the bump to the GitHub Actions step summary, or warn when there
+/// is no `$GITHUB_STEP_SUMMARY` to append to.
+fn write_step_summary(old: &str, new: &str, bump_type: &str, updated: &[PathBuf]) {
+    let Some(path) = std::env::var_os("GITHUB_STEP_SUMMARY").filter(|path| !path.is_empty()) else {
+        eprintln!("Warning: GITHUB_STEP_SUMMARY is not set; skipping the step summary.");
+        return;
+    };
+    let path = PathBuf::from(path);
+    let entry = ReportEntry::new(old, new, bump_type, updated);
+    if let Err(err) = write_github_step_summary(&path, &entry) {
+        eprintln!(
+            "Failed to append step summary to {}: {}",
+            path.display(),
+            err
+        );
+        exit(1);
+    }
+}
+
+fn prompt_bump_type() -> String {
+    prompt_bump_choice("What would you like to bump?", &["major", "minor", "patch"])
+}
+
+/// Ask `question` with `choices`, defaulting to `patch`. Without a terminal
+/// the answer is read as a line from stdin.
+fn prompt_bump_choice(question: &str, choices: &[&'static str]) -> String {
+    let default = choices
+        .iter()
+        .position(|&choice| choice == "patch")
+        .unwrap_or(0);
+
+    match Select::new(question, choices.to_vec())
+        .with_starting_cursor(default)
+        .prompt()
+    {
+        Ok(choice) => choice.to_string(),
+        Err(InquireError::OperationCanceled) | Err(InquireError::OperationInterrupted) => {
+            // Exit cleanly on Ctrl+C or ESC
+            exit(130); // 130 = standard exit code for SIGINT
+        }
+        Err(_) => {
+            std::println!(
+                "{} [{}] (default: {}): ",
+                question,
+                choices.join("|"),
+                choices[default]
+            );
+            print!("> ");
+            io::stdout().flush().unwrap();
+
+            let mut input = String::new();
+            io::stdin().read_line(&mut input).unwrap();
+            let trimmed = input.trim();
+
+            match choices.iter().find(|&&choice| choice == trimmed) {
+                Some(choice) => choice.to_string(),
+                None if trimmed.is_empty() => choices[default].to_string(),
+                None => {
+                    eprintln!("Invalid input. Defaulting to {}.", choices[default]);
+                    choices[default].to_string()
+                }
+            }
+        }
+    }
+}
+
+/// Ask for the bump type of each of `members`, in order. Members answered
+/// with `skip` are left out of the result.
+fn collect_workspace_bump_selections(members: &[WorkspaceMember]) -> HashMap<PathBuf, BumpType> {
+    let mut selections = HashMap::new();
+    for member in members {
+        let question = format!("What would you like to bump in {}?", member.path.display());
+        let choice = prompt_bump_choice(&question, &["major", "minor", "patch", "skip"]);
+        if let Ok(bump) = choice.parse::<BumpType>() {
+            selections.insert(member.path.clone(), bump);
+        }
+    }
+    selections
+}
+
+/// Warn about the major bump from `current` to `new` and make sure it is
+/// meant: `--allow-major-bump` proceeds, `--ci` fails with exit code 20,
+/// and otherwise the user has to type `yes`.
+fn confirm_major_bump(args: &Args, current: &Version, new: &Version) {
+    let warning = format!(
+        "WARNING: You are about to bump the MAJOR version from {}.x.x to {}",
+        current.major, new
+    );
+    if args.color.enabled() {
+        eprintln!("{}", warning.yellow().bold());
+    } else {
+        eprintln!("{}", warning);
+    }
+
+    if let Err(err) = validate_major_bump(current, new, args.allow_major_bump || !args.ci) {
+        eprintln!("{}", err);
+        exit(err.exit_code());
+    }
+    if args.allow_major_bump {
+        return;
+    }
+
+    eprint!("Type 'yes' to continue: ");
+    let _ = io::stderr().flush();
+    let mut input = String::new();
+    let _ = io::stdin().read_line(&mut input);
+    if input.trim() != "yes" {
+        println!("Major bump cancelled; no files were changed.");
+        exit(0);
+    }
+}
+
+/// Ask a yes/no `question`, e.g. whether the shown changes should be
+/// written; anything but yes declines.
+fn confirm(question: &str) -> bool {
+    match Confirm::new(question).with_default(false).prompt() {
+        Ok(answer) => answer,
+        Err(InquireError::OperationCanceled) | Err(InquireError::OperationInterrupted) => false,
+        Err(_) => {
+            print!("{} [y/N]: ", question);
+            io::stdout().flush().unwrap();
+
+            let mut input = String::new();
+            io::stdin().read_line(&mut input).unwrap();
+            matches!(input.trim(), "y" | "Y" | "yes")
+        }
+    }
+}
+
+fn main() {
+    let args = Args::parse();
+    if let Some(path) = &args.log_file {
+        let args: Vec<String> = std::env::args().collect();
+        let logger = StructuredLogger::open(path).and_then(|mut logger| {
+            logger.log_start(&args)?;
+            Ok(logger)
+        });
+        match logger {
+            Ok(logger) => audit::install(logger),
+            Err(err) => {
+                eprintln!("Failed to open {}: {}", path.display(), err);
+                exit(1);
+            }
+        }
+    }
+
+    run(args);
+    audit::finish(false);
+}
+
+fn run(mut args: Args) {
+    configure_git(&args);
+    if let Some(path) = &args.output_file {
+        match output::open_log(path, args.append) {
+            Ok(sink) => output::redirect(sink),
+            Err(err) => {
+                eprintln!("Failed to open {}: {}", path.display(), err);
+                exit(1);
+            }
+        }
+    }
+
+    match &args.command {
+        Some(Command::Diff { old, new }) => return run_diff(old, new),
+        Some(Command::Next { bump }) => return run_next(&args, *bump),
+        Some(Command::History) => return run_history(),
+        Some(Command::Audit { .. }) => return run_audit_verify(),
+        Some(Command::Tags { limit, json }) => return run_tags(&args.tag_prefix, *limit, *json),
+        None => {}
+    }
+    if let Some(bump) = args.print_next {
+        return run_next(&args, bump);
+    }
+    if args.pre_release_from_branch {
+        args.pre_release = Some(pre_release_from_branch(args.pre_release.take()));
+    }
+    let changelog =
+        ChangelogConfig::new(args.changelog_header_level, args.changelog_subheader_level)
+            .unwrap_or_else(|message| {
+                eprintln!("Invalid changelog heading levels: {}", message);
+                exit(1);
+            });
+
+    let _lock = args.lockfile.as_ref().map(|path| {
+        LockGuard::acquire(path, Duration::from_secs(args.lock_timeout)).unwrap_or_else(|err| {
+            eprintln!("Could not acquire lock {}: {}", path.display(), err);
+            exit(12);
+        })
+    });
+
+    let config =
+        resolve_config(args.global_config.as_deref(), Path::new("")).unwrap_or_else(|err| {
+            eprintln!("{}", err);
+            exit(err.exit_code());
+        });
+
+    if args.print_config {
+        println!("{}", resolved_config(&args, config).to_string().trim_end());
+        return;
+    }
+
+    if args.no_auto_detect && !has_explicit_version_files(&args) {
+        eprintln!(
+            "--no-auto-detect needs at least one version file given explicitly, e.g. --version-json."
+        );
+        exit(21);
+    }
+
+    if args.require_git && !git::is_git_repo(Path::new(".")) {
+        let err = BumpError::NotAGitRepository(std::env::current_dir().unwrap_or_default());
+        eprintln!("{}", err);
+        exit(err.exit_code());
+    }
+
+    if let Some(pattern) = &args.branch {
+        check_branch(pattern);
+    }
+
+    if args.check {
+        return run_check(&args);
+    }
+
+    if args.diff_files {
+        return run_diff_files(&args);
+    }
+
+    let found = if let Some(path) = &args.from {
+        Some(read_from_input(&args, path))
+    } else if args.interactive_source_priority {
+        prompt_source_priority(version_sources(&args))
+            .into_iter()
+            .next()
+    } else {
+        get_current_version(&args)
+    };
+    let VersionSource {
+        path: version_source,
+        version: current_version,
+    } = found.unwrap_or_else(|| {
+        if args.bump_scope != VersionScope::All {
+            eprintln!("No version found in {} files.", args.bump_scope);
+            exit(1);
+        }
+        eprintln!("No version found in composer.json, package.json, or VERSION file.");
+        exit(1);
+    });
+
+    if args.print_version {
+        print_version(&current_version, args.no_newline);
+        return;
+    }
+    if args.no_newline {
+        eprintln!(
+            "Warning: --no-newline only applies to --print-version, --print-next and next; ignoring it."
+        );
+    }
+    for (set, flag) in [
+        (args.no_reset_patch, "--no-reset-patch"),
+        (args.no_reset_minor, "--no-reset-minor"),
+    ] {
+        if set {
+            eprintln!(
+                "Warning: {} does not follow semver, which resets lower numbers on a bump.",
+                flag
+            );
+        }
+    }
+
+    if let Some(pattern) = &args.require_tag {
+        check_version_is_tagged(pattern.as_deref(), &args.tag_prefix, &current_version);
+    }
+
+    if args.bump_if_changed {
+        skip_unless_changed(&args.tag_prefix);
+    }
+
+    if args.strict_semver {
+        if let Err(err) = validate_strict_semver(&current_version, &version_source) {
+            eprintln!("{}", err);
+            exit(err.exit_code());
+        }
+    }
+
+    let version = Version::parse(&current_version).unwrap_or_else(|_| {
+        let err = BumpError::InvalidVersion {
+            path: version_source.clone(),
+            version: current_version.clone(),
+        };
+        eprintln!("{}", err);
+        exit(err.exit_code());
+    });
+    println!("Current version: {}", version);
+
+    // --sanitize rewrites the primary version everywhere instead of bumping it.
+    let out_of_sync: Vec<VersionSource> = if args.sanitize {
+        version_sources(&args)
+            .into_iter()
+            .filter(|source| source.version != current_version)
+            .collect()
+    } else {
+        Vec::new()
+    };
+    let (bump_type, mut version) = if args.sanitize {
+        ("sanitize".to_string(), version)
+    } else if args.clean_pre_release {
+        let clean = clean_prerelease(&version).unwrap_or_else(|err| {
+            eprintln!("{}", err);
+            exit(err.exit_code());
+        });
+        ("clean".to_string(), clean)
+    } else {
+        let (bump_type, new) = match (&args.batch_file, &args.bump_pre_then_release) {
+            (Some(path), _) => bump_batch(&version, path, &args),
+            (None, Some(pre_id)) => {
+                let bump_type = select_bump_type(&args, &config);
+                let bump = bump_type.parse::<BumpType>().unwrap_or_else(|_| {
+                    eprintln!("--bump-pre-then-release needs a major, minor or patch bump.");
+                    exit(1);
+                });
+                (bump_type, perform_staged_bump(&version, bump, pre_id).1)
+            }
+            (None, None) => {
+                let bump_type = select_bump_type(&args, &config);
+                let new = bump_version(version.clone(), &bump_type, &args);
+                (bump_type, new)
+            }
+        };
+        let policy = PrereleasePolicy {
+            fail_on_prerelease: args.fail_on_prerelease,
+            force: args.force_prerelease,
+        };
+        if let Err(err) = validate_prerelease_policy(&version, &new, &policy) {
+            eprintln!("{}", err);
+            exit(err.exit_code());
+        }
+        if args.warn_major_bump && is_major_bump(&version, &new) {
+            confirm_major_bump(&args, &version, &new);
+        }
+        if args.interactive_bump_preview {
+            println!("→ Next version will be: {}", new);
+            if !confirm("Bump to this version?") {
+                println!("No files were changed.");
+                exit(0);
+            }
+        }
+        (bump_type, new)
+    };
+
+    if let Some(var) = &args.build_metadata_from_env {
+        if let Some(build) = build_metadata_from_env(var, args.build_metadata_prefix.as_deref()) {
+            version.build = build;
+        }
+    }
+
+    let floors = FloorConfig {
+        major: args.min_major,
+        minor: args.min_minor,
+        patch: args.min_patch,
+    };
+    if let Err(err) = validate_component_floors(&version, &floors) {
+        eprintln!("{}", err);
+        exit(err.exit_code());
+    }
+
+    let new_version = version.to_string();
+    audit::record(|logger| logger.record_bump(&current_version, &new_version));
+    if args.sanitize {
+        println!(
+            "Syncing version files to {} from {}",
+            new_version,
+            version_source.display()
+        );
+    } else if args.color.enabled() {
+        println!(
+            "Bumping version {} {} {}",
+            current_version.red(),
+            "→".yellow(),
+            new_version.green()
+        );
+    } else {
+        println!("Bumping version {} → {}", current_version, new_version);
+    }
+
+    if let Some(path) = &args.tag_message_file {
+        if !path.is_file() {
+            eprintln!("Tag message file {} does not exist.", path.display());
+            exit(1);
+        }
+    }
+
+    let template = args.template_file.as_ref().map(|path| {
+        let template = MessageTemplate::load(path).unwrap_or_else(|err| {
+            eprintln!("{}", err);
+            exit(err.exit_code());
+        });
+        (path.clone(), template)
+    });
+
+    if let Some(path) = args.sign_file.as_ref().filter(|_| args.verify_sign) {
+        if let Err(err) = verify_signature(path) {
+            eprintln!("{}", err);
+            exit(err.exit_code());
+        }
+    }
+
+    if args.require_changelog_entry {
+        check_changelog_entry(&changelog, &new_version);
+    }
+
+    if args.tag_verify {
+        verify_existing_tag(&format!("{}{}", args.tag_prefix, new_version));
+    }
+
+    if let Some(dir) = &args.mirror {
+        if !dir.is_dir() {
+            eprintln!("Mirror directory {} does not exist.", dir.display());
+            exit(1);
+        }
+    }
+
+    if args.check_git_tag_exists {
+        check_tag_is_new(&format!("{}{}", args.tag_prefix, new_version));
+    }
+
+    let squash = args
+        .squash_commits
+        .map(|squash| resolve_squash_count(squash, &args.tag_prefix));
+
+    let verbosity = if args.verbose {
+        Verbosity::Verbose
+    } else {
+        Verbosity::Normal
+    };
+    let staged = args.staged_only.then(|| {
+        staged_files(Path::new(".")).unwrap_or_else(|err| {
+            eprintln!("Could not list staged files: {}", err);
+            exit(1);
+        })
+    });
+    let dry_run = args.dry_run || args.simulate;
+    let mut updater = Updater {
+        dry_run,
+        simulate: args.simulate,
+        concurrent: args.concurrent_writes,
+        color: args.color.enabled(),
+        verbosity,
+        log: &mut io::stderr(),
+        staged,
+        ignored: args
+            .ignore_files
+            .iter()
+            .chain(config.ignore_files.iter().flatten())
+            .cloned()
+            .collect(),
+        force_write: args.force_write,
+        scope: args.bump_scope,
+        json_indent: args.json_indent,
+        version_file_format: args.version_file_format,
+        pending: Vec::new(),
+        updated: Vec::new(),
+    };
+
+    let mut current_version = current_version;
+    let mut staged_undo = None;
+    if let Some(pre_id) = &args.bump_pre_then_release {
+        let (pre, undo) =
+            commit_prerelease_step(&args, &mut updater, &bump_type, &current_version, pre_id);
+        current_version = pre;
+        staged_undo = undo;
+    }
+    queue_version_updates(
+        &args,
+        &bump_type,
+        &current_version,
+        &new_version,
+        &mut updater,
+    );
+
+    if let Err(err) = validate_file_count(&updater.pending_paths(), args.max_files) {
+        eprintln!("{}", err);
+        exit(err.exit_code());
+    }
+
+    if args.interactive_diff_confirm {
+        updater.print_pending_diffs();
+        if !confirm("Apply these changes?") {
+            println!("No files were changed.");
+            exit(0);
+        }
+    }
+
+    let rollback = match &args.verify_after {
+        Some(_) if !dry_run => Some(RollbackGuard::new(&updater.pending_paths()).unwrap_or_else(
+            |err| {
+                eprintln!("Could not back up files for --verify-after: {}", err);
+                exit(1);
+            },
+        )),
+        _ => None,
+    };
+
+    let hooks = config.file_hooks.as_deref().unwrap_or_default();
+    if !dry_run {
+        run_file_hooks(hooks, &updater.pending_paths(), |hook| {
+            hook.pre_update.as_deref()
+        });
+    }
+
+    let digests_before =
+        (args.audit_trail && !dry_run).then(|| file_digests(&updater.pending_paths()));
+    let failures = updater.flush();
+    audit::record(|logger| logger.record_files(&updater.updated));
+    exit_on_write_failures(&failures);
+
+    if !dry_run {
+        run_file_hooks(hooks, &updater.updated, |hook| hook.post_update.as_deref());
+    }
+
+    if args.sanitize {
+        print_sanitize_summary(&out_of_sync, &updater, &new_version);
+    }
+
+    if let (Some(command), Some(rollback)) = (&args.verify_after, rollback) {
+        if verify_bump(command) {
+            rollback.disarm();
+        } else {
+            drop(rollback);
+            eprintln!("Verification command failed; restored the previous version files.");
+            exit(16);
+        }
+    }
+
+    let signature = args.sign_file.as_ref().filter(|_| !dry_run).map(|path| {
+        let signature = sign_file(path, args.sign_key.as_deref()).unwrap_or_else(|err| {
+            eprintln!("{}", err);
+            exit(err.exit_code());
+        });
+        println!("Signed {} into {}", path.display(), signature.display());
+        signature
+    });
+
+    if let Some(dir) = args.mirror.as_ref().filter(|_| !dry_run) {
+        for path in &updater.updated {
+            if let Err(err) = mirror_file(path, dir) {
+                eprintln!("{}", err);
+                exit(err.exit_code());
+            }
+        }
+        println!(
+            "Mirrored {} files to {}",
+            updater.updated.len(),
+            dir.display()
+        );
+    }
+
+    if args.staged_only && !dry_run && !updater.updated.is_empty() {
+        if let Err(err) = git::add(&updater.updated, Path::new(".")) {
+            eprintln!("Failed to stage updated files: {}", err);
+            exit(1);
+        }
+    }
+
+    if args.assert_clean_tree_after && !dry_run {
+        assert_only_updated_files_changed(&updater.updated);
+    }
+
+    if args.append_version_history && !dry_run {
+        let entry = HistoryEntry {
+            timestamp: now_rfc3339(),
+            old: current_version.clone(),
+            new: new_version.clone(),
+            bump_type: bump_type.clone(),
+        };
+        if let Err(err) = append_history(Path::new(HISTORY_FILE), &entry) {
+            eprintln!("Failed to append to {}: {}", HISTORY_FILE, err);
+            exit(1);
+        }
+    }
+
+    if let Some(before) = digests_before {
+        let entry = AuditEntry {
+            timestamp: now_rfc3339(),
+            old: current_version.clone(),
+            new: new_version.clone(),
+            bump_type: bump_type.clone(),
+            user: audit_user(),
+            before,
+            after: file_digests(&updater.updated),
+        };
+        if let Err(err) = AuditChain::new(AUDIT_TRAIL_FILE).append(&entry) {
+            eprintln!("{}", err);
+            exit(err.exit_code());
+        }
+    }
+
+    if let Some(path) = &args.report {
+        if !dry_run {
+            let entry =
+                ReportEntry::new(&current_version, &new_version, &bump_type, &updater.updated);
+            if let Err(err) = append_report_entry(path, &entry) {
+                eprintln!("Failed to append report to {}: {}", path.display(), err);
+                exit(1);
+            }
+        }
+    }
+
+    if args.github_actions_summary && !dry_run {
+        write_step_summary(&current_version, &new_version, &bump_type, &updater.updated);
+    }
+
+    let mut committed = updater.updated.clone();
+    committed.extend(signature);
+
+    let rendered = template
+        .as_ref()
+        .filter(|_| !dry_run && (args.git_commit || args.git_tag_annotated))
+        .map(|(path, template)| {
+            let dir = Path::new(".");
+            let context = MessageContext {
+                old_version: current_version.clone(),
+                new_version: new_version.clone(),
+                bump_type: bump_type.clone(),
+                files_updated: committed.iter().map(|p| p.display().to_string()).collect(),
+                date: now_rfc3339()[..10].to_string(),
+                branch: get_current_branch(dir).unwrap_or_default(),
+                git_sha: git::head_sha(dir).unwrap_or_default(),
+                commits: template_commits(&args, &current_version),
+            };
+            render_message_template(path, template, &context)
+        });
+
+    #[cfg(feature = "parallel-git")]
+    let parallel_git_ops = args.parallel_git_ops && !dry_run;
+    #[cfg(not(feature = "parallel-git"))]
+    let parallel_git_ops = false;
+
+    if parallel_git_ops {
+        if let Some(undo) = staged_undo.take() {
+            undo.files.disarm();
+        }
+        #[cfg(feature = "parallel-git")]
+        run_git_plan(&args, &committed, &new_version, rendered.as_deref());
+    } else {
+        if args.git_commit && !dry_run {
+            commit_version_bump(
+                &args,
+                &committed,
+                &new_version,
+                squash,
+                rendered.as_deref(),
+                staged_undo.take(),
+            );
+        }
+
+        if args.git_tag_annotated && !dry_run {
+            create_version_tag(
+                &args,
+                &format!("{}{}", args.tag_prefix, new_version),
+                rendered.as_deref(),
+            );
+        }
+    }
+
+    if let Some(description) = args.tag_description.as_ref().filter(|_| !dry_run) {
+        describe_version_tag(
+            &format!("{}{}", args.tag_prefix, new_version),
+            description,
+            args.push_notes,
+        );
+    }
+
+    if args.github_release && !dry_run {
+        let tag = format!("{}{}", args.tag_prefix, new_version);
+        match publish_github_release(&args, &tag) {
+            Ok(url) => println!("Created GitHub release: {}", url),
+            Err(err) => {
+                eprintln!("{}", err);
+                exit(err.exit_code());
+            }
+        }
+    }
+
+    if dry_run {
+        exit(args.dry_run_exit_code);
+    }
+}
+
+/// Queue the writes that move every version file from `current_version` to
+/// `new_version`.
+fn queue_version_updates(
+    args: &Args,
+    bump_type: &str,
+    current_version: &str,
+    new_version: &str,
+    updater: &mut Updater,
+) {
+    match &args.json_files {
+        Some(pattern) if args.no_auto_detect => update_json_files(pattern, new_version, updater),
+        None if args.no_auto_detect => {}
+        // The glob decides whether package.json is bumped, so it is not
+        // auto-detected as well.
+        Some(pattern) => {
+            update_json_files(pattern, new_version, updater);
+            update_version_file(Path::new(""), new_version, updater);
+            update_composer_json(Path::new(""), new_version, updater);
+            update_version_json(Path::new(".version.json"), new_version, updater);
+            update_structured_version(Path::new(STRUCTURED_VERSION_FILE), new_version, updater);
+        }
+        None => update_project_files(Path::new(""), new_version, updater),
+    }
+
+    if let Some(path) = &args.version_json {
+        update_version_json(path, new_version, updater);
+    }
+
+    if args.sync_wasm_pkg {
+        update_json_version(WASM_PKG_MANIFEST, new_version, updater);
+    }
+
+    if let Some(path) = &args.version_json_structured {
+        update_structured_version(path, new_version, updater);
+    }
+
+    if let Some(path) = &args.azure_pipelines {
+        update_text_version(path, new_version, replace_azure_version, updater);
+    }
+
+    if let Some(path) = gemspec_path(args) {
+        update_text_version(&path, new_version, replace_gemspec_version, updater);
+    }
+
+    let podspec = podspec_path(args);
+    if let Some(path) = &podspec {
+        update_text_version(path, new_version, update_podspec_version, updater);
+        update_podfile_lock(path, read_podspec_name, new_version, updater);
+    }
+    // A JSON spec next to the Ruby one is kept in sync with it; the lock
+    // entries were already bumped above.
+    if let Some(path) = podspec_json_path(args) {
+        update_json_version(&path, new_version, updater);
+        if podspec.is_none() {
+            update_podfile_lock(&path, read_podspec_json_name, new_version, updater);
+        }
+    }
+
+    if let Some(path) = csproj_path(args) {
+        update_text_version(&path, new_version, update_csproj_version, updater);
+    }
+
+    if let Some(path) = helm_chart_path(args).filter(|_| !args.sanitize) {
+        update_helm_chart(&path, &helm_bump_config(args, bump_type), updater);
+    }
+
+    if let Some(path) = mix_exs_path(args) {
+        update_text_version(&path, new_version, replace_mix_version, updater);
+    }
+
+    if let Some(path) = makefile_path(args) {
+        update_text_version(&path, new_version, update_makefile_version, updater);
+    }
+
+    if let Some(path) = snapcraft_path(args) {
+        update_snapcraft(&path, new_version, updater);
+    }
+
+    if let Some(path) = cargo_toml_path(args) {
+        update_text_version(&path, new_version, update_package_version, updater);
+    }
+
+    if let Some(path) = pyproject_path(args) {
+        update_text_version(&path, new_version, update_pyproject_version, updater);
+    }
+
+    if let Some(path) = &args.fastfile {
+        let replace = if args.all_occurrences {
+            replace_all_fastfile_versions
+        } else {
+            replace_fastfile_version
+        };
+        update_text_version(path, new_version, replace, updater);
+    }
+
+    if let Some(path) = args.workspace_file.as_ref().filter(|_| !args.sanitize) {
+        bump_workspace_members(path, bump_type, args, updater);
+    }
+
+    if let Some(path) = args.patch_file.as_ref().filter(|_| !args.sanitize) {
+        apply_version_patches(path, current_version, new_version, updater);
+    }
+
+    let placeholder = match &args.version_placeholder {
+        Some(placeholder) => Some(placeholder.as_str()),
+        None => args.replace_old_version.then_some(current_version),
+    };
+    if let Some(placeholder) = placeholder {
+        for path in &args.file {
+            replace_version_placeholder(path, placeholder, new_version, updater);
+        }
+    }
+
+    if args.update_deps && !args.sanitize {
+        update_cargo_dependents(args, current_version, new_version, updater);
+    }
+}
+
+/// Bump every enabled member of the workspace file with its own bump type,
+/// falling back to the one chosen for the root. `--workspace-root-only`
+/// lists the members without bumping them.
+fn bump_workspace_members(path: &Path, bump_type: &str, args: &Args, updater: &mut Updater) {
+    let config = read_workspace_config(path).unwrap_or_else(|err| {
+        eprintln!("{}", err);
+        exit(err.exit_code());
+    });
+
+    let members: Vec<WorkspaceMember> = config
+        .members
+        .into_iter()
+        .filter(|member| member.enabled)
+        .collect();
+    let selections = args
+        .interactive_workspace_bump
+        .then(|| confirm_workspace_bump_selections(&members));
+
+    for member in &members {
+        let Some(VersionSource {
+            path: source,
+            version: current,
+        }) = read_project_version(&member.path)
+        else {
+            eprintln!(
+                "No version found in workspace member {}.",
+                member.path.display()
+            );
+            exit(1);
+        };
+        if args.strict_semver {
+            if let Err(err) = validate_strict_semver(&current, &source) {
+                eprintln!("{}", err);
+                exit(err.exit_code());
+            }
+        }
+        let version = Version::parse(&current).unwrap_or_else(|_| {
+            eprintln!(
+                "Invalid semantic version in {}: {}",
+                member.path.display(),
+                current
+            );
+            exit(1);
+        });
+
+        if args.workspace_root_only {
+            println!("Keeping {} at {}", member.path.display(), current);
+            continue;
+        }
+
+        let member_bump = match &selections {
+            Some(selections) => match selections.get(&member.path) {
+                Some(bump) => bump.as_str(),
+                None => {
+                    println!("Skipping {} at {}", member.path.display(), current);
+                    continue;
+                }
+            },
+            None => member.bump_type.as_deref().unwrap_or(bump_type),
+        };
+        let new_version = bump_version(version, member_bump, args).to_string();
+        println!(
+            "Bumping {} {} → {}",
+            member.path.display(),
+            current,
+            new_version
+        );
+        update_project_files(&member.path, &new_version, updater);
+    }
+}
+
+/// `--interactive-workspace-bump`: collect a bump type for each member, show
+/// them all and exit without changes unless they are confirmed.
+fn confirm_workspace_bump_selections(members: &[WorkspaceMember]) -> HashMap<PathBuf, BumpType> {
+    let selections = collect_workspace_bump_selections(members);
+    println!("Workspace bumps:");
+    for member in members {
+        let choice = selections
+            .get(&member.path)
+            .map_or("skip", |bump| bump.as_str());
+        println!("  {}: {}", member.path.display(), choice);
+    }
+    if !confirm("Apply these workspace bumps?") {
+        println!("No files were changed.");
+        exit(0);
+    }
+    selections
+}
+
+/// List the sources `--sanitize` brought in line and the versions they had.
+fn print_sanitize_summary(out_of_sync: &[VersionSource], updater: &Updater, new_version: &str) {
+    let verb = if updater.dry_run {
+        "Would update"
+    } else {
+        "Updated"
+    };
+    let changed: Vec<_> = out_of_sync
+        .iter()
+        .filter(|source| updater.updated.iter().any(|p| same_path(p, &source.path)))
+        .collect();
+    if changed.is_empty() {
+        println!("All version files already match {}.", new_version);
+    }
+    for source in changed {
+        println!("{} {}: {} → {}", verb, source, source.version, new_version);
+    }
+}
+
+/// Queue the rewrites listed in a `--patch-file`.
+fn apply_version_patches(path: &Path, old: &str, new: &str, updater: &mut Updater) {
+    let results = apply_patch_file(path, old, new).unwrap_or_else(|err| {
+        eprintln!("{}", err);
+        exit(err.exit_code());
+    });
+
+    for result in results {
+        if result.replacements == 0 {
+            eprintln!(
+                "Warning: no patch patterns matched in {}",
+                result.file.display()
+            );
+            updater.report(&result.file, FileOutcome::Skipped("no match"));
+            continue;
+        }
+        println!(
+            "Patching {} ({} replacement{})",
+            result.file.display(),
+            result.replacements,
+            if result.replacements == 1 { "" } else { "s" }
+        );
+        updater.write(&result.file, &result.contents);
+    }
+}
+
+/// Replace `placeholder` with `new` throughout the `--file` at `path`.
+fn replace_version_placeholder(path: &Path, placeholder: &str, new: &str, updater: &mut Updater) {
+    let result = replace_placeholder(path, placeholder, new).unwrap_or_else(|err| {
+        eprintln!("Failed to read {}: {}", path.display(), err);
+        exit(1);
+    });
+
+    if result.replacements == 0 {
+        eprintln!(
+            "Warning: {} does not contain {}",
+            path.display(),
+            placeholder
+        );
+        updater.report(path, FileOutcome::Skipped("no placeholder"));
+        return;
+    }
+    println!(
+        "Replacing {} in {} ({} replacement{})",
+        placeholder,
+        path.display(),
+        result.replacements,
+        if result.replacements == 1 { "" } else { "s" }
+    );
+    updater.write(path, &result.contents);
+}
+
+/// `--update-deps`: bump the exact requirements on the package in
+/// `Cargo.toml` throughout its Cargo workspace. Exits if the package's own
+/// version is not bumped with them, as the workspace would not resolve.
+fn update_cargo_dependents(args: &Args, old: &str, new: &str, updater: &mut Updater) {
+    let manifest = cargo_toml_path(args).unwrap_or_else(|| PathBuf::from("Cargo.toml"));
+    let Some(name) = read_text_version(&manifest, read_package_name) else {
+        eprintln!("Warning: --update-deps needs a Cargo.toml with a [package] name.");
+        return;
+    };
+    let bumped = updater.is_pending(&manifest)
+        || read_text_version(&manifest, read_package_version).as_deref() == Some(new);
+    if !bumped {
+        eprintln!(
+            "--update-deps would require {} ={}, but the [package] version in {} is not being bumped.",
+            name,
+            new,
+            manifest.display()
+        );
+        exit(1);
+    }
+    let dir = manifest.parent().unwrap_or(Path::new(""));
+    let Some(root) = find_workspace_root(dir) else {
+        eprintln!("Warning: {} is not part of a Cargo workspace.", name);
+        return;
+    };
+    if let Err(err) = update_workspace_dependency_versions(&name, old, new, &root, updater) {
+        eprintln!("Failed to read the Cargo workspace: {}", err);
+        exit(1);
+    }
+}
+
+/// Replace `={old}` requirements on `name` with `={new}` in the workspace
+/// root at `dir` and each of its members, returning how many were replaced.
+fn update_workspace_dependency_versions(
+    name: &str,
+    old: &str,
+    new: &str,
+    dir: &Path,
+    updater: &mut Updater,
+) -> io::Result<usize> {
+    let mut manifests = vec![dir.join("Cargo.toml")];
+    manifests.extend(workspace_member_manifests(dir)?);
+
+    let mut total = 0;
+    for path in manifests {
+        let contents = fs::read_to_string(&path)?;
+        let (output, count) = replace_dependency_version(&contents, name, old, new);
+        if count == 0 {
+            continue;
+        }
+        println!(
+            "Updating {} ={} → ={} in {} ({} reference{})",
+            name,
+            old,
+            new,
+            path.display(),
+            count,
+            if count == 1 { "" } else { "s" }
+        );
+        updater.write(&path, &output);
+        total += count;
+    }
+    Ok(total)
+}
+
+/// Apply the steps of `--batch-file` to `version` in order, printing each.
+/// The returned bump type lists the steps, e.g. `minor,patch`.
+fn bump_batch(version: &Version, path: &Path, args: &Args) -> (String, Version) {
+    let steps = parse_batch_file(path).unwrap_or_else(|err| {
+        eprintln!("{}", err);
+        exit(err.exit_code());
+    });
+
+    let mut current = version.clone();
+    for (index, step) in steps.iter().enumerate() {
+        let next = bump_version(current.clone(), step.as_str(), args);
+        println!(
+            "Step {}/{}: {} {} → {}",
+            index + 1,
+            steps.len(),
+            step,
+            current,
+            next
+        );
+        current = next;
+    }
+    let bump_type = steps
+        .iter()
+        .map(|step| step.as_str())
+        .collect::<Vec<_>>()
+        .join(",");
+    (bump_type, current)
+}
+
+/// Apply `bump_type` and the `--pre-release` settings to `version`.
+fn bump_version(mut version: Version, bump_type: &str, args: &Args) -> Version {
+    // Repeating the current pre-release identifier continues its counter
+    // instead of bumping the release numbers again.
+    let separator_layout = args
+        .pre_release_separator
+        .as_deref()
+        .map(|separator| separator_format(separator).expect("validated by clap"));
+    let format = separator_layout
+        .as_deref()
+        .unwrap_or(&args.pre_release_format);
+    let next_counter = args
+        .pre_release
+        .as_deref()
+        .and_then(|id| {
+            let pre = version.pre.as_str();
+            prerelease_counter(pre, id, format).or_else(|| {
+                // Separator-only layouts pick up counters written in any style.
+                PRERELEASE_SEPARATORS
+                    .iter()
+                    .any(|separator| separator_format(separator).as_deref() == Ok(format))
+                    .then(|| prerelease_counter_any_separator(pre, id))
+                    .flatten()
+            })
+        })
+        .map(|n| n + 1);
+
+    match bump_type.parse::<BumpType>() {
+        Ok(_) if next_counter.is_some() => {}
+        Ok(bump) => {
+            let options = BumpOptions {
+                reset_patch: !args.no_reset_patch,
+                reset_minor: !args.no_reset_minor,
+            };
+            bump.apply_with(&mut version, options);
+        }
+        #[cfg(feature = "calver")]
+        Err(_) if bump_type == "calver" => {
+            let today = chrono::Local::now().date_naive();
+            version = bump_calver(today, &version, args.calver_micro, args.calver_year_digits);
+        }
+        Err(_) => {
+            eprintln!("Invalid bump type: {}", bump_type);
+            exit(1);
+        }
+    }
+
+    version.pre = match &args.pre_release {
+        Some(id) => {
+            let pre = format_prerelease(id, next_counter.unwrap_or(1), format);
+            semver::Prerelease::new(&pre).unwrap_or_else(|_| {
+                eprintln!("Invalid pre-release identifier: {}", pre);
+                exit(1);
+            })
+        }
+        None => semver::Prerelease::EMPTY,
+    };
+    version.build = semver::BuildMetadata::EMPTY;
+    version
+}
+
+fn publish_github_release(args: &Args, tag: &str) -> Result<String, BumpError> {
+    let token = std::env::var("GITHUB_TOKEN")
+        .ok()
+        .filter(|token| !token.is_empty())
+        .ok_or_else(|| BumpError::GithubRelease("GITHUB_TOKEN is not set".to_string()))?;
+    let remote = git::git_stdout(Path::new("."), &["remote", "get-url", "origin"])
+        .map_err(|err| BumpError::GithubRelease(err.to_string()))?;
+    let (owner, repo) = parse_github_remote(&remote).ok_or_else(|| {
+        BumpError::GithubRelease(format!("cannot infer owner/repo from remote {}", remote))
+    })?;
+
+    create_github_release(&GithubReleaseConfig {
+        api_url: std::env::var("GITHUB_API_URL").unwrap_or_else(|_| DEFAULT_API_URL.to_string()),
+        token,
+        owner,
+        repo,
+        tag: tag.to_string(),
+        name: tag.to_string(),
+        body: String::new(),
+        draft: args.github_release_draft,
+        prerelease: args.github_release_prerelease,
+    })
+}
+
+fn print_version(version: &str, no_newline: bool) {
+    if no_newline {
+        output::write(format_args!("{}", version));
+    } else {
+        println!("{}", version);
+    }
+}
+
+fn run_diff(old: &str, new: &str) {
+    let parse = |raw: &str| {
+        Version::parse(raw).unwrap_or_else(|_| {
+            eprintln!("Invalid semantic version: {}", raw);
+            exit(1);
+        })
+    };
+
+    println!("{}", classify_version_diff(&parse(old), &parse(new)));
+}
+
+/// `semver next` and `--print-next`: print the next version and exit.
+fn run_next(args: &Args, bump: BumpType) {
+    let Some(VersionSource { path, version }) = get_current_version(args) else {
+        eprintln!("No version found in composer.json, package.json, or VERSION file.");
+        exit(1);
+    };
+    let current = Version::parse(&version).unwrap_or_else(|_| {
+        let err = BumpError::InvalidVersion { path, version };
+        eprintln!("{}", err);
+        exit(err.exit_code());
+    });
+    print_version(
+        &compute_next_version(&current, bump).to_string(),
+        args.no_newline,
+    );
+}
+
+fn run_history() {
+    match read_history(Path::new(HISTORY_FILE)) {
+        Ok(history) => output::write(format_args!("{}", history)),
+        Err(_) => {
+            eprintln!("No {} file found.", HISTORY_FILE);
+            exit(1);
+        }
+    }
+}
+
+/// `semver audit --verify`: exit 25 if the audit trail's chain is broken.
+fn run_audit_verify() {
+    if let Err(err) = AuditChain::new(AUDIT_TRAIL_FILE).verify() {
+        eprintln!("{}", err);
+        exit(err.exit_code());
+    }
+    println!("Audit trail {} is intact.", AUDIT_TRAIL_FILE);
+}
+
+/// Who ran the bump, for `--audit-trail`: the git identity, else the login
+/// name.
+fn audit_user() -> String {
+    git::user_identity(Path::new("."))
+        .or_else(|| std::env::var("USER").ok())
+        .or_else(|| std::env::var("USERNAME").ok())
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+/// `--check`: exit 1 if a version source is invalid or out of sync.
+fn run_check(args: &Args) {
+    let sources: Vec<_> = if args.check_only_primary {
+        get_current_version(args).into_iter().collect()
+    } else {
+        version_sources(args)
+    };
+    if sources.is_empty() {
+        eprintln!("No version found in composer.json, package.json, or VERSION file.");
+        exit(1);
+    }
+
+    if let Err(err) = validate_sources_agree(&sources, args.strict_semver) {
+        eprintln!("{}", err);
+        exit(err.exit_code());
+    }
+    for source in &sources {
+        println!("{}: {}", source, source.version);
+    }
+}
+
+/// `--diff-files`: report each version source against the primary one and
+/// exit 7 if any differ. Nothing is changed.
+fn run_diff_files(args: &Args) {
+    let Some(primary) = get_current_version(args) else {
+        eprintln!("No version found in composer.json, package.json, or VERSION file.");
+        exit(1);
+    };
+
+    let mut differs = false;
+    for source in version_sources(args) {
+        if source.version == primary.version {
+            println!("{}: OK", source);
+        } else {
+            differs = true;
+            println!(
+                "{}: expected {}, found {}",
+                source, primary.version, source.version
+            );
+        }
+    }
+    if differs {
+        exit(7);
+    }
+}
+
+fn run_tags(prefix: &str, limit: Option<usize>, json: bool) {
+    let versions = list_semver_tags(prefix, Path::new(".")).unwrap_or_else(|err| {
+        eprintln!("{}", err);
+        exit(err.exit_code());
+    });
+    let tags = versions
+        .iter()
+        .take(limit.unwrap_or(usize::MAX))
+        .map(|version| format!("{}{}", prefix, version));
+
+    if json {
+        let tags: Vec<Value> = tags
+            .zip(&versions)
+            .map(|(tag, version)| serde_json::json!({ "tag": tag, "version": version.to_string() }))
+            .collect();
+        println!("{}", Value::Array(tags));
+    } else {
+        for tag in tags {
+            println!("{}", tag);
+        }
+    }
+}
+
+/// Exit with code 0 when no tracked file changed since the latest
+/// `<prefix><semver>` tag; without such a tag the bump goes ahead.
+fn skip_unless_changed(prefix: &str) {
+    let dir = Path::new(".");
+    let unchanged_since =
+        list_semver_tags(prefix, dir).and_then(|versions| match versions.first() {
+            Some(latest) => {
+                let tag = format!("{}{}", prefix, latest);
+                git::has_changes_since_tag(&tag, dir).map(|changed| (!changed).then_some(tag))
+            }
+            None => Ok(None),
+        });
+    match unchanged_since {
+        Ok(Some(tag)) => {
+            println!("No changes since {}; skipping bump", tag);
+            exit(0);
+        }
+        Ok(None) => {}
+        Err(err) => {
+            eprintln!("{}", err);
+            exit(err.exit_code());
+        }
+    }
+}
+
+/// Exit with code 19 unless a local tag matches `pattern`, by default
+/// `<prefix><version>`.
+fn check_version_is_tagged(pattern: Option<&str>, prefix: &str, version: &str) {
+    let pattern = match pattern {
+        Some(pattern) => pattern.replace("{version}", version),
+        None => format!("{}{}", prefix, version),
+    };
+    let result = git::has_tag(&pattern, Path::new(".")).and_then(|tagged| {
+        if tagged {
+            Ok(())
+        } else {
+            Err(BumpError::UntaggedVersion {
+                version: version.to_string(),
+                pattern,
+            })
+        }
+    });
+
+    if let Err(err) = result {
+        eprintln!("{}", err);
+        exit(err.exit_code());
+    }
+}
+
+/// Exit with code 13 if `tag` already exists locally or on `origin`.
+fn check_tag_is_new(tag: &str) {
+    let dir = Path::new(".");
+    let exists = tag_exists_locally(tag, dir).and_then(|local| {
+        if local || !has_remote("origin", dir) {
+            return Ok(local);
+        }
+        tag_exists_remote(tag, "origin", dir)
+    });
+
+    match exists {
+        Ok(false) => {}
+        Ok(true) => {
+            eprintln!("Git tag {} already exists.", tag);
+            exit(13);
+        }
+        Err(err) => {
+            eprintln!("Could not check git tags: {}", err);
+            exit(1);
+        }
+    }
+}
+
+/// Run the `--verify-after` command through the shell, reporting whether it
+/// succeeded.
+fn verify_bump(command: &str) -> bool {
+    match std::process::Command::new("sh")
+        .args(["-c", command])
+        .status()
+    {
+        Ok(status) => status.success(),
+        Err(err) => {
+            eprintln!("Could not run verification command: {}", err);
+            false
+        }
+    }
+}
+
+/// Run the `file_hooks` command `select` picks for each of `paths`, in the
+/// project directory, exiting on the first failure.
+fn run_file_hooks(
+    hooks: &[FileHookConfig],
+    paths: &[PathBuf],
+    select: impl Fn(&FileHookConfig) -> Option<&str>,
+) {
+    for path in paths {
+        for hook in hooks.iter().filter(|hook| hook.applies_to(path)) {
+            let Some(command) = select(hook) else {
+                continue;
+            };
+            let failed = |message: String| BumpError::HookFailed {
+                path: path.clone(),
+                command: command.to_string(),
+                message,
+            };
+            let result = match std::process::Command::new("sh")
+                .args(["-c", command])
+                .status()
+            {
+                Ok(status) if status.success() => Ok(()),
+                Ok(status) => Err(failed(status.to_string())),
+                Err(err) => Err(failed(err.to_string())),
+            };
+            if let Err(err) = result {
+                eprintln!("{}", err);
+                exit(err.exit_code());
+            }
+        }
+    }
+}
+
+/// Number of commits `--squash-commits` should fold into the bump commit,
+/// exiting if that reaches past the merge base.
+fn resolve_squash_count(squash: SquashCommits, tag_prefix: &str) -> usize {
+    let dir = Path::new(".");
+    let count = match squash {
+        SquashCommits::Count(count) => Ok(count),
+        SquashCommits::Auto => list_semver_tags(tag_prefix, dir).and_then(|versions| {
+            let latest = versions.first().ok_or_else(|| {
+                BumpError::Git("--squash-commits auto needs a semver tag to squash back to".into())
+            })?;
+            git::count_commits(&format!("{}{}..HEAD", tag_prefix, latest), dir)
+        }),
+    };
+    let checked = count.and_then(|count| {
+        let available = git::squashable_commits(dir)?;
+        if count > available {
+            return Err(BumpError::SquashPastMergeBase {
+                requested: count,
+                available,
+            });
+        }
+        Ok(count)
+    });
+
+    checked.unwrap_or_else(|err| {
+        eprintln!("{}", err);
+        exit(err.exit_code());
+    })
+}
+
+/// Stage and commit the bump. By default only `updated` is committed, so
+/// changes staged beforehand are left alone; `--include-all-modified` widens
+/// staging to every modified tracked file, `--commit-all` to the whole
+/// working tree. With `squash`, that many commits are first undone with
+/// `git reset --soft` so their changes land in the bump commit.
+fn commit_version_bump(
+    args: &Args,
+    updated: &[PathBuf],
+    new_version: &str,
+    squash: Option<usize>,
+    rendered: Option<&str>,
+    staged_undo: Option<StagedUndo>,
+) {
+    let dir = Path::new(".");
+    if let Some(count) = squash.filter(|&count| count > 0) {
+        if let Err(err) = git::reset_soft(count, dir) {
+            eprintln!("Failed to squash {} commits: {}", count, err);
+            exit(1);
+        }
+        println!("Squashed {} commits into the version bump", count);
+    }
+    let staged = if args.commit_all {
+        eprintln!(
+            "Warning: --commit-all stages every change, including untracked files; all of it will be committed."
+        );
+        git::add_all(dir)
+    } else if args.include_all_modified {
+        eprintln!(
+            "Warning: --include-all-modified stages every modified tracked file; unrelated changes will be committed."
+        );
+        git::add_tracked(dir)
+    } else {
+        git::add(updated, dir)
+    };
+    let message = rendered
+        .map(str::to_string)
+        .unwrap_or_else(|| commit_message(new_version, args.message_scope.as_deref()));
+    let message = append_trailers(&message, &args.commit_trailer);
+    let widened = args.commit_all || args.include_all_modified || squash.is_some_and(|n| n > 0);
+    let committed = staged.and_then(|_| {
+        if widened {
+            git::commit(&message, dir)
+        } else {
+            git::commit_paths(&message, updated, dir)
+        }
+    });
+    if let Err(err) = committed {
+        eprintln!("Failed to commit version bump: {}", err);
+        if let Some(undo) = staged_undo {
+            undo.roll_back();
+        }
+        exit(1);
+    }
+    if let Some(undo) = staged_undo {
+        undo.files.disarm();
+    }
+    println!("Committed version {}", new_version);
+}
+
+/// What undoes the pre-release commit of `--bump-pre-then-release` when the
+/// release commit fails.
+struct StagedUndo {
+    /// HEAD before the pre-release commit.
+    head: String,
+    /// The version files as they were before either step.
+    files: RollbackGuard,
+}
+
+impl StagedUndo {
+    fn roll_back(self) {
+        if let Err(err) = git::reset_to(&self.head, Path::new(".")) {
+            eprintln!("Failed to reset to {}: {}", self.head, err);
+        }
+        drop(self.files);
+        eprintln!("Rolled back both steps of --bump-pre-then-release.");
+    }
+}
+
+/// The first step of `--bump-pre-then-release`: write the pre-release of
+/// `new_version` and, with `--git-commit`, commit it. Returns that
+/// pre-release and, when it was committed, how to undo it.
+fn commit_prerelease_step(
+    args: &Args,
+    updater: &mut Updater,
+    bump_type: &str,
+    current_version: &str,
+    pre_id: &str,
+) -> (String, Option<StagedUndo>) {
+    let current = Version::parse(current_version).expect("validated current version");
+    let bump = bump_type
+        .parse::<BumpType>()
+        .expect("checked when the bump was chosen");
+    let (pre, _) = perform_staged_bump(&current, bump, pre_id);
+    let pre = pre.to_string();
+    println!("Pre-release step: {} → {}", current_version, pre);
+
+    // Not a release bump, so chart fields are left for the release step.
+    queue_version_updates(args, "pre-release", current_version, &pre, updater);
+    let dir = Path::new(".");
+    let commit = args.git_commit && !updater.dry_run;
+    let undo = commit.then(|| {
+        let head = git::head_sha(dir).unwrap_or_else(|err| {
+            eprintln!("{}", err);
+            exit(err.exit_code());
+        });
+        let files = RollbackGuard::new(&updater.pending_paths()).unwrap_or_else(|err| {
+            eprintln!(
+                "Could not back up files for --bump-pre-then-release: {}",
+                err
+            );
+            exit(1);
+        });
+        StagedUndo { head, files }
+    });
+    exit_on_write_failures(&updater.flush());
+    let updated = std::mem::take(&mut updater.updated);
+
+    let Some(undo) = undo else {
+        return (pre, None);
+    };
+    let message = append_trailers(
+        &commit_message(&pre, args.message_scope.as_deref()),
+        &args.commit_trailer,
+    );
+    let committed =
+        git::add(&updated, dir).and_then(|_| git::commit_paths(&message, &updated, dir));
+    if let Err(err) = committed {
+        eprintln!("Failed to commit version {}: {}", pre, err);
+        undo.roll_back();
+        exit(1);
+    }
+    println!("Committed version {}", pre);
+    (pre, Some(undo))
+}
+
+/// Apply `--git-credential-helper` and the GitHub token to every git command.
+fn configure_git(args: &Args) {
+    let token = args.github_token.clone().or_else(|| {
+        ["SEMVER_GITHUB_TOKEN", "GITHUB_TOKEN"]
+            .iter()
+            .filter_map(|name| std::env::var(name).ok())
+            .find(|token| !token.is_empty())
+    });
+    if args.git_credential_helper.is_none() && token.is_none() {
+        return;
+    }
+
+    let mut options = GitOptions::default();
+    if let Some(command) = &args.git_credential_helper {
+        options = options.credential_helper(command);
+    }
+    if let Some(token) = &token {
+        options = options.github_token(token);
+    }
+    if args.verbose {
+        eprintln!("Git options: {}", options.describe());
+    }
+    git::configure(options);
+}
+
+/// Message for the bump commit, `chore: bump version to <version>`; a
+/// `--message-scope` adds the scope as in `chore(<scope>): ...`.
+fn commit_message(new_version: &str, scope: Option<&str>) -> String {
+    match scope {
+        Some(scope) => format!("chore({}): bump version to {}", scope, new_version),
+        None => format!("chore: bump version to {}", new_version),
+    }
+}
+
+/// The annotated tag message: the `rendered` template, or `Release <tag>`.
+fn tag_message(tag: &str, rendered: Option<&str>) -> String {
+    rendered.map_or_else(|| format!("Release {}", tag), str::to_string)
+}
+
+/// The commits since the tag of `current_version`, or the whole history
+/// when it is untagged, rendered with `--git-log-format`. Empty outside a
+/// git repository.
+fn template_commits(args: &Args, current_version: &str) -> Vec<CommitLine> {
+    let dir = Path::new(".");
+    let tag = format!("{}{}", args.tag_prefix, current_version);
+    let since = if tag_exists_locally(&tag, dir).unwrap_or(false) {
+        tag
+    } else {
+        String::new()
+    };
+    let format = args.git_log_format.as_deref().unwrap_or(DEFAULT_LOG_FORMAT);
+    collect_formatted_commits(&since, format, dir)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|log_line| CommitLine { log_line })
+        .collect()
+}
+
+/// Render `--template-file` for the bump's commit and tag messages, warning
+/// about variables it uses that the context does not provide.
+fn render_message_template(
+    path: &Path,
+    template: &MessageTemplate,
+    context: &MessageContext,
+) -> String {
+    for name in template.unknown_variables() {
+        eprintln!(
+            "Warning: template {} uses unknown variable '{}'; it renders as an empty string.",
+            path.display(),
+            name
+        );
+    }
+    template.render(context).unwrap_or_else(|message| {
+        let err = BumpError::Template {
+            path: path.to_path_buf(),
+            message,
+        };
+        eprintln!("{}", err);
+        exit(err.exit_code());
+    })
+}
+
+/// The pre-release identifier `--pre-release-from-branch` derives from the
+/// current branch, or `fallback` from `--pre-release` when the branch names
+/// none.
+fn pre_release_from_branch(fallback: Option<String>) -> String {
+    let branch = get_current_branch(Path::new(".")).unwrap_or_else(|err| {
+        eprintln!("{}", err);
+        exit(err.exit_code());
+    });
+    if let Some(id) = prerelease_id_from_branch(&branch) {
+        return id;
+    }
+    fallback.unwrap_or_else(|| {
+        eprintln!(
+            "Branch {} does not name a pre-release; pass --pre-release to use as a fallback.",
+            branch
+        );
+        exit(1);
+    })
+}
+
+/// `--require-changelog-entry`: exit 23 unless `CHANGELOG.md` has an entry
+/// for `new_version`.
+fn check_changelog_entry(changelog: &ChangelogConfig, new_version: &str) {
+    let path = Path::new(CHANGELOG_FILE);
+    match changelog.file_has_entry(path, new_version) {
+        Ok(true) => {}
+        Ok(false) => {
+            let err = BumpError::MissingChangelogEntry {
+                path: path.to_path_buf(),
+                heading: changelog.release_heading(new_version),
+            };
+            eprintln!("{}", err);
+            exit(err.exit_code());
+        }
+        Err(err) => {
+            eprintln!("Failed to read {}: {}", path.display(), err);
+            exit(1);
+        }
+    }
+}
+
+/// `--tag-verify`: exit 22 unless the tag `--tag-force` is about to move
+/// carries a valid signature. A tag that does not exist yet is not checked.
+fn verify_existing_tag(tag: &str) {
+    let dir = Path::new(".");
+    if !git::tag_exists_locally(tag, dir).unwrap_or(false) {
+        eprintln!(
+            "Warning: git tag {} does not exist yet; nothing to verify.",
+            tag
+        );
+        return;
+    }
+    if let Err(err) = git::verify_tag_signature(tag, dir) {
+        eprintln!("{}", err);
+        exit(err.exit_code());
+    }
+    println!("Verified the signature of git tag {}", tag);
+}
+
+/// Create the annotated tag `tag`, with the message from `--tag-message-file`
+/// or the `rendered` `--template-file` when given. `--tag-force` moves an
+/// existing tag of the same name.
+fn create_version_tag(args: &Args, tag: &str, rendered: Option<&str>) {
+    let dir = Path::new(".");
+    if args.tag_force {
+        eprintln!(
+            "Warning: --tag-force moves git tag {} if it already exists.",
+            tag
+        );
+    }
+    let result = match &args.tag_message_file {
+        Some(path) => git::create_annotated_tag_from_file(tag, path, args.tag_force, dir),
+        None => git::create_annotated_tag(tag, &tag_message(tag, rendered), args.tag_force, dir),
+    };
+    if let Err(err) = result {
+        eprintln!("Failed to create git tag {}: {}", tag, err);
+        exit(1);
+    }
+    println!("Created git tag {}", tag);
+}
+
+/// Create the lightweight tag `tag` and attach `description` as a git note,
+/// pushing `refs/notes/commits` to origin when `push` is set.
+fn describe_version_tag(tag: &str, description: &str, push: bool) {
+    let dir = Path::new(".");
+    let result = git::create_lightweight_tag(tag, dir)
+        .and_then(|_| git::attach_git_note(tag, description, dir));
+    if let Err(err) = result {
+        eprintln!("Failed to describe git tag {}: {}", tag, err);
+        exit(1);
+    }
+    println!("Created git tag {} with a note", tag);
+
+    if !push {
+        eprintln!(
+            "Warning: git notes are not pushed automatically; use --push-notes or `git push origin refs/notes/commits`."
+        );
+        return;
+    }
+    if let Err(err) = git::push_notes("origin", dir) {
+        eprintln!("Failed to push git notes: {}", err);
+        exit(1);
+    }
+    println!("Pushed git notes to origin");
+}
+
+/// Commit and tag the bump through a [`GitPlan`]: staging, then the commit,
+/// then the tag. Steps that share a wave run concurrently; staging stays a
+/// single `git add` because concurrent adds would contend for the index lock.
+#[cfg(feature = "parallel-git")]
+fn run_git_plan(args: &Args, updated: &[PathBuf], new_version: &str, rendered: Option<&str>) {
+    let mut plan = GitPlan::default();
+    let mut committed = Vec::new();
+    let message = rendered
+        .map(str::to_string)
+        .unwrap_or_else(|| commit_message(new_version, args.message_scope.as_deref()));
+    let message = append_trailers(&message, &args.commit_trailer);
+    if args.git_commit {
+        let stage = plan.step(&git::add_args(updated), &[]);
+        committed.push(plan.step(&git::commit_paths_args(&message, updated), &[stage]));
+    }
+
+    let tag = format!("{}{}", args.tag_prefix, new_version);
+    if args.git_tag_annotated {
+        if args.tag_force {
+            eprintln!(
+                "Warning: --tag-force moves git tag {} if it already exists.",
+                tag
+            );
+        }
+        let (flag, text) = match &args.tag_message_file {
+            Some(path) => ("-F", path.display().to_string()),
+            None => ("-m", tag_message(&tag, rendered)),
+        };
+        plan.step(
+            &git::tag_args(&tag, &[flag, &text], args.tag_force),
+            &committed,
+        );
+    }
+
+    if let Err(err) = plan.run_parallel(Path::new(".")) {
+        eprintln!("{}", err);
+        exit(err.exit_code());
+    }
+    if args.git_commit {
+        println!("Committed version {}", new_version);
+    }
+    if args.git_tag_annotated {
+        println!("Created git tag {}", tag);
+    }
+}
+
+/// Exit with code 24 unless the current branch matches `pattern`; outside a
+/// git repository the check is skipped.
+fn check_branch(pattern: &str) {
+    let dir = Path::new(".");
+    if !git::is_git_repo(dir) {
+        eprintln!("Warning: not a git repository; skipping --branch check.");
+        return;
+    }
+    if let Err(err) = get_current_branch(dir).and_then(|branch| validate_branch(&branch, pattern)) {
+        eprintln!("{}", err);
+        exit(err.exit_code());
+    }
+}
+
+/// Exit with code 14 if the working tree has changes beyond `updated`.
+fn assert_only_updated_files_changed(updated: &[PathBuf]) {
+    let modified = modified_files(Path::new(".")).unwrap_or_else(|err| {
+        eprintln!("Could not list modified files: {}", err);
+        exit(1);
+    });
+
+    let unexpected: Vec<_> = modified
+        .iter()
+        .filter(|path| !updated.iter().any(|u| same_path(u, path)))
+        .collect();
+
+    if !unexpected.is_empty() {
+        eprintln!("Unexpected files modified:");
+        for path in unexpected {
+            eprintln!("  {}", path.display());
+        }
+        exit(14);
+    }
+}
+
+/// The version in the environment variable `var`, for `--version-in-env-var`.
+fn read_env_version(var: &str) -> VersionSource {
+    let version = std::env::var(var)
+        .ok()
+        .and_then(|value| read_version_file(&value))
+        .filter(|version| !version.is_empty())
+        .unwrap_or_else(|| {
+            eprintln!("Environment variable {} is not set or empty.", var);
+            exit(1);
+        });
+    VersionSource::new(format!("${}", var), version)
+}
+
+/// The build metadata for `--build-metadata-from-env`: the value of `var`
+/// after `prefix`, or `None` when `var` is unset or empty.
+fn build_metadata_from_env(var: &str, prefix: Option<&str>) -> Option<semver::BuildMetadata> {
+    let value = std::env::var(var).ok().filter(|value| !value.is_empty())?;
+    let build = format!("{}{}", prefix.unwrap_or(""), value);
+    let metadata = semver::BuildMetadata::new(&build).unwrap_or_else(|_| {
+        eprintln!(
+            "Invalid build metadata {:?} from environment variable {}: only ASCII letters, digits, `-` and `.`-separated identifiers are allowed.",
+            build, var
+        );
+        exit(1);
+    });
+    Some(metadata)
 }
 
-#[derive(Deserialize, Serialize)]
-struct ComposerJson {
-    version: String,
-    #[serde(flatten)]
-    other: serde_json::Value,
-}
+/// The version given with `--from`, parsed as `--input-format`.
+fn read_from_input(args: &Args, path: &Path) -> VersionSource {
+    let stdin = path == Path::new("-");
+    let contents = if stdin {
+        io::read_to_string(io::stdin())
+    } else {
+        fs::read_to_string(path)
+    };
+    let name = if stdin {
+        "stdin".to_string()
+    } else {
+        path.display().to_string()
+    };
+    let key = match args.input_format {
+        InputFormat::Raw => "",
+        InputFormat::Json => &args.json_key,
+        InputFormat::Toml => &args.toml_key,
+        InputFormat::Yaml => &args.yaml_key,
+    };
+    let version = contents
+        .map_err(|err| err.to_string())
+        .and_then(|contents| read_input_version(&contents, args.input_format, key))
+        .unwrap_or_else(|err| {
+            eprintln!("Could not read a version from {}: {}", name, err);
+            exit(1);
+        });
+    VersionSource::new(path, version)
+}
+
+/// Find the current version and the file it was read from.
+fn get_current_version(args: &Args) -> Option<VersionSource> {
+    if let Some(var) = &args.version_in_env_var {
+        return Some(read_env_version(var));
+    }
+
+    if args.bump_scope != VersionScope::All {
+        return sources_for_scope(args, args.bump_scope).into_iter().next();
+    }
+
+    if let Some(found) = read_project_version(Path::new("")).filter(|_| !args.no_auto_detect) {
+        return Some(found);
+    }
+
+    if let Some(path) = &args.version_json {
+        if let Some(version) = read_version_json(path) {
+            return Some(VersionSource::new(path.clone(), version));
+        }
+    }
+
+    if let Some(path) = &args.version_json_structured {
+        if let Some(version) = read_version_json_structured(path) {
+            return Some(VersionSource::new(path.clone(), version));
+        }
+    }
+
+    if let Some(path) = &args.azure_pipelines {
+        if let Some(version) = read_text_version(path, read_azure_version) {
+            return Some(VersionSource::new(path.clone(), version));
+        }
+    }
 
-/// CLI tool to bump semantic version
-#[derive(Parser)]
-#[command(author, version, about)]
-struct Args {
-    /// Optional version bump type: major, minor, patch
-    #[arg(long)]
-    bump: Option<String>,
-}
+    if let Some(path) = gemspec_path(args) {
+        if let Some(version) = read_text_version(&path, read_gemspec_version) {
+            return Some(VersionSource::new(path, version));
+        }
+    }
+
+    if let Some(path) = podspec_path(args) {
+        if let Some(version) = read_text_version(&path, read_podspec_version) {
+            return Some(VersionSource::new(path, version));
+        }
+    }
 
-#[derive(Deserialize, Serialize)]
-#[serde(transparent)]
-struct OrderedJson(#[serde(with = "ordered_json_map")] IndexMap<String, Value>);
+    if let Some(path) = podspec_json_path(args) {
+        if let Some(version) = read_text_version(&path, read_podspec_json_version) {
+            return Some(VersionSource::new(path, version));
+        }
+    }
 
-mod ordered_json_map {
-    use super::*;
-    use serde::{Deserializer, Serializer};
+    if let Some(path) = csproj_path(args) {
+        if let Some(version) = read_text_version(&path, read_csproj_version) {
+            return Some(VersionSource::new(path, version));
+        }
+    }
 
-    pub fn serialize<S>(map: &IndexMap<String, Value>, serializer: S) -> Result<S::Ok, S::Error>
-    where
-        S: Serializer,
-    {
-        map.serialize(serializer)
+    if let Some(path) = mix_exs_path(args) {
+        if let Some(version) = read_text_version(&path, read_mix_version) {
+            return Some(VersionSource::new(path, version));
+        }
     }
 
-    pub fn deserialize<'de, D>(deserializer: D) -> Result<IndexMap<String, Value>, D::Error>
-    where
-        D: Deserializer<'de>,
-    {
-        IndexMap::<String, Value>::deserialize(deserializer)
+    if let Some(path) = makefile_path(args) {
+        if let Some(version) = read_text_version(&path, read_makefile_version) {
+            return Some(VersionSource::new(path, version));
+        }
     }
-}
 
-fn prompt_bump_type() -> String {
-    let choices = vec!["major", "minor", "patch"];
+    if let Some(path) = snapcraft_path(args) {
+        if let Some(version) = read_text_version(&path, read_snapcraft_version) {
+            return Some(VersionSource::new(path, version));
+        }
+    }
 
-    match Select::new("What would you like to bump?", choices.clone())
-        .with_starting_cursor(2)
-        .prompt()
+    if let Some(path) = cargo_toml_path(args) {
+        if let Some(version) = read_text_version(&path, read_package_version) {
+            return Some(VersionSource::new(path, version));
+        }
+    }
+
+    if let Some(path) = pyproject_path(args) {
+        if let Some(version) = read_text_version(&path, read_pyproject_version) {
+            return Some(VersionSource::new(path, version));
+        }
+    }
+
+    if let Some(path) = &args.fastfile {
+        if let Some(version) = read_text_version(path, read_fastfile_version) {
+            return Some(VersionSource::new(path.clone(), version));
+        }
+    }
+
+    None
+}
+
+/// Every readable version source, in the order `get_current_version` tries them.
+fn version_sources(args: &Args) -> Vec<VersionSource> {
+    let mut sources = Vec::new();
+    let auto_detect = !args.no_auto_detect;
+    if auto_detect {
+        for name in ["composer.json", "package.json"] {
+            let path = PathBuf::from(name);
+            if let Some(version) = read_version_json(&path) {
+                sources.push(VersionSource::new(path, version));
+            }
+        }
+        if let Some(version) = read_text_version(Path::new("VERSION"), read_version_file) {
+            sources.push(VersionSource::new("VERSION", version));
+        }
+    }
+    for path in [
+        Some(PathBuf::from(".version.json")).filter(|_| auto_detect),
+        args.version_json.clone(),
+    ]
+    .into_iter()
+    .flatten()
     {
-        Ok(choice) => choice.to_string(),
-        Err(InquireError::OperationCanceled) | Err(InquireError::OperationInterrupted) => {
-            // Exit cleanly on Ctrl+C or ESC
-            std::process::exit(130); // 130 = standard exit code for SIGINT
+        if let Some(version) = read_version_json(&path) {
+            sources.push(VersionSource::new(path, version));
         }
-        Err(_) => {
-            println!("What would you like to bump? [major|minor|patch] (default: patch): ");
-            print!("> ");
-            io::stdout().flush().unwrap();
+    }
+    for path in [
+        Some(PathBuf::from(STRUCTURED_VERSION_FILE)).filter(|_| auto_detect),
+        args.version_json_structured.clone(),
+    ]
+    .into_iter()
+    .flatten()
+    {
+        if let Some(version) = read_version_json_structured(&path) {
+            sources.push(VersionSource::new(path, version));
+        }
+    }
 
-            let mut input = String::new();
-            io::stdin().read_line(&mut input).unwrap();
-            let trimmed = input.trim();
+    let text_sources: [(Option<PathBuf>, TextReader); 11] = [
+        (args.azure_pipelines.clone(), read_azure_version),
+        (gemspec_path(args), read_gemspec_version),
+        (podspec_path(args), read_podspec_version),
+        (podspec_json_path(args), read_podspec_json_version),
+        (csproj_path(args), read_csproj_version),
+        (mix_exs_path(args), read_mix_version),
+        (makefile_path(args), read_makefile_version),
+        (snapcraft_path(args), read_snapcraft_version),
+        (cargo_toml_path(args), read_package_version),
+        (pyproject_path(args), read_pyproject_version),
+        (args.fastfile.clone(), read_fastfile_version),
+    ];
+    for (path, read) in text_sources {
+        let Some(path) = path else { continue };
+        if let Some(version) = read_text_version(&path, read) {
+            sources.push(VersionSource::new(path, version));
+        }
+    }
+
+    sources.dedup_by(|a, b| same_path(&a.path, &b.path));
+    sources
+}
 
-            match trimmed {
-                "major" | "minor" | "patch" => trimmed.to_string(),
-                "" => "patch".to_string(),
-                _ => {
-                    eprintln!("Invalid input. Defaulting to patch.");
-                    "patch".to_string()
+/// The version sources belonging to `scope`, in the order
+/// `get_current_version` tries them.
+fn sources_for_scope(args: &Args, scope: VersionScope) -> Vec<VersionSource> {
+    version_sources(args)
+        .into_iter()
+        .filter(|source| scope.includes(&source.path))
+        .collect()
+}
+
+/// Let the user reorder `sources`; the first one becomes the read source.
+///
+/// On a terminal each position is picked from a list; otherwise the new order
+/// is read as a line of 1-based numbers, with unlisted sources kept after them.
+fn prompt_source_priority(sources: Vec<VersionSource>) -> Vec<VersionSource> {
+    if sources.len() < 2 {
+        return sources;
+    }
+    let labels: Vec<String> = sources
+        .iter()
+        .map(|source| format!("{} ({})", source, source.version))
+        .collect();
+
+    let mut order = Vec::new();
+    if io::stdin().is_terminal() {
+        let mut remaining: Vec<usize> = (0..sources.len()).collect();
+        while remaining.len() > 1 {
+            let options = remaining.iter().map(|&i| labels[i].clone()).collect();
+            let prompt = format!("Which source has priority #{}?", order.len() + 1);
+            match Select::new(&prompt, options).raw_prompt() {
+                Ok(choice) => order.push(remaining.remove(choice.index)),
+                Err(InquireError::OperationCanceled) | Err(InquireError::OperationInterrupted) => {
+                    exit(130);
+                }
+                Err(_) => break,
+            }
+        }
+    } else {
+        println!("Detected version sources:");
+        for (i, label) in labels.iter().enumerate() {
+            println!("  {}. {}", i + 1, label);
+        }
+        print!("Priority order (e.g. \"2 1\", empty keeps this order): ");
+        io::stdout().flush().unwrap();
+
+        let mut input = String::new();
+        io::stdin().read_line(&mut input).unwrap();
+        for n in input
+            .split([',', ' '])
+            .map(str::trim)
+            .filter(|n| !n.is_empty())
+        {
+            match n.parse::<usize>() {
+                Ok(n) if (1..=sources.len()).contains(&n) && !order.contains(&(n - 1)) => {
+                    order.push(n - 1)
                 }
+                _ => eprintln!("Ignoring invalid source number: {}", n),
             }
         }
     }
+
+    order.extend(
+        (0..sources.len())
+            .filter(|i| !order.contains(i))
+            .collect::<Vec<_>>(),
+    );
+    let mut sources: Vec<_> = sources.into_iter().map(Some).collect();
+    order.iter().filter_map(|&i| sources[i].take()).collect()
 }
 
-fn main() {
-    let args = Args::parse();
+/// Read the version from the standard files in `dir`, in priority order,
+/// along with the file it came from.
+///
+/// The project root is `Path::new("")` so paths stay relative, e.g. `package.json`.
+fn read_project_version(dir: &Path) -> Option<VersionSource> {
+    let composer = dir.join("composer.json");
+    if composer.exists() && json_is_valid(&composer) {
+        if let Ok(contents) = fs::read_to_string(&composer) {
+            if let Some(version) = read_composer_json_version(&contents) {
+                return Some(VersionSource::new(composer, version));
+            }
+        }
+    }
 
-    let current_version = get_current_version().unwrap_or_else(|| {
-        eprintln!("No version found in composer.json, package.json, or VERSION file.");
-        exit(1);
-    });
+    let package = dir.join("package.json");
+    if package.exists() && json_is_valid(&package) {
+        if let Ok(contents) = fs::read_to_string(&package) {
+            if let Some(version) = read_package_json_version(&contents) {
+                return Some(VersionSource::new(package, version));
+            }
+        }
+    }
 
-    let mut version = Version::parse(&current_version).expect("Invalid semantic version");
-    println!("Current version: {}", version);
+    let version_file = dir.join("VERSION");
+    if version_file.exists() {
+        if let Some(version) = read_text_version(&version_file, read_version_file) {
+            return Some(VersionSource::new(version_file, version));
+        }
+    }
+
+    let version_json = dir.join(".version.json");
+    if let Some(version) = read_version_json(&version_json) {
+        return Some(VersionSource::new(version_json, version));
+    }
+
+    let structured = dir.join(STRUCTURED_VERSION_FILE);
+    read_version_json_structured(&structured).map(|version| VersionSource::new(structured, version))
+}
+
+fn read_version_json(path: &Path) -> Option<String> {
+    if !path.exists() || !json_is_valid(path) {
+        return None;
+    }
+    let contents = fs::read_to_string(path).ok()?;
+    read_version_json_version(&contents)
+}
+
+/// The version in a structured `VERSION.json`, from its `major`, `minor`
+/// and `patch` fields.
+fn read_version_json_structured(path: &Path) -> Option<String> {
+    if !path.exists() || !json_is_valid(path) {
+        return None;
+    }
+    let contents = fs::read_to_string(path).ok()?;
+    read_structured_version(&contents).map(|version| version.to_string())
+}
+
+/// Report a malformed JSON source on stderr; other sources are still tried.
+fn json_is_valid(path: &Path) -> bool {
+    match validate_json_file(path) {
+        Ok(()) => true,
+        Err(err) => {
+            eprintln!("{}", err);
+            false
+        }
+    }
+}
+
+/// The manifest `wasm-pack build` writes next to the crate.
+const WASM_PKG_MANIFEST: &str = "pkg/package.json";
+
+/// Whether any version file was named on the command line, as
+/// `--no-auto-detect` requires.
+fn has_explicit_version_files(args: &Args) -> bool {
+    args.json_files.is_some()
+        || args.from.is_some()
+        || args.version_in_env_var.is_some()
+        || [
+            &args.version_json,
+            &args.version_json_structured,
+            &args.azure_pipelines,
+            &args.gemspec,
+            &args.podspec,
+            &args.csproj,
+            &args.fastfile,
+            &args.helm_chart,
+            &args.mix_exs,
+            &args.makefile,
+            &args.snapcraft,
+            &args.cargo_toml,
+            &args.pyproject,
+        ]
+        .iter()
+        .any(|path| path.is_some())
+}
+
+/// The explicit `--gemspec`, or the only `*.gemspec` in the project root.
+fn gemspec_path(args: &Args) -> Option<PathBuf> {
+    if let Some(path) = &args.gemspec {
+        return Some(path.clone());
+    }
+    if args.no_auto_detect {
+        return None;
+    }
+
+    let mut found = fs::read_dir(".")
+        .ok()?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "gemspec"));
+
+    match (found.next(), found.next()) {
+        (Some(path), None) => Some(path.strip_prefix("./").unwrap_or(&path).to_path_buf()),
+        _ => None,
+    }
+}
 
-    let bump_type = args.bump.unwrap_or_else(prompt_bump_type);
+/// The explicit `--podspec`, or the only `*.podspec` in the project root.
+///
+/// Several podspecs are ambiguous: none is bumped and a warning is printed
+/// once per run.
+fn podspec_path(args: &Args) -> Option<PathBuf> {
+    static WARN_MULTIPLE: Once = Once::new();
 
-    match bump_type.as_str() {
-        "major" => {
-            version.major += 1;
-            version.minor = 0;
-            version.patch = 0;
+    if let Some(path) = &args.podspec {
+        return Some(path.clone()).filter(|path| !is_podspec_json(path));
+    }
+    if args.no_auto_detect {
+        return None;
+    }
+
+    let found: Vec<PathBuf> = fs::read_dir(".")
+        .ok()?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "podspec"))
+        .collect();
+
+    match found.as_slice() {
+        [path] => Some(path.strip_prefix("./").unwrap_or(path).to_path_buf()),
+        [] => None,
+        _ => {
+            WARN_MULTIPLE.call_once(|| {
+                eprintln!(
+                    "Warning: found {} podspecs; pass --podspec to choose one.",
+                    found.len()
+                );
+            });
+            None
         }
-        "minor" => {
-            version.minor += 1;
-            version.patch = 0;
+    }
+}
+
+/// The `--podspec` when it is a JSON spec, or the only `*.podspec.json` in
+/// the project root.
+fn podspec_json_path(args: &Args) -> Option<PathBuf> {
+    if let Some(path) = &args.podspec {
+        return Some(path.clone()).filter(|path| is_podspec_json(path));
+    }
+    if args.no_auto_detect {
+        return None;
+    }
+
+    let mut found = fs::read_dir(".")
+        .ok()?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| is_podspec_json(path));
+
+    match (found.next(), found.next()) {
+        (Some(path), None) => Some(path.strip_prefix("./").unwrap_or(&path).to_path_buf()),
+        _ => None,
+    }
+}
+
+fn is_podspec_json(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| name.ends_with(".podspec.json"))
+}
+
+/// Bump the pod's own entries in `Podfile.lock`, when the project has one.
+/// `read_name` reads the pod name from the `podspec`.
+fn update_podfile_lock(
+    podspec: &Path,
+    read_name: TextReader,
+    new_version: &str,
+    updater: &mut Updater,
+) {
+    let lock = Path::new("Podfile.lock");
+    if !lock.exists() {
+        return;
+    }
+    let pod = read_text_version(podspec, read_name);
+    let Some(pod) = pod else {
+        updater.report(lock, FileOutcome::Skipped("podspec has no name"));
+        return;
+    };
+    let Ok(contents) = fs::read_to_string(lock) else {
+        updater.report(lock, FileOutcome::Skipped("unreadable"));
+        return;
+    };
+
+    match update_podfile_lock_version(&contents, &pod, new_version) {
+        Some(output) => updater.write(lock, &output),
+        None => updater.report(lock, FileOutcome::Skipped("no pod entry")),
+    }
+}
+
+/// The explicit `--csproj`, else the first `*.csproj` in the project root
+/// that declares a version, else `Directory.Build.props` when present.
+fn csproj_path(args: &Args) -> Option<PathBuf> {
+    if let Some(path) = &args.csproj {
+        return Some(path.clone());
+    }
+    if args.no_auto_detect {
+        return None;
+    }
+
+    let mut projects: Vec<PathBuf> = fs::read_dir(".")
+        .ok()?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "csproj"))
+        .collect();
+    projects.sort();
+
+    projects
+        .into_iter()
+        .find(|path| read_text_version(path, read_csproj_version).is_some())
+        .map(|path| path.strip_prefix("./").unwrap_or(&path).to_path_buf())
+        .or_else(|| Some(PathBuf::from("Directory.Build.props")).filter(|path| path.exists()))
+}
+
+/// The explicit `--helm-chart`, or `Chart.yaml` in the project root.
+fn helm_chart_path(args: &Args) -> Option<PathBuf> {
+    match &args.helm_chart {
+        Some(path) => Some(path.clone()),
+        None if args.no_auto_detect => None,
+        None => Some(PathBuf::from("Chart.yaml")).filter(|path| path.exists()),
+    }
+}
+
+/// Per-field Helm bumps; fields without their own bump follow the root one,
+/// unless that is not a release bump (e.g. `--bump-date`).
+fn helm_bump_config(args: &Args, bump_type: &str) -> HelmBumpConfig {
+    let root = bump_type.parse::<BumpType>().ok();
+    HelmBumpConfig {
+        chart: args
+            .helm_chart_bump
+            .or(root)
+            .filter(|_| !args.helm_app_version_only),
+        app: args
+            .helm_app_bump
+            .or(root)
+            .filter(|_| !args.helm_chart_version_only),
+    }
+}
+
+/// Bump the Helm chart's fields together; if one cannot be bumped, exit
+/// before anything is written.
+fn update_helm_chart(path: &Path, config: &HelmBumpConfig, updater: &mut Updater) {
+    let Ok(contents) = fs::read_to_string(path) else {
+        updater.report(path, FileOutcome::Skipped("unreadable"));
+        return;
+    };
+
+    match config.apply(&contents, path) {
+        Ok(Some(output)) => updater.write(path, &output),
+        Ok(None) => updater.report(path, FileOutcome::Skipped("no bump for chart fields")),
+        Err(err) => {
+            eprintln!("{}", err);
+            exit(err.exit_code());
         }
-        "patch" => {
-            version.patch += 1;
+    }
+}
+
+/// The explicit `--mix-exs`, or `mix.exs` in the project root.
+fn mix_exs_path(args: &Args) -> Option<PathBuf> {
+    match &args.mix_exs {
+        Some(path) => Some(path.clone()),
+        None if args.no_auto_detect => None,
+        None => Some(PathBuf::from("mix.exs")).filter(|path| path.exists()),
+    }
+}
+
+/// The explicit `--makefile`, or the Makefile `make` itself would pick.
+fn makefile_path(args: &Args) -> Option<PathBuf> {
+    match &args.makefile {
+        Some(path) => Some(path.clone()),
+        None if args.no_auto_detect => None,
+        None => MAKEFILE_NAMES
+            .iter()
+            .map(PathBuf::from)
+            .find(|path| path.exists()),
+    }
+}
+
+/// The explicit `--snapcraft`, or the project file `snapcraft` itself would
+/// pick.
+fn snapcraft_path(args: &Args) -> Option<PathBuf> {
+    match &args.snapcraft {
+        Some(path) => Some(path.clone()),
+        None if args.no_auto_detect => None,
+        None => SNAPCRAFT_PATHS
+            .iter()
+            .map(PathBuf::from)
+            .find(|path| path.exists()),
+    }
+}
+
+/// The explicit `--cargo-toml`, or `Cargo.toml` when present under
+/// `--bump-scope rust` or `--update-deps`. Other projects may carry a
+/// Cargo.toml whose version is not theirs, so it is never picked up otherwise.
+fn cargo_toml_path(args: &Args) -> Option<PathBuf> {
+    match &args.cargo_toml {
+        Some(path) => Some(path.clone()),
+        None if args.no_auto_detect => None,
+        None if args.bump_scope == VersionScope::Rust || args.update_deps => {
+            Some(PathBuf::from("Cargo.toml")).filter(|path| path.exists())
         }
-        _ => {
-            eprintln!("Invalid bump type: {}", bump_type);
-            exit(1);
+        None => None,
+    }
+}
+
+/// The explicit `--pyproject`, or `pyproject.toml` when present under
+/// `--bump-scope python`.
+fn pyproject_path(args: &Args) -> Option<PathBuf> {
+    match &args.pyproject {
+        Some(path) => Some(path.clone()),
+        None if args.no_auto_detect => None,
+        None if args.bump_scope == VersionScope::Python => {
+            Some(PathBuf::from("pyproject.toml")).filter(|path| path.exists())
         }
+        None => None,
     }
+}
 
-    version.pre = semver::Prerelease::EMPTY;
-    version.build = semver::BuildMetadata::EMPTY;
+/// Extracts the version from the contents of a text-based source.
+type TextReader = fn(&str) -> Option<String>;
 
-    let new_version = version.to_string();
-    println!("Bumping version {} → {}", current_version, new_version);
+/// Read a version from a text file using a format-specific extractor.
+fn read_text_version(path: &Path, read: TextReader) -> Option<String> {
+    let contents = fs::read_to_string(path).ok()?;
+    read(&contents)
+}
 
-    update_package_json(&new_version);
-    update_version_file(&new_version);
-    update_composer_json(&new_version);
+/// Run-wide settings and bookkeeping shared by every `update_*` function.
+struct Updater<'a> {
+    dry_run: bool,
+    /// Print a diff of each write; implies `dry_run`.
+    simulate: bool,
+    /// Apply the queued writes in parallel.
+    concurrent: bool,
+    color: bool,
+    verbosity: Verbosity,
+    log: &'a mut dyn Write,
+    /// With `--staged-only`, the files allowed to be written.
+    staged: Option<Vec<PathBuf>>,
+    /// `--ignore-files` globs; matching files are never written.
+    ignored: Vec<String>,
+    /// Write files even when their contents would not change.
+    force_write: bool,
+    /// `--bump-scope`; files of other project types are never written.
+    scope: VersionScope,
+    /// Indentation for re-serialized JSON files.
+    json_indent: JsonIndent,
+    /// Layout of rewritten `VERSION` files.
+    version_file_format: VersionFileFormat,
+    /// Writes queued by the `update_*` functions, applied by `flush`.
+    pending: Vec<(PathBuf, String)>,
+    updated: Vec<PathBuf>,
 }
 
-fn get_current_version() -> Option<String> {
-    if Path::new("composer.json").exists() {
-        if let Ok(contents) = fs::read_to_string("composer.json") {
-            if let Ok(json) = serde_json::from_str::<ComposerJson>(&contents) {
-                return Some(json.version);
+impl Updater<'_> {
+    fn report(&mut self, path: &Path, outcome: FileOutcome) {
+        if self.verbosity != Verbosity::Verbose {
+            return;
+        }
+
+        let path = path.display();
+        let _ = match outcome {
+            FileOutcome::Updated if self.dry_run => writeln!(self.log, "Would update: {}", path),
+            FileOutcome::Updated => writeln!(self.log, "Updated: {}", path),
+            FileOutcome::Skipped(reason) => writeln!(self.log, "Skipped ({}): {}", reason, path),
+        };
+    }
+
+    fn write(&mut self, path: &Path, contents: &str) {
+        if self
+            .ignored
+            .iter()
+            .any(|pattern| glob_match_path(pattern, path))
+        {
+            self.report(path, FileOutcome::Skipped("ignored"));
+            return;
+        }
+        if !self.scope.includes(path) {
+            self.report(path, FileOutcome::Skipped("outside --bump-scope"));
+            return;
+        }
+        if let Some(staged) = &self.staged {
+            if !staged.iter().any(|p| same_path(p, path)) {
+                self.report(path, FileOutcome::Skipped("not staged"));
+                return;
             }
         }
+        if !self.force_write && fs::read_to_string(path).is_ok_and(|existing| existing == contents)
+        {
+            self.report(path, FileOutcome::Skipped("unchanged"));
+            return;
+        }
+
+        self.pending
+            .push((path.to_path_buf(), contents.to_string()));
     }
 
-    if Path::new("package.json").exists() {
-        if let Ok(contents) = fs::read_to_string("package.json") {
-            if let Ok(json) = serde_json::from_str::<PackageJson>(&contents) {
-                return Some(json.version);
+    fn is_pending(&self, path: &Path) -> bool {
+        self.pending.iter().any(|(p, _)| same_path(p, path))
+    }
+
+    fn pending_paths(&self) -> Vec<PathBuf> {
+        self.pending.iter().map(|(path, _)| path.clone()).collect()
+    }
+
+    /// Apply every queued write and return the writes that failed; every
+    /// other file is recorded as updated.
+    fn flush(&mut self) -> Vec<BumpError> {
+        let pending = std::mem::take(&mut self.pending);
+        let results: Vec<(PathBuf, Result<(), BumpError>)> = if self.concurrent && !self.dry_run {
+            let paths: Vec<PathBuf> = pending.iter().map(|(path, _)| path.clone()).collect();
+            paths
+                .into_iter()
+                .zip(write_all_concurrent(pending))
+                .collect()
+        } else {
+            pending
+                .into_iter()
+                .map(|(path, contents)| {
+                    if self.simulate {
+                        self.print_diff(&path, &contents);
+                    }
+                    let result = if self.dry_run {
+                        Ok(())
+                    } else {
+                        write_file(&path, &contents)
+                    };
+                    (path, result)
+                })
+                .collect()
+        };
+
+        let mut failures = Vec::new();
+        for (path, result) in results {
+            match result {
+                Ok(()) => {
+                    self.report(&path, FileOutcome::Updated);
+                    self.updated.push(path);
+                }
+                Err(err) => {
+                    self.report(&path, FileOutcome::Skipped("write failed"));
+                    failures.push(err);
+                }
             }
         }
+        failures
     }
 
-    if Path::new("VERSION").exists() {
-        if let Ok(version) = fs::read_to_string("VERSION") {
-            return Some(version.trim().to_string());
+    fn print_pending_diffs(&self) {
+        for (path, contents) in &self.pending {
+            self.print_diff(path, contents);
         }
     }
 
-    None
+    fn print_diff(&self, path: &Path, contents: &str) {
+        let old = fs::read_to_string(path).unwrap_or_default();
+        let name = path.display().to_string();
+        let diff = TextDiff::from_lines(old.as_str(), contents)
+            .unified_diff()
+            .header(&format!("a/{}", name), &format!("b/{}", name))
+            .to_string();
+
+        for line in diff.lines() {
+            if !self.color {
+                println!("{}", line);
+            } else if line.starts_with("---") || line.starts_with("+++") {
+                println!("{}", line.bold());
+            } else if line.starts_with('-') {
+                println!("{}", line.red());
+            } else if line.starts_with('+') {
+                println!("{}", line.green());
+            } else if line.starts_with("@@") {
+                println!("{}", line.cyan());
+            } else {
+                println!("{}", line);
+            }
+        }
+    }
 }
-fn update_json_version(path: &str, new_version: &str) {
-    if !Path::new(path).exists() {
+
+/// Compare relative paths, ignoring `./` components.
+fn same_path(a: &Path, b: &Path) -> bool {
+    let normal = |p: &Path| {
+        p.components()
+            .filter(|c| *c != Component::CurDir)
+            .collect::<PathBuf>()
+    };
+    normal(a) == normal(b)
+}
+
+fn update_json_version(path: impl AsRef<Path>, new_version: &str, updater: &mut Updater) {
+    let path = path.as_ref();
+    if !path.exists() {
         return;
     }
 
     let contents = match fs::read_to_string(path) {
         Ok(c) => c,
-        Err(_) => return,
+        Err(_) => {
+            updater.report(path, FileOutcome::Skipped("unreadable"));
+            return;
+        }
     };
 
-    let mut ordered: OrderedJson = match serde_json::from_str(&contents) {
-        Ok(v) => v,
-        Err(_) => return,
+    match replace_json_version(&contents, new_version, updater.json_indent) {
+        // serde_json always emits `\n`; keep files checked in with CRLF that way.
+        Ok(Some(output)) => {
+            updater.write(path, &normalize_line_endings(&output, uses_crlf(&contents)))
+        }
+        Ok(None) => updater.report(path, FileOutcome::Skipped("no version field")),
+        Err(_) => updater.report(path, FileOutcome::Skipped("invalid JSON")),
+    }
+}
+
+/// Update every standard version file present in `dir`.
+fn update_project_files(dir: &Path, new_version: &str, updater: &mut Updater) {
+    update_package_json(dir, new_version, updater);
+    update_version_file(dir, new_version, updater);
+    update_composer_json(dir, new_version, updater);
+    update_version_json(&dir.join(".version.json"), new_version, updater);
+    update_structured_version(&dir.join(STRUCTURED_VERSION_FILE), new_version, updater);
+}
+
+/// Bump every file matching the `--json-files` glob, then list which were
+/// updated and which were skipped; `-v` gives the reason for each skip.
+fn update_json_files(pattern: &str, new_version: &str, updater: &mut Updater) {
+    let paths = expand_file_glob(pattern).unwrap_or_else(|err| {
+        eprintln!("Invalid --json-files pattern {}: {}", pattern, err);
+        exit(1);
+    });
+    if paths.is_empty() {
+        eprintln!("Warning: --json-files {} matched no files.", pattern);
+        return;
+    }
+
+    let (mut updated, mut skipped) = (Vec::new(), Vec::new());
+    for path in paths {
+        update_json_version(&path, new_version, updater);
+        if updater.is_pending(&path) {
+            updated.push(path.display().to_string());
+        } else {
+            skipped.push(path.display().to_string());
+        }
+    }
+
+    if !updated.is_empty() {
+        let verb = if updater.dry_run {
+            "would be updated"
+        } else {
+            "updated"
+        };
+        println!("JSON files {}: {}", verb, updated.join(", "));
+    }
+    if !skipped.is_empty() {
+        println!("JSON files skipped: {}", skipped.join(", "));
+    }
+}
+
+/// Update `package.json` and, when it is bumped, the npm lockfiles beside it.
+fn update_package_json(dir: &Path, new_version: &str, updater: &mut Updater) {
+    let manifest = dir.join("package.json");
+    update_json_version(&manifest, new_version, updater);
+    if !updater.is_pending(&manifest) {
+        return;
+    }
+    for name in NPM_LOCKFILES {
+        update_package_lock(&dir.join(name), new_version, updater);
+    }
+}
+
+fn update_package_lock(path: &Path, new_version: &str, updater: &mut Updater) {
+    let Ok(contents) = fs::read_to_string(path) else {
+        return;
     };
 
-    let map = &mut ordered.0;
-    let mut updated = false;
+    match replace_lockfile_version(&contents, new_version, updater.json_indent) {
+        Ok(Some(output)) => {
+            updater.write(path, &normalize_line_endings(&output, uses_crlf(&contents)))
+        }
+        Ok(None) => updater.report(path, FileOutcome::Skipped("no version field")),
+        Err(_) => updater.report(path, FileOutcome::Skipped("invalid JSON")),
+    }
+}
+
+fn update_composer_json(dir: &Path, new_version: &str, updater: &mut Updater) {
+    update_json_version(dir.join("composer.json"), new_version, updater);
+}
+
+fn update_version_json(path: &Path, new_version: &str, updater: &mut Updater) {
+    update_json_version(path, new_version, updater);
+}
 
-    if let Some(Value::String(version)) = map.get_mut("version") {
-        *version = new_version.to_string();
-        updated = true;
+/// Update the `major`, `minor` and `patch` fields of a structured version
+/// file. A pre-release or build cannot be stored there, so such versions
+/// leave the file alone with a warning.
+fn update_structured_version(path: &Path, new_version: &str, updater: &mut Updater) {
+    let Ok(contents) = fs::read_to_string(path) else {
+        return;
+    };
+    let Ok(version) = Version::parse(new_version) else {
+        return;
+    };
+    if read_structured_version(&contents).is_none() {
+        updater.report(path, FileOutcome::Skipped("no major/minor/patch fields"));
+        return;
+    }
+    if !version.pre.is_empty() || !version.build.is_empty() {
+        eprintln!(
+            "Warning: {} only holds major, minor and patch; not writing {}.",
+            path.display(),
+            new_version
+        );
+        updater.report(path, FileOutcome::Skipped("pre-release or build"));
+        return;
     }
 
-    if updated {
-        if let Ok(output) = serde_json::to_string_pretty(&ordered) {
-            let _ = fs::write(path, output);
+    match replace_structured_version(&contents, &version, updater.json_indent) {
+        Ok(Some(output)) => {
+            updater.write(path, &normalize_line_endings(&output, uses_crlf(&contents)))
         }
+        Ok(None) => updater.report(path, FileOutcome::Skipped("no major/minor/patch fields")),
+        Err(_) => updater.report(path, FileOutcome::Skipped("invalid JSON")),
     }
 }
 
-fn update_package_json(new_version: &str) {
-    update_json_version("package.json", new_version);
+fn update_version_file(dir: &Path, new_version: &str, updater: &mut Updater) {
+    let path = dir.join("VERSION");
+    if !path.exists() {
+        return;
+    }
+
+    let existing = fs::read_to_string(&path).unwrap_or_default();
+    match updater
+        .version_file_format
+        .render(new_version, &existing, &path)
+    {
+        Ok(contents) => updater.write(&path, &contents),
+        Err(err) => {
+            eprintln!("{}", err);
+            exit(err.exit_code());
+        }
+    }
 }
 
-fn update_composer_json(new_version: &str) {
-    update_json_version("composer.json", new_version);
+/// Bump the root `version` of a `snapcraft.yaml`, exiting on invalid ones.
+fn update_snapcraft(path: &Path, new_version: &str, updater: &mut Updater) {
+    let Ok(contents) = fs::read_to_string(path) else {
+        updater.report(path, FileOutcome::Skipped("unreadable"));
+        return;
+    };
+
+    let Some(output) = update_snapcraft_version(&contents, new_version) else {
+        let reason = if uses_adopt_info(&contents) {
+            "version set by adopt-info"
+        } else {
+            "no version field"
+        };
+        updater.report(path, FileOutcome::Skipped(reason));
+        return;
+    };
+    if let Err(message) = validate_snapcraft_version(new_version) {
+        eprintln!("Cannot update {}: {}", path.display(), message);
+        exit(1);
+    }
+    updater.write(path, &output);
 }
 
-fn update_version_file(new_version: &str) {
-    let path = "VERSION";
-    if Path::new(path).exists() {
-        let _ = fs::write(path, new_version);
+/// Rewrite the version in a text file using a format-specific replacer.
+fn update_text_version(
+    path: &Path,
+    new_version: &str,
+    replace: fn(&str, &str) -> Option<String>,
+    updater: &mut Updater,
+) {
+    let Ok(contents) = fs::read_to_string(path) else {
+        updater.report(path, FileOutcome::Skipped("unreadable"));
+        return;
+    };
+
+    match replace(&contents, new_version) {
+        Some(output) => updater.write(path, &output),
+        None => updater.report(path, FileOutcome::Skipped("no version field")),
     }
 }
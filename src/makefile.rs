@@ -0,0 +1,68 @@
+//! `VERSION` variables in Makefiles:
+//!
+//! ```make
+//! VERSION ?= 1.2.3
+//! ```
+//!
+//! Only the value is rewritten, so `=`, `:=` and `?=` assignments keep their
+//! operator.
+
+use regex::Regex;
+use std::ops::Range;
+use std::sync::LazyLock;
+
+/// File names `make` looks for, in its own order of preference.
+pub const MAKEFILE_NAMES: [&str; 3] = ["GNUmakefile", "makefile", "Makefile"];
+
+static VERSION_ASSIGNMENT: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?m)^VERSION[ \t]*[:?]?=[ \t]*([^\s#]+)").unwrap());
+
+/// Read the value of the first `VERSION` assignment.
+pub fn read_makefile_version(contents: &str) -> Option<String> {
+    version_span(contents).map(|span| contents[span].to_string())
+}
+
+/// Replace the value of the first `VERSION` assignment, returning the new
+/// file contents.
+pub fn update_makefile_version(contents: &str, new_version: &str) -> Option<String> {
+    let span = version_span(contents)?;
+    Some(format!(
+        "{}{}{}",
+        &contents[..span.start],
+        new_version,
+        &contents[span.end..]
+    ))
+}
+
+fn version_span(contents: &str) -> Option<Range<usize>> {
+    VERSION_ASSIGNMENT
+        .captures(contents)?
+        .get(1)
+        .map(|m| m.range())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_assignment_operator() {
+        for op in ["=", ":=", "?="] {
+            let makefile =
+                format!("BIN := app\nVERSION {op} 1.2.3 # release\n\nall:\n\techo $(VERSION)\n");
+
+            assert_eq!(read_makefile_version(&makefile).as_deref(), Some("1.2.3"));
+            assert_eq!(
+                update_makefile_version(&makefile, "1.3.0").unwrap(),
+                makefile.replace("1.2.3", "1.3.0")
+            );
+        }
+    }
+
+    #[test]
+    fn ignores_other_variables() {
+        let makefile = "GO_VERSION = 1.21\nAPP_VERSION=2.0.0\n  VERSION = 9.9.9\n";
+
+        assert_eq!(read_makefile_version(makefile), None);
+    }
+}
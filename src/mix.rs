@@ -0,0 +1,93 @@
+//! Editing the project version in an Elixir `mix.exs`.
+//!
+//! The canonical version is whatever `def project` passes as `version:`.
+//! That is either a literal or a module attribute such as `@version`; when
+//! `def project` cannot be found the first `@version` attribute is used.
+//! Other attributes that merely hold version-like strings are left alone.
+
+use regex::Regex;
+use std::ops::Range;
+use std::sync::LazyLock;
+
+static PROJECT_FN: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?s)\bdef\s+project\b.*?\bend\b").unwrap());
+static PROJECT_VERSION: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"\bversion:\s*(?:"([^"]+)"|@(\w+))"#).unwrap());
+
+/// Read the canonical project version.
+pub fn read_mix_version(contents: &str) -> Option<String> {
+    version_span(contents).map(|span| contents[span].to_string())
+}
+
+/// Replace the canonical project version, returning the new file contents.
+pub fn replace_mix_version(contents: &str, new_version: &str) -> Option<String> {
+    let span = version_span(contents)?;
+    Some(format!(
+        "{}{}{}",
+        &contents[..span.start],
+        new_version,
+        &contents[span.end..]
+    ))
+}
+
+fn version_span(contents: &str) -> Option<Range<usize>> {
+    let attribute = match PROJECT_FN.find(contents) {
+        Some(project) => {
+            let caps = PROJECT_VERSION.captures(project.as_str())?;
+            if let Some(literal) = caps.get(1) {
+                let start = project.start() + literal.start();
+                return Some(start..start + literal.len());
+            }
+            caps.get(2)?.as_str().to_string()
+        }
+        None => "version".to_string(),
+    };
+
+    let pattern = Regex::new(&format!(
+        r#"(?m)^\s*@{}\s+"([^"]+)""#,
+        regex::escape(&attribute)
+    ))
+    .ok()?;
+    pattern.captures(contents)?.get(1).map(|m| m.range())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn follows_attribute_used_by_project() {
+        let mix = r#"defmodule Demo.MixProject do
+  use Mix.Project
+
+  @elixir_version "1.15.0"
+  @version "0.3.1"
+
+  def project do
+    [app: :demo, version: @version, elixir: "~> " <> @elixir_version]
+  end
+end
+"#;
+
+        assert_eq!(read_mix_version(mix).as_deref(), Some("0.3.1"));
+        assert_eq!(
+            replace_mix_version(mix, "0.4.0").unwrap(),
+            mix.replace("@version \"0.3.1\"", "@version \"0.4.0\"")
+        );
+    }
+
+    #[test]
+    fn prefers_literal_in_project() {
+        let mix = "defmodule Demo.MixProject do\n  @version \"9.9.9\"\n\n  def project do\n    [app: :demo, version: \"1.0.0\"]\n  end\nend\n";
+
+        assert_eq!(read_mix_version(mix).as_deref(), Some("1.0.0"));
+    }
+
+    #[test]
+    fn falls_back_to_version_attribute() {
+        assert_eq!(
+            read_mix_version("@version \"2.1.0\"\n").as_deref(),
+            Some("2.1.0")
+        );
+    }
+}
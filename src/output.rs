@@ -0,0 +1,84 @@
+//! Where the CLI's regular output goes: stdout, or the `--output-file` log.
+//!
+//! Only stdout is redirected; warnings and errors stay on stderr.
+
+use std::fmt;
+use std::fs::{File, OpenOptions};
+use std::io::{self, Write};
+use std::path::Path;
+use std::sync::{Mutex, OnceLock};
+
+type Sink = Box<dyn Write + Send>;
+
+static SINK: OnceLock<Mutex<Sink>> = OnceLock::new();
+
+/// Open `path` as an output log, truncating it unless `append` is set.
+pub fn open_log(path: &Path, append: bool) -> io::Result<Sink> {
+    let mut options = OpenOptions::new();
+    options.create(true);
+    if append {
+        options.append(true);
+    } else {
+        options.write(true).truncate(true);
+    }
+    let file: File = options.open(path)?;
+    Ok(Box::new(file))
+}
+
+/// Send every later [`write_line`] to `sink`. Only the first call takes
+/// effect; output goes to stdout until then.
+pub fn redirect(sink: Sink) {
+    let _ = SINK.set(Mutex::new(sink));
+}
+
+/// Write `args` without a newline to the configured sink, or stdout,
+/// flushing it so the text is not held back.
+pub fn write(args: fmt::Arguments) {
+    match SINK.get() {
+        Some(sink) => {
+            let mut sink = sink.lock().unwrap_or_else(|err| err.into_inner());
+            let _ = write!(sink, "{}", args);
+            let _ = sink.flush();
+        }
+        None => {
+            let mut stdout = io::stdout();
+            let _ = write!(stdout, "{}", args);
+            let _ = stdout.flush();
+        }
+    }
+}
+
+/// Write `args` and a newline to the configured sink, or stdout.
+pub fn write_line(args: fmt::Arguments) {
+    match SINK.get() {
+        Some(sink) => {
+            let mut sink = sink.lock().unwrap_or_else(|err| err.into_inner());
+            let _ = writeln!(sink, "{}", args);
+        }
+        None => {
+            let _ = writeln!(io::stdout(), "{}", args);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn log_truncates_or_appends() {
+        let dir = std::env::temp_dir().join(format!("semver-output-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("out.log");
+        fs::write(&path, "old\n").unwrap();
+
+        writeln!(open_log(&path, true).unwrap(), "new").unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "old\nnew\n");
+
+        writeln!(open_log(&path, false).unwrap(), "fresh").unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "fresh\n");
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,189 @@
+//! Patch files listing extra places that carry the version:
+//!
+//! ```yaml
+//! - file: Dockerfile
+//!   pattern: 'LABEL version="{old}"'
+//!   replacement: 'LABEL version="{new}"'
+//! - file: docs/install.md
+//!   pattern: 'mytool@{old}'
+//!   replacement: 'mytool@{new}'
+//! ```
+//!
+//! `pattern` is a regex in which `{old}` stands for the escaped current
+//! version; `{new}` in `replacement` is the new version, and capture groups can
+//! be referenced as `$1`.
+//!
+//! Plain placeholders such as `%%VERSION%%` given with `--version-placeholder`
+//! are replaced literally by [`replace_placeholder`].
+
+use crate::error::BumpError;
+use regex::Regex;
+use serde::Deserialize;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct PatchFileEntry {
+    pub file: PathBuf,
+    pub pattern: String,
+    pub replacement: String,
+}
+
+/// The rewritten contents of one target file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PatchResult {
+    pub file: PathBuf,
+    /// Occurrences replaced across every entry for this file.
+    pub replacements: usize,
+    pub contents: String,
+}
+
+/// Apply every entry of the patch file at `path` in memory, returning the new
+/// contents per target file in first-seen order. Entries for the same file are
+/// applied in sequence; target paths are resolved against the patch file's
+/// directory.
+pub fn apply_patch_file(path: &Path, old: &str, new: &str) -> Result<Vec<PatchResult>, BumpError> {
+    let invalid = |message: String| BumpError::InvalidConfig {
+        path: path.to_path_buf(),
+        message,
+    };
+
+    let contents = fs::read_to_string(path).map_err(|err| invalid(err.to_string()))?;
+    let entries: Vec<PatchFileEntry> =
+        serde_yaml::from_str(&contents).map_err(|err| invalid(err.to_string()))?;
+
+    let base = path.parent().unwrap_or(Path::new(""));
+    let mut results: Vec<PatchResult> = Vec::new();
+    for entry in entries {
+        let pattern = entry.pattern.replace("{old}", &regex::escape(old));
+        let re = Regex::new(&pattern)
+            .map_err(|err| invalid(format!("invalid pattern '{}': {}", entry.pattern, err)))?;
+        let replacement = entry.replacement.replace("{new}", new);
+
+        let file = base.join(&entry.file);
+        let index = match results.iter().position(|r| r.file == file) {
+            Some(index) => index,
+            None => {
+                let contents = fs::read_to_string(&file)
+                    .map_err(|err| invalid(format!("{}: {}", file.display(), err)))?;
+                results.push(PatchResult {
+                    file,
+                    replacements: 0,
+                    contents,
+                });
+                results.len() - 1
+            }
+        };
+
+        let result = &mut results[index];
+        let count = re.find_iter(&result.contents).count();
+        if count > 0 {
+            result.contents = re
+                .replace_all(&result.contents, replacement.as_str())
+                .into_owned();
+            result.replacements += count;
+        }
+    }
+
+    Ok(results)
+}
+
+/// Replace every literal occurrence of `placeholder` in the file at `path`
+/// with `new`, in memory.
+pub fn replace_placeholder(path: &Path, placeholder: &str, new: &str) -> io::Result<PatchResult> {
+    let contents = fs::read_to_string(path)?;
+    let replacements = contents.matches(placeholder).count();
+    Ok(PatchResult {
+        file: path.to_path_buf(),
+        replacements,
+        contents: contents.replace(placeholder, new),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn applies_entries_in_order() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(
+            dir.path().join("Dockerfile"),
+            "FROM scratch\nLABEL version=\"1.2.3\"\n# 1.2.3\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("versions.yaml"),
+            concat!(
+                "- file: Dockerfile\n",
+                "  pattern: 'LABEL version=\"{old}\"'\n",
+                "  replacement: 'LABEL version=\"{new}\"'\n",
+                "- file: Dockerfile\n",
+                "  pattern: '# (\\S+)'\n",
+                "  replacement: '# was $1'\n",
+            ),
+        )
+        .unwrap();
+
+        let results =
+            apply_patch_file(&dir.path().join("versions.yaml"), "1.2.3", "1.3.0").unwrap();
+
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].replacements, 2);
+        assert_eq!(
+            results[0].contents,
+            "FROM scratch\nLABEL version=\"1.3.0\"\n# was 1.2.3\n"
+        );
+    }
+
+    #[test]
+    fn escapes_the_old_version() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("a.txt"), "v1x2x3 v1.2.3").unwrap();
+        fs::write(
+            dir.path().join("p.yaml"),
+            "- {file: a.txt, pattern: 'v{old}', replacement: 'v{new}'}\n",
+        )
+        .unwrap();
+
+        let results = apply_patch_file(&dir.path().join("p.yaml"), "1.2.3", "2.0.0").unwrap();
+
+        assert_eq!(results[0].contents, "v1x2x3 v2.0.0");
+    }
+
+    #[test]
+    fn rejects_invalid_patterns() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("a.txt"), "").unwrap();
+        fs::write(
+            dir.path().join("p.yaml"),
+            "- {file: a.txt, pattern: '(', replacement: ''}\n",
+        )
+        .unwrap();
+
+        assert!(matches!(
+            apply_patch_file(&dir.path().join("p.yaml"), "1.0.0", "1.0.1"),
+            Err(BumpError::InvalidConfig { .. })
+        ));
+    }
+
+    #[test]
+    fn replaces_placeholders_literally() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("about.txt");
+        fs::write(&path, "v%%VERSION%% (%%VERSION%%) 1.2.3 %%VERSION\n").unwrap();
+
+        let result = replace_placeholder(&path, "%%VERSION%%", "1.3.0").unwrap();
+
+        assert_eq!(result.replacements, 2);
+        assert_eq!(result.contents, "v1.3.0 (1.3.0) 1.2.3 %%VERSION\n");
+        assert_eq!(
+            replace_placeholder(&path, "1.2.3", "1.3.0")
+                .unwrap()
+                .replacements,
+            1
+        );
+        assert!(replace_placeholder(&dir.path().join("missing"), "x", "y").is_err());
+    }
+}
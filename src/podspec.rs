@@ -0,0 +1,119 @@
+//! Editing `s.version = "..."` in CocoaPods podspecs and the matching pod
+//! entries in `Podfile.lock`. JSON specs (`*.podspec.json`) keep both in
+//! root-level `name` and `version` fields.
+
+use regex::Regex;
+use serde::Deserialize;
+use std::ops::Range;
+use std::sync::LazyLock;
+
+static VERSION: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"(?m)^\s*\w+\.version\s*=\s*(?:"([^"]+)"|'([^']+)')"#).unwrap());
+static NAME: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"(?m)^\s*\w+\.name\s*=\s*(?:"([^"]+)"|'([^']+)')"#).unwrap());
+
+/// Read the literal version assigned in the podspec.
+pub fn read_podspec_version(contents: &str) -> Option<String> {
+    literal_span(&VERSION, contents).map(|span| contents[span].to_string())
+}
+
+/// Replace the literal podspec version, returning the new file contents.
+pub fn update_podspec_version(contents: &str, new_version: &str) -> Option<String> {
+    let span = literal_span(&VERSION, contents)?;
+    Some(format!(
+        "{}{}{}",
+        &contents[..span.start],
+        new_version,
+        &contents[span.end..]
+    ))
+}
+
+/// Read the pod name assigned in the podspec.
+pub fn read_podspec_name(contents: &str) -> Option<String> {
+    literal_span(&NAME, contents).map(|span| contents[span].to_string())
+}
+
+#[derive(Deserialize)]
+struct PodspecJson {
+    name: Option<String>,
+    version: Option<String>,
+}
+
+fn read_podspec_json(contents: &str) -> Option<PodspecJson> {
+    serde_json::from_str(contents).ok()
+}
+
+/// Read the root-level `version` of a `*.podspec.json`.
+pub fn read_podspec_json_version(contents: &str) -> Option<String> {
+    read_podspec_json(contents)?.version
+}
+
+/// Read the root-level `name` of a `*.podspec.json`.
+pub fn read_podspec_json_name(contents: &str) -> Option<String> {
+    read_podspec_json(contents)?.name
+}
+
+/// Replace the version of every `- <pod> (x.y.z)` entry in a `Podfile.lock`,
+/// subspecs such as `<pod>/Core` included. Requirements such as `(= 1.2.3)`
+/// and local `from` paths are left alone.
+///
+/// Returns `None` when the lock file has no versioned entry for `pod`.
+pub fn update_podfile_lock_version(contents: &str, pod: &str, new_version: &str) -> Option<String> {
+    let entry = Regex::new(&format!(
+        r"(?m)^(\s*- {}(?:/[^\s(]+)? \()[^)\s]+\)",
+        regex::escape(pod)
+    ))
+    .ok()?;
+    if !entry.is_match(contents) {
+        return None;
+    }
+    let replacement = format!("${{1}}{})", new_version);
+    Some(
+        entry
+            .replace_all(contents, replacement.as_str())
+            .into_owned(),
+    )
+}
+
+fn literal_span(assignment: &Regex, contents: &str) -> Option<Range<usize>> {
+    let caps = assignment.captures(contents)?;
+    caps.get(1).or(caps.get(2)).map(|m| m.range())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PODSPEC: &str = "Pod::Spec.new do |s|\n  s.name = 'Demo'\n  s.version = '1.2.3'\n  s.swift_version = '5.0'\nend\n";
+
+    #[test]
+    fn reads_and_replaces_version() {
+        assert_eq!(read_podspec_version(PODSPEC).as_deref(), Some("1.2.3"));
+        assert_eq!(read_podspec_name(PODSPEC).as_deref(), Some("Demo"));
+        assert_eq!(
+            update_podspec_version(PODSPEC, "1.3.0").unwrap(),
+            PODSPEC.replace("1.2.3", "1.3.0")
+        );
+    }
+
+    #[test]
+    fn reads_json_specs() {
+        let spec = r#"{"name": "Demo", "version": "1.2.3", "platforms": {"ios": "13.0"}}"#;
+
+        assert_eq!(read_podspec_json_version(spec).as_deref(), Some("1.2.3"));
+        assert_eq!(read_podspec_json_name(spec).as_deref(), Some("Demo"));
+        assert_eq!(read_podspec_json_version(r#"{"name": "Demo"}"#), None);
+        assert_eq!(read_podspec_json_name("not json"), None);
+    }
+
+    #[test]
+    fn updates_pod_and_subspec_lock_entries() {
+        let lock = "PODS:\n  - Demo (1.2.3):\n    - Demo/Core (= 1.2.3)\n  - Demo/Core (1.2.3)\n  - DemoKit (0.1.0)\n\nDEPENDENCIES:\n  - Demo (from `.`)\n";
+
+        assert_eq!(
+            update_podfile_lock_version(lock, "Demo", "1.3.0").unwrap(),
+            "PODS:\n  - Demo (1.3.0):\n    - Demo/Core (= 1.2.3)\n  - Demo/Core (1.3.0)\n  - DemoKit (0.1.0)\n\nDEPENDENCIES:\n  - Demo (from `.`)\n"
+        );
+        assert_eq!(update_podfile_lock_version(lock, "Other", "1.3.0"), None);
+    }
+}
@@ -0,0 +1,263 @@
+//! Rendering and parsing of pre-release identifiers such as `alpha.3`.
+//!
+//! A format string combines the literal identifier `{id}` with the counter
+//! `{n}`. The counter accepts a zero-padded width, e.g. `{id}-{n:03}`
+//! renders `alpha-007`.
+
+use regex::Regex;
+use semver::Prerelease;
+
+pub const DEFAULT_PRERELEASE_FORMAT: &str = "{id}.{n}";
+
+/// Separators `--pre-release-separator` accepts between identifier and counter.
+pub const PRERELEASE_SEPARATORS: [&str; 3] = [".", "-", ""];
+
+enum Segment<'a> {
+    Literal(&'a str),
+    Id,
+    Counter { width: usize },
+}
+
+fn parse_format(fmt: &str) -> Result<Vec<Segment<'_>>, String> {
+    let mut segments = Vec::new();
+    let mut rest = fmt;
+    let mut has_counter = false;
+
+    while let Some(open) = rest.find('{') {
+        if open > 0 {
+            segments.push(Segment::Literal(&rest[..open]));
+        }
+        let close = rest[open..]
+            .find('}')
+            .map(|i| open + i)
+            .ok_or_else(|| format!("unclosed '{{' in pre-release format '{}'", fmt))?;
+
+        match &rest[open + 1..close] {
+            "id" => segments.push(Segment::Id),
+            "n" => {
+                segments.push(Segment::Counter { width: 0 });
+                has_counter = true;
+            }
+            spec => {
+                let width = spec
+                    .strip_prefix("n:0")
+                    .and_then(|w| w.parse::<usize>().ok())
+                    .ok_or_else(|| {
+                        format!("unknown placeholder '{{{}}}' in pre-release format", spec)
+                    })?;
+                segments.push(Segment::Counter { width });
+                has_counter = true;
+            }
+        }
+        rest = &rest[close + 1..];
+    }
+
+    if rest.contains('}') {
+        return Err(format!("unmatched '}}' in pre-release format '{}'", fmt));
+    }
+    if !rest.is_empty() {
+        segments.push(Segment::Literal(rest));
+    }
+    if !has_counter {
+        return Err(format!("pre-release format '{}' must contain {{n}}", fmt));
+    }
+
+    Ok(segments)
+}
+
+/// Check that `fmt` is well-formed and renders a valid semver pre-release.
+pub fn validate_prerelease_format(fmt: &str) -> Result<(), String> {
+    parse_format(fmt)?;
+
+    let sample = format_prerelease("alpha", 1, fmt);
+    Prerelease::new(&sample).map_err(|err| {
+        format!(
+            "pre-release format '{}' renders invalid pre-release '{}': {}",
+            fmt, sample, err
+        )
+    })?;
+
+    Ok(())
+}
+
+/// Render a pre-release identifier. `fmt` is expected to be validated.
+pub fn format_prerelease(id: &str, counter: u64, fmt: &str) -> String {
+    let Ok(segments) = parse_format(fmt) else {
+        return format!("{}.{}", id, counter);
+    };
+
+    let mut output = String::new();
+    for segment in segments {
+        match segment {
+            Segment::Literal(text) => output.push_str(text),
+            Segment::Id => output.push_str(id),
+            Segment::Counter { width } => output.push_str(&format!("{:0width$}", counter)),
+        }
+    }
+    output
+}
+
+/// The format placing `separator` between identifier and counter, e.g.
+/// `{id}-{n}` for `-`.
+pub fn separator_format(separator: &str) -> Result<String, String> {
+    if PRERELEASE_SEPARATORS.contains(&separator) {
+        Ok(format!("{{id}}{}{{n}}", separator))
+    } else {
+        Err(format!(
+            "invalid pre-release separator '{}': expected '.', '-' or an empty string",
+            separator
+        ))
+    }
+}
+
+/// Extract the counter from `pre` if it was rendered from `id` with any of
+/// the [`PRERELEASE_SEPARATORS`], so `alpha.3`, `alpha-3` and `alpha3` all
+/// give 3.
+pub fn prerelease_counter_any_separator(pre: &str, id: &str) -> Option<u64> {
+    PRERELEASE_SEPARATORS
+        .iter()
+        .find_map(|separator| prerelease_counter(pre, id, &separator_format(separator).ok()?))
+}
+
+/// Extract the counter from `pre` if it was rendered from `id` and `fmt`.
+pub fn prerelease_counter(pre: &str, id: &str, fmt: &str) -> Option<u64> {
+    let mut pattern = String::from("^");
+    for segment in parse_format(fmt).ok()? {
+        match segment {
+            Segment::Literal(text) => pattern.push_str(&regex::escape(text)),
+            Segment::Id => pattern.push_str(&regex::escape(id)),
+            Segment::Counter { .. } => pattern.push_str(r"(\d+)"),
+        }
+    }
+    pattern.push('$');
+
+    let caps = Regex::new(&pattern).ok()?.captures(pre)?;
+    caps.get(1)?.as_str().parse().ok()
+}
+
+/// Branches that never name a pre-release.
+const MAINLINE_BRANCHES: [&str; 3] = ["main", "master", "develop"];
+
+/// The pre-release identifier named by `branch`: its last path component
+/// with every run of characters other than ASCII letters, digits and `-`
+/// turned into a single `-`, e.g. `release/rc_2` → `rc-2`.
+///
+/// Mainline branches and names that leave no valid identifier give `None`.
+pub fn prerelease_id_from_branch(branch: &str) -> Option<String> {
+    let last = branch.rsplit('/').next().unwrap_or(branch);
+    let mut id = String::with_capacity(last.len());
+    for c in last.chars() {
+        if c.is_ascii_alphanumeric() || c == '-' {
+            id.push(c);
+        } else if !id.ends_with('-') {
+            id.push('-');
+        }
+    }
+    let id = id.trim_matches('-');
+    if id.is_empty() || MAINLINE_BRANCHES.contains(&id) {
+        return None;
+    }
+    Prerelease::new(id).is_ok().then(|| id.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_without_padding() {
+        assert_eq!(
+            format_prerelease("alpha", 3, DEFAULT_PRERELEASE_FORMAT),
+            "alpha.3"
+        );
+        assert_eq!(format_prerelease("rc", 12, "{id}.{n}"), "rc.12");
+    }
+
+    #[test]
+    fn formats_with_zero_padding() {
+        assert_eq!(format_prerelease("alpha", 3, "{id}.{n:03}"), "alpha.003");
+        assert_eq!(format_prerelease("beta", 1234, "{id}-{n:02}"), "beta-1234");
+    }
+
+    #[test]
+    fn formats_with_custom_separator() {
+        assert_eq!(format_prerelease("alpha", 3, "{id}-{n}"), "alpha-3");
+        assert_eq!(format_prerelease("nightly", 7, "{id}{n}"), "nightly7");
+    }
+
+    #[test]
+    fn formats_each_separator() {
+        for (separator, expected) in [(".", "alpha.3"), ("-", "alpha-3"), ("", "alpha3")] {
+            let fmt = separator_format(separator).unwrap();
+            assert_eq!(format_prerelease("alpha", 3, &fmt), expected);
+            assert!(validate_prerelease_format(&fmt).is_ok(), "{fmt}");
+        }
+        assert!(separator_format("_").is_err());
+        assert!(separator_format("..").is_err());
+    }
+
+    #[test]
+    fn parses_counter_with_any_separator() {
+        for pre in ["alpha.3", "alpha-3", "alpha3"] {
+            assert_eq!(
+                prerelease_counter_any_separator(pre, "alpha"),
+                Some(3),
+                "{pre}"
+            );
+        }
+        assert_eq!(prerelease_counter_any_separator("alpha.3", "beta"), None);
+        assert_eq!(prerelease_counter_any_separator("alpha_3", "alpha"), None);
+        assert_eq!(prerelease_counter_any_separator("alpha.x", "alpha"), None);
+    }
+
+    #[test]
+    fn validates_format_strings() {
+        assert!(validate_prerelease_format("{id}.{n}").is_ok());
+        assert!(validate_prerelease_format("{id}-{n:03}").is_ok());
+        assert!(validate_prerelease_format("{id}").is_err());
+        assert!(validate_prerelease_format("{id}.{count}").is_err());
+        assert!(validate_prerelease_format("{id}.{n").is_err());
+        // A purely numeric identifier may not carry leading zeros in semver.
+        assert!(validate_prerelease_format("{id}.{n:03}").is_err());
+    }
+
+    #[test]
+    fn parses_counter_back_out() {
+        assert_eq!(prerelease_counter("alpha.3", "alpha", "{id}.{n}"), Some(3));
+        assert_eq!(
+            prerelease_counter("alpha-007", "alpha", "{id}-{n:03}"),
+            Some(7)
+        );
+        assert_eq!(prerelease_counter("beta.3", "alpha", "{id}.{n}"), None);
+        assert_eq!(prerelease_counter("alpha.3", "alpha", "{id}-{n}"), None);
+    }
+
+    #[test]
+    fn derives_ids_from_branch_names() {
+        assert_eq!(
+            prerelease_id_from_branch("release/alpha").as_deref(),
+            Some("alpha")
+        );
+        assert_eq!(
+            prerelease_id_from_branch("feature/My_Feature!").as_deref(),
+            Some("My-Feature")
+        );
+        assert_eq!(
+            prerelease_id_from_branch("release/1.3.0-beta").as_deref(),
+            Some("1-3-0-beta")
+        );
+        assert_eq!(
+            prerelease_id_from_branch("nightly").as_deref(),
+            Some("nightly")
+        );
+        for branch in [
+            "main",
+            "master",
+            "origin/develop",
+            "release/__",
+            "hotfix/0123",
+        ] {
+            assert_eq!(prerelease_id_from_branch(branch), None, "{branch}");
+        }
+    }
+}
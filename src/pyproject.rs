@@ -0,0 +1,60 @@
+//! The version of a Python `pyproject.toml`: `[project]` per PEP 621, or
+//! `[tool.poetry]` for Poetry projects.
+//!
+//! A version listed in `dynamic` is computed at build time and has nothing
+//! to bump. The file is edited line by line, so comments and layout are kept.
+
+use crate::text::replace_toml_value;
+
+/// Tables that may hold the version, in order of preference.
+const VERSION_TABLES: [&str; 2] = ["project", "tool.poetry"];
+
+/// Read the project version.
+pub fn read_pyproject_version(contents: &str) -> Option<String> {
+    let manifest: toml::Table = toml::from_str(contents).ok()?;
+    let project = manifest.get("project");
+    let poetry = manifest.get("tool").and_then(|tool| tool.get("poetry"));
+    let version = [project, poetry]
+        .into_iter()
+        .flatten()
+        .find_map(|table| table.get("version")?.as_str())?;
+    Some(version.to_string())
+}
+
+/// Replace the project version, returning the new file contents.
+pub fn update_pyproject_version(contents: &str, new_version: &str) -> Option<String> {
+    VERSION_TABLES
+        .iter()
+        .find_map(|table| replace_toml_value(contents, table, "version", new_version))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_and_replaces_pep_621_and_poetry_versions() {
+        let pep621 =
+            "[project]\nname = \"demo\"\nversion = \"0.4.1\"\n\n[tool.black]\nline-length = 88\n";
+        assert_eq!(read_pyproject_version(pep621).as_deref(), Some("0.4.1"));
+        assert_eq!(
+            update_pyproject_version(pep621, "0.5.0").unwrap(),
+            pep621.replace("0.4.1", "0.5.0")
+        );
+
+        let poetry = "[tool.poetry]\nname = \"demo\"\nversion = \"2.0.0\"\n";
+        assert_eq!(read_pyproject_version(poetry).as_deref(), Some("2.0.0"));
+        assert_eq!(
+            update_pyproject_version(poetry, "2.0.1").unwrap(),
+            poetry.replace("2.0.0", "2.0.1")
+        );
+    }
+
+    #[test]
+    fn dynamic_versions_have_nothing_to_bump() {
+        let dynamic = "[project]\nname = \"demo\"\ndynamic = [\"version\"]\n";
+
+        assert_eq!(read_pyproject_version(dynamic), None);
+        assert_eq!(update_pyproject_version(dynamic, "1.0.0"), None);
+    }
+}
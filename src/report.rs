@@ -0,0 +1,90 @@
+use crate::timestamp::now_rfc3339;
+use serde::Serialize;
+use std::fs::OpenOptions;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::{env, process};
+
+/// One line of the `--report` audit trail.
+#[derive(Debug, Serialize)]
+pub struct ReportEntry {
+    pub timestamp: String,
+    pub old: String,
+    pub new: String,
+    pub bump_type: String,
+    pub user: String,
+    pub cwd: String,
+    pub files_updated: Vec<String>,
+}
+
+impl ReportEntry {
+    /// Describe a bump performed now, in the current directory, by the current user.
+    pub fn new(old: &str, new: &str, bump_type: &str, files_updated: &[PathBuf]) -> ReportEntry {
+        ReportEntry {
+            timestamp: now_rfc3339(),
+            old: old.to_string(),
+            new: new.to_string(),
+            bump_type: bump_type.to_string(),
+            user: current_user(),
+            cwd: env::current_dir()
+                .map(|dir| dir.display().to_string())
+                .unwrap_or_default(),
+            files_updated: files_updated
+                .iter()
+                .map(|path| path.display().to_string())
+                .collect(),
+        }
+    }
+}
+
+/// The git `user.email`, falling back to `$USER`.
+fn current_user() -> String {
+    let email = Command::new("git")
+        .args(["config", "user.email"])
+        .stderr(process::Stdio::null())
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+        .filter(|email| !email.is_empty());
+
+    email.unwrap_or_else(|| env::var("USER").unwrap_or_default())
+}
+
+/// Append `entry` to the NDJSON file at `path`, creating it if needed.
+///
+/// The line is emitted with a single write on an `O_APPEND` handle so
+/// concurrent writers never interleave partial entries.
+pub fn append_report_entry(path: &Path, entry: &ReportEntry) -> io::Result<()> {
+    let mut line = serde_json::to_string(entry)?;
+    line.push('\n');
+
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    file.write_all(line.as_bytes())
+}
+
+/// Append a Markdown table describing `entry` to the GitHub Actions step
+/// summary file at `path`, usually `$GITHUB_STEP_SUMMARY`.
+pub fn write_github_step_summary(path: &Path, entry: &ReportEntry) -> io::Result<()> {
+    let files = if entry.files_updated.is_empty() {
+        "none".to_string()
+    } else {
+        entry
+            .files_updated
+            .iter()
+            .map(|file| format!("`{}`", file))
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    let summary = format!(
+        "### Version bump\n\n\
+         | Old version | New version | Bump type | Files updated |\n\
+         | --- | --- | --- | --- |\n\
+         | `{}` | `{}` | {} | {} |\n\n",
+        entry.old, entry.new, entry.bump_type, files
+    );
+
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    file.write_all(summary.as_bytes())
+}
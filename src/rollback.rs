@@ -0,0 +1,102 @@
+//! Restoring files when a bump has to be undone.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Snapshot of files about to be written, restored when the guard is dropped
+/// unless [`RollbackGuard::disarm`] was called first.
+///
+/// `std::process::exit` skips destructors, so drop the guard explicitly before
+/// exiting on failure.
+#[derive(Debug)]
+pub struct RollbackGuard {
+    /// Original contents, or `None` for files that did not exist yet.
+    originals: Vec<(PathBuf, Option<Vec<u8>>)>,
+    armed: bool,
+}
+
+impl RollbackGuard {
+    /// Record the current contents of `paths`.
+    pub fn new(paths: &[PathBuf]) -> io::Result<Self> {
+        let mut originals = Vec::with_capacity(paths.len());
+        for path in paths {
+            let contents = match fs::read(path) {
+                Ok(contents) => Some(contents),
+                Err(err) if err.kind() == io::ErrorKind::NotFound => None,
+                Err(err) => return Err(err),
+            };
+            originals.push((path.clone(), contents));
+        }
+        Ok(RollbackGuard {
+            originals,
+            armed: true,
+        })
+    }
+
+    /// Keep the new contents.
+    pub fn disarm(mut self) {
+        self.armed = false;
+    }
+
+    fn restore(&self) {
+        for (path, contents) in &self.originals {
+            let result = match contents {
+                Some(contents) => fs::write(path, contents),
+                None => remove_if_present(path),
+            };
+            if let Err(err) = result {
+                eprintln!("Failed to restore {}: {}", path.display(), err);
+            }
+        }
+    }
+}
+
+fn remove_if_present(path: &Path) -> io::Result<()> {
+    match fs::remove_file(path) {
+        Err(err) if err.kind() != io::ErrorKind::NotFound => Err(err),
+        _ => Ok(()),
+    }
+}
+
+impl Drop for RollbackGuard {
+    fn drop(&mut self) {
+        if self.armed {
+            self.restore();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn restores_on_drop() {
+        let dir = tempfile::tempdir().unwrap();
+        let existing = dir.path().join("VERSION");
+        let created = dir.path().join("new.txt");
+        fs::write(&existing, "1.0.0").unwrap();
+
+        let guard = RollbackGuard::new(&[existing.clone(), created.clone()]).unwrap();
+        fs::write(&existing, "1.0.1").unwrap();
+        fs::write(&created, "1.0.1").unwrap();
+        drop(guard);
+
+        assert_eq!(fs::read_to_string(&existing).unwrap(), "1.0.0");
+        assert!(!created.exists());
+    }
+
+    #[test]
+    fn disarm_keeps_changes() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("VERSION");
+        fs::write(&path, "1.0.0").unwrap();
+
+        let guard = RollbackGuard::new(std::slice::from_ref(&path)).unwrap();
+        fs::write(&path, "1.0.1").unwrap();
+        guard.disarm();
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "1.0.1");
+    }
+}
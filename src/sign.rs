@@ -0,0 +1,74 @@
+//! Detached GPG signatures for release files.
+
+use crate::error::BumpError;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output, Stdio};
+
+/// Where the armored detached signature of `path` is written: `<path>.asc`.
+pub fn signature_path(path: &Path) -> PathBuf {
+    let mut signature = path.as_os_str().to_owned();
+    signature.push(".asc");
+    PathBuf::from(signature)
+}
+
+/// Sign `path` with `gpg --detach-sign --armor`, using `key_id` instead of
+/// the default key when given, and return the path of the `.asc` file.
+pub fn sign_file(path: &Path, key_id: Option<&str>) -> Result<PathBuf, BumpError> {
+    let signature = signature_path(path);
+    let mut args = vec!["--batch", "--yes", "--detach-sign", "--armor"];
+    if let Some(key_id) = key_id {
+        args.extend(["--local-user", key_id]);
+    }
+    let (signature_arg, path_arg) = (signature.to_string_lossy(), path.to_string_lossy());
+    args.extend(["--output", &signature_arg, &path_arg]);
+
+    gpg(&args).map(|_| signature.clone())
+}
+
+/// Check the existing `<path>.asc` signature of `path`.
+pub fn verify_signature(path: &Path) -> Result<(), BumpError> {
+    let signature = signature_path(path);
+    if !signature.is_file() {
+        return Err(BumpError::Signing(format!(
+            "no signature {} to verify",
+            signature.display()
+        )));
+    }
+    let (signature_arg, path_arg) = (signature.to_string_lossy(), path.to_string_lossy());
+    gpg(&["--batch", "--verify", &signature_arg, &path_arg]).map(|_| ())
+}
+
+fn gpg(args: &[&str]) -> Result<Output, BumpError> {
+    let output = Command::new("gpg")
+        .args(args)
+        .stdin(Stdio::null())
+        .output()
+        .map_err(|err| match err.kind() {
+            io::ErrorKind::NotFound => {
+                BumpError::Signing("gpg is not available; install GnuPG to sign files".to_string())
+            }
+            _ => BumpError::Signing(format!("could not run gpg: {}", err)),
+        })?;
+    if !output.status.success() {
+        return Err(BumpError::Signing(format!(
+            "gpg {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn signature_sits_next_to_the_file() {
+        assert_eq!(
+            signature_path(Path::new("dist/VERSION")),
+            PathBuf::from("dist/VERSION.asc")
+        );
+    }
+}
@@ -0,0 +1,122 @@
+//! Line-based editing of the root `version` of a Snapcraft `snapcraft.yaml`.
+//!
+//! A snap may leave `version` out and take it from a part through
+//! `adopt-info` instead, or use the legacy `version: git`; neither has a
+//! version to bump. The file is never parsed as YAML, so comments and
+//! layout are kept.
+
+use regex::Regex;
+use std::ops::Range;
+use std::sync::LazyLock;
+
+static VERSION: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"(?m)^version:[ \t]*(?:"([^"]*)"|'([^']*)'|([^\s#'"]+))"#).unwrap()
+});
+static ADOPT_INFO: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?m)^adopt-info:[ \t]*\S").unwrap());
+
+/// Where `snapcraft` looks for the project file, in its own order of
+/// preference.
+pub const SNAPCRAFT_PATHS: [&str; 2] = ["snapcraft.yaml", "snap/snapcraft.yaml"];
+
+/// Longest version the Snap Store accepts.
+pub const MAX_SNAPCRAFT_VERSION_LEN: usize = 32;
+
+/// Read the root `version`.
+pub fn read_snapcraft_version(contents: &str) -> Option<String> {
+    version_span(contents).map(|span| contents[span].to_string())
+}
+
+/// Replace the root `version`, returning the new file contents.
+pub fn update_snapcraft_version(contents: &str, new_version: &str) -> Option<String> {
+    let span = version_span(contents)?;
+    let mut output = contents.to_string();
+    output.replace_range(span, new_version);
+    Some(output)
+}
+
+/// Whether the snap takes its version from a part via `adopt-info`.
+pub fn uses_adopt_info(contents: &str) -> bool {
+    ADOPT_INFO.is_match(contents)
+}
+
+/// Check `version` against Snapcraft's own rules, which are stricter than
+/// semver: at most 32 characters, starting and ending with a letter or
+/// digit, and no leading `v`.
+pub fn validate_snapcraft_version(version: &str) -> Result<(), String> {
+    if version.len() > MAX_SNAPCRAFT_VERSION_LEN {
+        return Err(format!(
+            "snap version {} is {} characters long, more than the limit of {}",
+            version,
+            version.len(),
+            MAX_SNAPCRAFT_VERSION_LEN
+        ));
+    }
+    if version.starts_with(['v', 'V']) {
+        return Err(format!(
+            "snap version {} must not start with a `v`",
+            version
+        ));
+    }
+    let valid_char = |c: char| c.is_ascii_alphanumeric() || ".:+~-".contains(c);
+    let ends_alphanumeric = |c: Option<char>| c.is_some_and(|c| c.is_ascii_alphanumeric());
+    if !version.chars().all(valid_char)
+        || !ends_alphanumeric(version.chars().next())
+        || !ends_alphanumeric(version.chars().last())
+    {
+        return Err(format!(
+            "snap version {} may only contain letters, digits and `.:+~-`, and must start and end with a letter or digit",
+            version
+        ));
+    }
+    Ok(())
+}
+
+fn version_span(contents: &str) -> Option<Range<usize>> {
+    let caps = VERSION.captures(contents)?;
+    caps.get(1)
+        .or(caps.get(2))
+        .or(caps.get(3))
+        .filter(|m| m.as_str() != "git")
+        .map(|m| m.range())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SNAP: &str = "name: demo\nbase: core22\nversion: '1.4.0'\nsummary: A demo snap\nparts:\n  demo:\n    plugin: rust\n    source-tag: v1.4.0\n    version: 0.0.1\n";
+
+    #[test]
+    fn reads_and_replaces_the_root_version_only() {
+        assert_eq!(read_snapcraft_version(SNAP).as_deref(), Some("1.4.0"));
+        assert_eq!(
+            update_snapcraft_version(SNAP, "1.5.0").unwrap(),
+            SNAP.replace("'1.4.0'", "'1.5.0'")
+        );
+    }
+
+    #[test]
+    fn adopt_info_snaps_have_no_version() {
+        let snap = "name: demo\nadopt-info: demo\nparts:\n  demo:\n    override-pull: |\n      craftctl default\n      craftctl set version=1.4.0\n";
+
+        assert!(uses_adopt_info(snap));
+        assert!(!uses_adopt_info(SNAP));
+        assert_eq!(read_snapcraft_version(snap), None);
+        assert_eq!(update_snapcraft_version(snap, "1.5.0"), None);
+        assert_eq!(read_snapcraft_version("name: demo\nversion: git\n"), None);
+    }
+
+    #[test]
+    fn validates_snap_versions() {
+        assert!(validate_snapcraft_version("1.2.3").is_ok());
+        assert!(validate_snapcraft_version("1.2.3-rc.1+build.7").is_ok());
+        assert_eq!(
+            validate_snapcraft_version("v1.2.3").unwrap_err(),
+            "snap version v1.2.3 must not start with a `v`"
+        );
+        assert!(validate_snapcraft_version("1.2.3-alpha.1+build.20260101.abcdef").is_err());
+        assert!(validate_snapcraft_version("1.2.3-").is_err());
+        assert!(validate_snapcraft_version("1.2_3").is_err());
+    }
+}
@@ -0,0 +1,395 @@
+//! Reading versions from project files.
+
+use crate::checks::validate_strict_semver;
+use crate::error::BumpError;
+use serde::Deserialize;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+/// A version and the file it was read from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VersionSource {
+    pub path: PathBuf,
+    pub version: String,
+}
+
+impl VersionSource {
+    pub fn new(path: impl Into<PathBuf>, version: impl Into<String>) -> Self {
+        VersionSource {
+            path: path.into(),
+            version: version.into(),
+        }
+    }
+}
+
+/// Displays as the file name, e.g. `package.json`.
+impl fmt::Display for VersionSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.path.display())
+    }
+}
+
+#[derive(Deserialize)]
+struct VersionField {
+    version: String,
+}
+
+/// The top-level `version` string of a JSON document.
+fn read_json_version(contents: &str) -> Option<String> {
+    serde_json::from_str::<VersionField>(contents)
+        .ok()
+        .map(|json| json.version)
+}
+
+/// The `version` field of a `package.json`.
+pub fn read_package_json_version(contents: &str) -> Option<String> {
+    read_json_version(contents)
+}
+
+/// The `version` field of a `composer.json`.
+pub fn read_composer_json_version(contents: &str) -> Option<String> {
+    read_json_version(contents)
+}
+
+/// The `version` field of a `.version.json` or `--version-json` file.
+pub fn read_version_json_version(contents: &str) -> Option<String> {
+    read_json_version(contents)
+}
+
+/// The contents of a plain `VERSION` file, without surrounding whitespace
+/// or a leading `v`.
+pub fn read_version_file(contents: &str) -> Option<String> {
+    let version = contents.trim();
+    Some(version.strip_prefix('v').unwrap_or(version).to_string())
+}
+
+/// How `VERSION` is written, chosen with `--version-file-format`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum VersionFileFormat {
+    /// The bare version, e.g. `1.2.3`.
+    #[default]
+    Plain,
+    /// A strict semver 2.0.0 version followed by a newline.
+    Semver,
+    /// Keep the `v` prefix and trailing newline of the existing file.
+    Auto,
+}
+
+impl VersionFileFormat {
+    /// Contents of the `VERSION` file at `path` holding `version`, given its
+    /// current `existing` contents.
+    pub fn render(self, version: &str, existing: &str, path: &Path) -> Result<String, BumpError> {
+        match self {
+            VersionFileFormat::Plain => Ok(version.to_string()),
+            VersionFileFormat::Semver => {
+                validate_strict_semver(version, path)?;
+                Ok(format!("{}\n", version))
+            }
+            VersionFileFormat::Auto => {
+                let current = existing.trim();
+                let prefix = if current.starts_with('v') { "v" } else { "" };
+                let ending = if existing.ends_with("\r\n") {
+                    "\r\n"
+                } else if existing.ends_with('\n') {
+                    "\n"
+                } else {
+                    ""
+                };
+                Ok(format!("{}{}{}", prefix, version, ending))
+            }
+        }
+    }
+}
+
+impl fmt::Display for VersionFileFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            VersionFileFormat::Plain => "plain",
+            VersionFileFormat::Semver => "semver",
+            VersionFileFormat::Auto => "auto",
+        })
+    }
+}
+
+impl FromStr for VersionFileFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "plain" => Ok(VersionFileFormat::Plain),
+            "semver" => Ok(VersionFileFormat::Semver),
+            "auto" => Ok(VersionFileFormat::Auto),
+            _ => Err(format!(
+                "invalid VERSION file format '{}': expected auto, plain or semver",
+                s
+            )),
+        }
+    }
+}
+
+/// How the `--from` input is parsed, chosen with `--input-format`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum InputFormat {
+    /// A bare version string, read like a `VERSION` file.
+    #[default]
+    Raw,
+    Json,
+    Toml,
+    Yaml,
+}
+
+impl fmt::Display for InputFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            InputFormat::Raw => "raw",
+            InputFormat::Json => "json",
+            InputFormat::Toml => "toml",
+            InputFormat::Yaml => "yaml",
+        })
+    }
+}
+
+impl FromStr for InputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "raw" => Ok(InputFormat::Raw),
+            "json" => Ok(InputFormat::Json),
+            "toml" => Ok(InputFormat::Toml),
+            "yaml" => Ok(InputFormat::Yaml),
+            _ => Err(format!(
+                "invalid input format '{}': expected raw, json, toml or yaml",
+                s
+            )),
+        }
+    }
+}
+
+/// The version in `contents` parsed as `format`. For structured formats it
+/// is the string at `key`, a dotted path such as `package.version`; raw input
+/// ignores `key`.
+pub fn read_input_version(
+    contents: &str,
+    format: InputFormat,
+    key: &str,
+) -> Result<String, String> {
+    let document: serde_json::Value = match format {
+        InputFormat::Raw => {
+            return read_version_file(contents)
+                .filter(|version| !version.is_empty())
+                .ok_or_else(|| "input is empty".to_string());
+        }
+        InputFormat::Json => serde_json::from_str(contents).map_err(|err| err.to_string())?,
+        InputFormat::Toml => toml::from_str(contents).map_err(|err| err.to_string())?,
+        InputFormat::Yaml => serde_yaml::from_str(contents).map_err(|err| err.to_string())?,
+    };
+    key.split('.')
+        .try_fold(&document, |value, field| value.get(field))
+        .and_then(|value| value.as_str())
+        .map(str::to_string)
+        .ok_or_else(|| format!("no string at key `{}`", key))
+}
+
+/// Which project type's files a bump may touch, chosen with `--bump-scope`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum VersionScope {
+    Node,
+    Php,
+    Rust,
+    Python,
+    /// Every detected version file.
+    #[default]
+    All,
+}
+
+impl VersionScope {
+    /// File names belonging to the scope; `None` for [`VersionScope::All`].
+    fn file_names(self) -> Option<&'static [&'static str]> {
+        match self {
+            VersionScope::Node => {
+                Some(&["package.json", "package-lock.json", "npm-shrinkwrap.json"])
+            }
+            VersionScope::Php => Some(&["composer.json"]),
+            VersionScope::Rust => Some(&["Cargo.toml"]),
+            VersionScope::Python => Some(&["pyproject.toml"]),
+            VersionScope::All => None,
+        }
+    }
+
+    /// Whether the file at `path` belongs to this scope.
+    pub fn includes(self, path: &Path) -> bool {
+        let Some(names) = self.file_names() else {
+            return true;
+        };
+        path.file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| names.contains(&name))
+    }
+}
+
+impl fmt::Display for VersionScope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            VersionScope::Node => "node",
+            VersionScope::Php => "php",
+            VersionScope::Rust => "rust",
+            VersionScope::Python => "python",
+            VersionScope::All => "all",
+        })
+    }
+}
+
+impl FromStr for VersionScope {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "node" => Ok(VersionScope::Node),
+            "php" => Ok(VersionScope::Php),
+            "rust" => Ok(VersionScope::Rust),
+            "python" => Ok(VersionScope::Python),
+            "all" => Ok(VersionScope::All),
+            _ => Err(format!(
+                "invalid bump scope '{}': expected node, php, rust, python or all",
+                s
+            )),
+        }
+    }
+}
+
+/// Check that `path` holds syntactically valid JSON.
+///
+/// Run before deserializing a version source so a broken file is reported
+/// instead of silently skipped.
+pub fn validate_json_file(path: &Path) -> Result<(), BumpError> {
+    let parse_error = |message: String| BumpError::ParseError {
+        path: path.to_path_buf(),
+        message,
+    };
+
+    let contents = fs::read_to_string(path).map_err(|err| parse_error(err.to_string()))?;
+    serde_json::from_str::<serde_json::Value>(&contents)
+        .map(|_| ())
+        .map_err(|err| parse_error(err.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_json_version_fields() {
+        let package = r#"{"name": "demo", "version": "1.2.3", "private": true}"#;
+
+        assert_eq!(read_package_json_version(package).as_deref(), Some("1.2.3"));
+        assert_eq!(read_composer_json_version(r#"{"version": 1}"#), None);
+        assert_eq!(read_version_json_version("[1, 2]"), None);
+        assert_eq!(read_version_file(" 1.2.3\n").as_deref(), Some("1.2.3"));
+    }
+
+    #[test]
+    fn reads_input_version_by_format() {
+        let read = |contents: &str, format: InputFormat, key: &str| {
+            read_input_version(contents, format, key)
+        };
+
+        assert_eq!(
+            read("v1.2.3\n", InputFormat::Raw, "version").unwrap(),
+            "1.2.3"
+        );
+        assert_eq!(
+            read(
+                r#"{"app": {"version": "2.0.0"}}"#,
+                InputFormat::Json,
+                "app.version"
+            )
+            .unwrap(),
+            "2.0.0"
+        );
+        assert_eq!(
+            read(
+                "[package]\nversion = \"0.4.1\"\n",
+                InputFormat::Toml,
+                "package.version"
+            )
+            .unwrap(),
+            "0.4.1"
+        );
+        assert_eq!(
+            read("version: 3.1.0\n", InputFormat::Yaml, "version").unwrap(),
+            "3.1.0"
+        );
+        assert_eq!(
+            read("version: 1.2\n", InputFormat::Yaml, "version").unwrap_err(),
+            "no string at key `version`"
+        );
+        assert!(read("{", InputFormat::Json, "version").is_err());
+        assert!(read("  \n", InputFormat::Raw, "version").is_err());
+    }
+
+    #[test]
+    fn version_file_formats_round_trip() {
+        let path = Path::new("VERSION");
+        let round_trip = |format: VersionFileFormat, existing: &str| {
+            let written = format.render("1.3.0", existing, path).unwrap();
+            (read_version_file(&written).unwrap(), written)
+        };
+
+        assert_eq!(
+            round_trip(VersionFileFormat::Plain, "1.2.3\n"),
+            ("1.3.0".to_string(), "1.3.0".to_string())
+        );
+        assert_eq!(
+            round_trip(VersionFileFormat::Semver, "1.2.3"),
+            ("1.3.0".to_string(), "1.3.0\n".to_string())
+        );
+        assert_eq!(
+            round_trip(VersionFileFormat::Auto, "v1.2.3\r\n"),
+            ("1.3.0".to_string(), "v1.3.0\r\n".to_string())
+        );
+        assert_eq!(
+            round_trip(VersionFileFormat::Auto, "1.2.3"),
+            ("1.3.0".to_string(), "1.3.0".to_string())
+        );
+        for format in ["auto", "plain", "semver"] {
+            assert_eq!(
+                format.parse::<VersionFileFormat>().unwrap().to_string(),
+                format
+            );
+        }
+    }
+
+    #[test]
+    fn semver_format_requires_strict_versions() {
+        assert!(matches!(
+            VersionFileFormat::Semver.render("01.2.3", "", Path::new("VERSION")),
+            Err(BumpError::NotStrictSemver { .. })
+        ));
+    }
+
+    #[test]
+    fn version_source_displays_its_path() {
+        let source = VersionSource::new("packages/a/package.json", "1.2.3");
+
+        assert_eq!(source.to_string(), "packages/a/package.json");
+    }
+
+    #[test]
+    fn scopes_match_by_file_name() {
+        let scope = |s: &str| s.parse::<VersionScope>().unwrap();
+
+        assert!(scope("node").includes(Path::new("packages/web/package.json")));
+        assert!(scope("node").includes(Path::new("package-lock.json")));
+        assert!(!scope("node").includes(Path::new("composer.json")));
+        assert!(scope("php").includes(Path::new("composer.json")));
+        assert!(!scope("php").includes(Path::new("VERSION")));
+        assert!(scope("rust").includes(Path::new("crates/core/Cargo.toml")));
+        assert!(scope("python").includes(Path::new("pyproject.toml")));
+        assert!(scope("all").includes(Path::new("VERSION")));
+        assert_eq!(VersionScope::default().to_string(), "all");
+        assert!("go".parse::<VersionScope>().is_err());
+    }
+}
@@ -0,0 +1,245 @@
+//! Commit and tag messages: Handlebars templates set by `--template-file`,
+//! and git trailers from `--commit-trailer`.
+
+use crate::error::BumpError;
+use handlebars::{Handlebars, Template};
+use regex::Regex;
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+use std::sync::LazyLock;
+
+/// Variables a message template can use.
+pub const CONTEXT_FIELDS: [&str; 8] = [
+    "old_version",
+    "new_version",
+    "bump_type",
+    "files_updated",
+    "date",
+    "branch",
+    "git_sha",
+    "commits",
+];
+
+/// Variables each entry of `commits` provides, usable inside
+/// `{{#each commits}}`.
+pub const COMMIT_FIELDS: [&str; 1] = ["log_line"];
+
+/// Bare `{{name}}`, `{{{name}}}` and `{{name.field}}` expressions. Helper
+/// calls, blocks, `this` and `@` data are left alone when looking for
+/// unknown variables.
+static VARIABLE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\{\{\{?~?\s*([A-Za-z_][A-Za-z0-9_]*)(?:\.[A-Za-z0-9_.]*)?\s*~?\}").unwrap()
+});
+
+/// What a message template is rendered with.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct MessageContext {
+    pub old_version: String,
+    pub new_version: String,
+    pub bump_type: String,
+    pub files_updated: Vec<String>,
+    /// The date of the bump, as `YYYY-MM-DD` in UTC.
+    pub date: String,
+    /// The checked-out branch, empty outside a git repository.
+    pub branch: String,
+    /// HEAD before the bump commit, empty outside a git repository.
+    pub git_sha: String,
+    /// Commits since the tag of the old version, newest first.
+    pub commits: Vec<CommitLine>,
+}
+
+/// One commit, as rendered by `--git-log-format`.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct CommitLine {
+    pub log_line: String,
+}
+
+/// A compiled message template.
+#[derive(Debug)]
+pub struct MessageTemplate {
+    registry: Handlebars<'static>,
+    unknown: Vec<String>,
+}
+
+impl MessageTemplate {
+    /// Read and compile the template at `path`.
+    pub fn load(path: &Path) -> Result<Self, BumpError> {
+        let source = fs::read_to_string(path).map_err(|err| BumpError::Template {
+            path: path.to_path_buf(),
+            message: err.to_string(),
+        })?;
+        Self::parse(&source).map_err(|message| BumpError::Template {
+            path: path.to_path_buf(),
+            message,
+        })
+    }
+
+    /// Compile `source`. Output is not HTML-escaped, and variables outside
+    /// [`CONTEXT_FIELDS`] render as empty strings.
+    pub fn parse(source: &str) -> Result<Self, String> {
+        let template = Template::compile(source).map_err(|err| err.to_string())?;
+        let mut registry = Handlebars::new();
+        registry.register_escape_fn(handlebars::no_escape);
+        registry.register_template("message", template);
+
+        let mut unknown: Vec<String> = Vec::new();
+        for captures in VARIABLE.captures_iter(source) {
+            let name = &captures[1];
+            let known = CONTEXT_FIELDS.contains(&name)
+                || COMMIT_FIELDS.contains(&name)
+                || name == "this"
+                || name == "else";
+            if !known && !unknown.iter().any(|n| n == name) {
+                unknown.push(name.to_string());
+            }
+        }
+        Ok(MessageTemplate { registry, unknown })
+    }
+
+    /// Variables the template uses that the context does not provide.
+    pub fn unknown_variables(&self) -> &[String] {
+        &self.unknown
+    }
+
+    /// Render the template with `context`, trimming surrounding whitespace.
+    pub fn render(&self, context: &MessageContext) -> Result<String, String> {
+        self.registry
+            .render("message", context)
+            .map(|message| message.trim().to_string())
+            .map_err(|err| err.to_string())
+    }
+}
+
+/// Parse a `--commit-trailer` such as `Signed-off-by: Jane <jane@example.com>`
+/// into its key and value.
+pub fn parse_trailer(trailer: &str) -> Result<(String, String), String> {
+    let invalid = || {
+        format!(
+            "invalid commit trailer '{}': expected 'Key: value'",
+            trailer
+        )
+    };
+    let (key, value) = trailer.split_once(':').ok_or_else(invalid)?;
+    let (key, value) = (key.trim(), value.trim());
+    if key.is_empty()
+        || value.is_empty()
+        || !key.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+    {
+        return Err(invalid());
+    }
+    Ok((key.to_string(), value.to_string()))
+}
+
+/// Append `trailers` to `message` in git's trailer format: a blank line,
+/// then one `Key: value` line per trailer, in order.
+pub fn append_trailers(message: &str, trailers: &[(String, String)]) -> String {
+    if trailers.is_empty() {
+        return message.to_string();
+    }
+    let mut output = format!("{}\n", message.trim_end());
+    output.push('\n');
+    for (key, value) in trailers {
+        output.push_str(&format!("{}: {}\n", key, value));
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn context() -> MessageContext {
+        MessageContext {
+            old_version: "1.0.0".to_string(),
+            new_version: "1.1.0".to_string(),
+            bump_type: "minor".to_string(),
+            files_updated: vec!["VERSION".to_string(), "package.json".to_string()],
+            date: "2024-03-05".to_string(),
+            branch: "main".to_string(),
+            git_sha: "abc123".to_string(),
+            commits: vec![
+                CommitLine {
+                    log_line: "Jane: Fix parser".to_string(),
+                },
+                CommitLine {
+                    log_line: "Sam: Add flag".to_string(),
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn renders_context_without_escaping() {
+        let template = MessageTemplate::parse(
+            "release: {{old_version}} -> {{new_version}} <{{bump_type}}>\n\n\
+             {{#each files_updated}}- {{this}}\n{{/each}}",
+        )
+        .unwrap();
+
+        assert_eq!(
+            template.render(&context()).unwrap(),
+            "release: 1.0.0 -> 1.1.0 <minor>\n\n- VERSION\n- package.json"
+        );
+        assert!(template.unknown_variables().is_empty());
+    }
+
+    #[test]
+    fn renders_commit_log_lines() {
+        let template =
+            MessageTemplate::parse("{{#each commits}}- {{log_line}}\n{{/each}}").unwrap();
+
+        assert_eq!(
+            template.render(&context()).unwrap(),
+            "- Jane: Fix parser\n- Sam: Add flag"
+        );
+        assert!(template.unknown_variables().is_empty());
+    }
+
+    #[test]
+    fn unknown_variables_render_empty() {
+        let template =
+            MessageTemplate::parse("{{new_version}}{{ticket}} {{#if branch}}{{author}}{{/if}}")
+                .unwrap();
+
+        assert_eq!(template.unknown_variables(), ["ticket", "author"]);
+        assert_eq!(template.render(&context()).unwrap(), "1.1.0");
+    }
+
+    #[test]
+    fn appends_trailers_after_a_blank_line() {
+        let trailers = [
+            (
+                "Signed-off-by".to_string(),
+                "Jane <jane@example.com>".to_string(),
+            ),
+            ("Refs".to_string(), "#42".to_string()),
+        ];
+
+        assert_eq!(
+            append_trailers("Bump version to 1.0.1\n", &trailers),
+            "Bump version to 1.0.1\n\nSigned-off-by: Jane <jane@example.com>\nRefs: #42\n"
+        );
+        assert_eq!(append_trailers("Bump", &[]), "Bump");
+    }
+
+    #[test]
+    fn parses_trailers() {
+        assert_eq!(
+            parse_trailer("Reviewed-by:  Sam <sam@example.com> ").unwrap(),
+            (
+                "Reviewed-by".to_string(),
+                "Sam <sam@example.com>".to_string()
+            )
+        );
+        assert_eq!(parse_trailer("Refs: a:b").unwrap().1, "a:b");
+        for invalid in ["no separator", ": value", "Key:", "Two words: value"] {
+            assert!(parse_trailer(invalid).is_err(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn rejects_malformed_templates() {
+        assert!(MessageTemplate::parse("{{#if branch}}unclosed").is_err());
+    }
+}
@@ -0,0 +1,96 @@
+//! Helpers for keeping rewritten files byte-compatible with their originals.
+
+use regex::Regex;
+
+/// Whether `contents` uses Windows-style `\r\n` line endings.
+pub fn uses_crlf(contents: &str) -> bool {
+    contents.contains("\r\n")
+}
+
+/// Convert the line endings of `content` to `\r\n` when `crlf` is set, or to
+/// `\n` otherwise.
+pub fn normalize_line_endings(content: &str, crlf: bool) -> String {
+    let lf = content.replace("\r\n", "\n");
+    if crlf { lf.replace('\n', "\r\n") } else { lf }
+}
+
+/// Replace the quoted string value of `key` directly in the TOML `[table]`,
+/// returning the new contents, or `None` when the table has no such key.
+///
+/// Only the value changes, so comments and layout are kept.
+pub fn replace_toml_value(contents: &str, table: &str, key: &str, value: &str) -> Option<String> {
+    let entry = Regex::new(&format!(
+        r#"^(\s*{}\s*=\s*)("|')[^"']*("|')"#,
+        regex::escape(key)
+    ))
+    .unwrap();
+
+    let mut output = String::with_capacity(contents.len());
+    let (mut in_table, mut replaced) = (false, false);
+    for line in contents.split_inclusive('\n') {
+        if let Some(header) = line.trim_start().strip_prefix('[') {
+            // `[[array]]` headers never match: their name keeps a `[`.
+            in_table = header
+                .split(']')
+                .next()
+                .is_some_and(|header| header.trim() == table);
+        } else if in_table && !replaced {
+            if let Some(caps) = entry.captures(line) {
+                output.push_str(&format!("{}{}{}{}", &caps[1], &caps[2], value, &caps[3]));
+                output.push_str(&line[caps[0].len()..]);
+                replaced = true;
+                continue;
+            }
+        }
+        output.push_str(line);
+    }
+    replaced.then_some(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_lf_to_crlf() {
+        assert_eq!(
+            normalize_line_endings("{\n  \"a\": 1\n}", true),
+            "{\r\n  \"a\": 1\r\n}"
+        );
+    }
+
+    #[test]
+    fn does_not_double_existing_crlf() {
+        assert_eq!(normalize_line_endings("a\r\nb\n", true), "a\r\nb\r\n");
+        assert_eq!(normalize_line_endings("a\r\nb\n", false), "a\nb\n");
+    }
+
+    #[test]
+    fn replaces_toml_values_in_their_table_only() {
+        let manifest = "[package]\nname = \"demo\"\nversion = '1.2.3' # released\n\n[[bin]]\nversion = \"0.1.0\"\n\n[dependencies]\nversion = \"1\"\n";
+
+        assert_eq!(
+            replace_toml_value(manifest, "package", "version", "1.3.0").unwrap(),
+            manifest.replace("'1.2.3'", "'1.3.0'")
+        );
+        assert_eq!(
+            replace_toml_value(manifest, "bin", "version", "2.0.0"),
+            None
+        );
+        assert_eq!(
+            replace_toml_value(
+                "[package]\nname = \"demo\"\n",
+                "package",
+                "version",
+                "1.0.0"
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn detects_crlf() {
+        assert!(uses_crlf("a\r\nb"));
+        assert!(!uses_crlf("a\nb"));
+    }
+}
@@ -0,0 +1,131 @@
+//! Explicit workspace definitions such as `semver-workspace.toml`:
+//!
+//! ```toml
+//! members = [
+//!     "packages/a",
+//!     { path = "packages/b", bump_type = "minor" },
+//!     { path = "packages/legacy", enabled = false },
+//! ]
+//! ```
+
+use crate::bump::BumpType;
+use crate::error::BumpError;
+use serde::Deserialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Deserialize)]
+pub struct WorkspaceConfig {
+    #[serde(default)]
+    pub members: Vec<WorkspaceMember>,
+}
+
+/// A member directory, its optional bump override and whether it is bumped at all.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(from = "MemberSpec")]
+pub struct WorkspaceMember {
+    pub path: PathBuf,
+    pub bump_type: Option<String>,
+    pub enabled: bool,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum MemberSpec {
+    Path(PathBuf),
+    Table {
+        path: PathBuf,
+        #[serde(default)]
+        bump_type: Option<String>,
+        #[serde(default = "enabled_by_default")]
+        enabled: bool,
+    },
+}
+
+fn enabled_by_default() -> bool {
+    true
+}
+
+impl From<MemberSpec> for WorkspaceMember {
+    fn from(spec: MemberSpec) -> Self {
+        match spec {
+            MemberSpec::Path(path) => WorkspaceMember {
+                path,
+                bump_type: None,
+                enabled: true,
+            },
+            MemberSpec::Table {
+                path,
+                bump_type,
+                enabled,
+            } => WorkspaceMember {
+                path,
+                bump_type,
+                enabled,
+            },
+        }
+    }
+}
+
+/// Load a workspace definition. Member paths are resolved against the
+/// directory containing the file.
+pub fn read_workspace_config(path: &Path) -> Result<WorkspaceConfig, BumpError> {
+    let invalid = |message: String| BumpError::InvalidConfig {
+        path: path.to_path_buf(),
+        message,
+    };
+
+    let contents = fs::read_to_string(path).map_err(|err| invalid(err.to_string()))?;
+    let mut config: WorkspaceConfig =
+        toml::from_str(&contents).map_err(|err| invalid(err.message().to_string()))?;
+
+    let base = path.parent().unwrap_or(Path::new(""));
+    for member in &mut config.members {
+        if let Some(bump) = &member.bump_type {
+            if bump.parse::<BumpType>().is_err() {
+                return Err(invalid(format!(
+                    "invalid bump_type '{}' for member {}",
+                    bump,
+                    member.path.display()
+                )));
+            }
+        }
+        member.path = base.join(&member.path);
+    }
+
+    Ok(config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accepts_plain_paths_and_tables() {
+        let config: WorkspaceConfig = toml::from_str(
+            r#"members = ["a", { path = "b", bump_type = "minor" }, { path = "c", enabled = false }]"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            config.members,
+            vec![
+                WorkspaceMember {
+                    path: "a".into(),
+                    bump_type: None,
+                    enabled: true,
+                },
+                WorkspaceMember {
+                    path: "b".into(),
+                    bump_type: Some("minor".to_string()),
+                    enabled: true,
+                },
+                WorkspaceMember {
+                    path: "c".into(),
+                    bump_type: None,
+                    enabled: false,
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,144 @@
+//! Writing several files at once.
+
+use crate::error::BumpError;
+use std::fs;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+use std::thread;
+
+/// Write every `(path, contents)` pair on its own thread and return one result
+/// per update, in the same order.
+///
+/// Each file is written in place, so targets may live on different
+/// filesystems; a failure on one does not stop the others.
+pub fn write_all_concurrent(updates: Vec<(PathBuf, String)>) -> Vec<Result<(), BumpError>> {
+    let handles: Vec<_> = updates
+        .into_iter()
+        .map(|(path, contents)| thread::spawn(move || write_file(&path, &contents)))
+        .collect();
+
+    handles
+        .into_iter()
+        .map(|handle| {
+            handle.join().unwrap_or_else(|_| {
+                Err(BumpError::Write {
+                    path: PathBuf::new(),
+                    source: io::Error::other("writer thread panicked"),
+                })
+            })
+        })
+        .collect()
+}
+
+/// Replace the contents of `path`. A read-only file is an error rather than
+/// being replaced.
+pub fn write_file(path: &Path, contents: &str) -> Result<(), BumpError> {
+    let write_error = |source| BumpError::Write {
+        path: path.to_path_buf(),
+        source,
+    };
+    if fs::metadata(path).is_ok_and(|meta| meta.permissions().readonly()) {
+        return Err(write_error(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            "file is read-only",
+        )));
+    }
+    fs::write(path, contents).map_err(write_error)
+}
+
+/// Copy `src`, a path relative to the project root, to the same relative
+/// path under `dest_dir`, creating intermediate directories.
+///
+/// `dest_dir` itself must exist. A read-only destination file is an error
+/// rather than being replaced.
+pub fn mirror_file(src: &Path, dest_dir: &Path) -> Result<(), BumpError> {
+    let mut relative = PathBuf::new();
+    for component in src.components() {
+        match component {
+            Component::CurDir => {}
+            Component::Normal(part) => relative.push(part),
+            _ => {
+                return Err(BumpError::Write {
+                    path: src.to_path_buf(),
+                    source: io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        "only files inside the project can be mirrored",
+                    ),
+                });
+            }
+        }
+    }
+
+    let dest = dest_dir.join(relative);
+    let write_error = |source| BumpError::Write {
+        path: dest.clone(),
+        source,
+    };
+    if !dest_dir.is_dir() {
+        return Err(write_error(io::Error::new(
+            io::ErrorKind::NotFound,
+            "mirror directory does not exist",
+        )));
+    }
+    if fs::metadata(&dest).is_ok_and(|meta| meta.permissions().readonly()) {
+        return Err(write_error(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            "destination file is read-only",
+        )));
+    }
+    if let Some(parent) = dest.parent() {
+        fs::create_dir_all(parent).map_err(write_error)?;
+    }
+    fs::copy(src, &dest).map(|_| ()).map_err(write_error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn writes_all_files_and_keeps_order_of_errors() {
+        let dir = tempfile::tempdir().unwrap();
+        let ok_a = dir.path().join("a");
+        let missing = dir.path().join("missing/b");
+        let ok_c = dir.path().join("c");
+
+        let results = write_all_concurrent(vec![
+            (ok_a.clone(), "1.0.1".to_string()),
+            (missing.clone(), "1.0.1".to_string()),
+            (ok_c.clone(), "1.0.1".to_string()),
+        ]);
+
+        assert!(results[0].is_ok());
+        assert!(matches!(&results[1], Err(BumpError::Write { path, .. }) if *path == missing));
+        assert!(results[2].is_ok());
+        assert_eq!(fs::read_to_string(ok_a).unwrap(), "1.0.1");
+        assert_eq!(fs::read_to_string(ok_c).unwrap(), "1.0.1");
+    }
+
+    #[test]
+    fn refuses_read_only_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("VERSION");
+        fs::write(&path, "1.0.0").unwrap();
+        let mut permissions = fs::metadata(&path).unwrap().permissions();
+        permissions.set_readonly(true);
+        fs::set_permissions(&path, permissions).unwrap();
+
+        assert!(matches!(
+            write_file(&path, "1.0.1"),
+            Err(BumpError::Write { source, .. }) if source.kind() == io::ErrorKind::PermissionDenied
+        ));
+        assert_eq!(fs::read_to_string(&path).unwrap(), "1.0.0");
+    }
+
+    #[test]
+    fn refuses_paths_outside_the_project() {
+        let dest = tempfile::tempdir().unwrap();
+
+        assert!(matches!(
+            mirror_file(Path::new("../VERSION"), dest.path()),
+            Err(BumpError::Write { source, .. }) if source.kind() == io::ErrorKind::InvalidInput
+        ));
+    }
+}
@@ -1,5 +1,5 @@
 use assert_cmd::Command;
-use predicates::str::contains;
+use predicates::{prelude::*, str::contains};
 use std::{
     fs,
     path::{Path, PathBuf},
@@ -186,3 +186,5514 @@ fn test_preserves_key_order_in_package_json() {
                 .unwrap()
     );
 }
+
+#[test]
+fn test_verbose_lists_each_file_on_stderr() {
+    let path = make_test_dir("verbose-log");
+
+    write_file(&path, "package.json", r#"{ "version": "1.0.0" }"#);
+    write_file(&path, "composer.json", r#"{ "name": "no-version" }"#);
+    write_file(&path, "VERSION", "1.0.0");
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .arg("--bump")
+        .arg("minor")
+        .arg("--verbose")
+        .assert()
+        .success()
+        .stdout(contains("Updated:").not())
+        .stderr(contains("Updated: package.json"))
+        .stderr(contains("Updated: VERSION"))
+        .stderr(contains("Skipped (no version field): composer.json"));
+}
+
+#[test]
+fn test_output_file_captures_stdout() {
+    let path = make_test_dir("output-file");
+
+    write_file(&path, "VERSION", "1.0.0");
+    write_file(&path, "bump.log", "stale\n");
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args(["--bump", "patch", "--output-file", "bump.log"])
+        .assert()
+        .success()
+        .stdout("");
+
+    assert_eq!(
+        fs::read_to_string(path.join("bump.log")).unwrap(),
+        "Current version: 1.0.0\nBumping version 1.0.0 → 1.0.1\n"
+    );
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args(["--bump", "patch", "--output-file", "bump.log", "--append"])
+        .assert()
+        .success()
+        .stdout("");
+
+    assert_eq!(
+        fs::read_to_string(path.join("bump.log")).unwrap(),
+        "Current version: 1.0.0\nBumping version 1.0.0 → 1.0.1\n\
+         Current version: 1.0.1\nBumping version 1.0.1 → 1.0.2\n"
+    );
+}
+
+#[test]
+fn test_output_file_captures_unterminated_output() {
+    let path = make_test_dir("output-file-no-newline");
+
+    write_file(&path, "VERSION", "1.0.0");
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args([
+            "--print-version",
+            "--no-newline",
+            "--output-file",
+            "out.txt",
+        ])
+        .assert()
+        .success()
+        .stdout("");
+    assert_eq!(fs::read_to_string(path.join("out.txt")).unwrap(), "1.0.0");
+
+    write_file(
+        &path,
+        "VERSION.history",
+        "2024-03-05T14:07:09Z 0.9.0 → 1.0.0 (major)\n",
+    );
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args(["--output-file", "out.txt", "history"])
+        .assert()
+        .success()
+        .stdout("");
+    assert_eq!(
+        fs::read_to_string(path.join("out.txt")).unwrap(),
+        "2024-03-05 14:07:09  0.9.0 → 1.0.0  major\n"
+    );
+}
+
+#[test]
+fn test_output_file_keeps_errors_on_stderr() {
+    let path = make_test_dir("output-file-errors");
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args(["--bump", "patch", "--output-file", "bump.log"])
+        .assert()
+        .failure()
+        .stderr(contains("No version found"));
+
+    assert_eq!(fs::read_to_string(path.join("bump.log")).unwrap(), "");
+}
+
+#[test]
+fn test_dry_run_verbose_does_not_write() {
+    let path = make_test_dir("dry-run-verbose");
+
+    write_file(&path, "package.json", r#"{ "version": "1.0.0" }"#);
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .arg("--bump")
+        .arg("major")
+        .arg("--dry-run")
+        .arg("-v")
+        .assert()
+        .success()
+        .stdout(contains("1.0.0 → 2.0.0"))
+        .stderr(contains("Would update: package.json"));
+
+    let unchanged = fs::read_to_string(path.join("package.json")).unwrap();
+    assert_eq!(unchanged, r#"{ "version": "1.0.0" }"#);
+}
+
+#[test]
+fn test_diff_subcommand_prints_change_kind() {
+    let cases = [
+        ("1.2.3", "2.0.0", "major"),
+        ("1.2.3", "1.3.0", "minor"),
+        ("1.2.3", "1.2.4", "patch"),
+        ("1.2.3-rc.1", "1.2.3", "prerelease"),
+        ("1.2.3", "1.2.3", "none"),
+        ("1.2.3", "1.2.0", "downgrade"),
+    ];
+
+    for (old, new, expected) in cases {
+        let mut cmd = Command::cargo_bin("semver").unwrap();
+        cmd.args(["diff", old, new])
+            .assert()
+            .success()
+            .stdout(format!("{}\n", expected));
+    }
+}
+
+#[test]
+fn test_bumps_azure_pipelines_variables() {
+    let path = make_test_dir("azure-pipelines");
+
+    write_file(
+        &path,
+        "azure-pipelines.yml",
+        r#"# Release pipeline
+trigger:
+- main
+
+variables:
+- group: release-secrets
+- name: version
+  value: '3.1.4' # kept in sync by semver
+- name: buildConfiguration
+  value: Release
+
+pool:
+  vmImage: ubuntu-latest
+"#,
+    );
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args([
+            "--azure-pipelines",
+            "azure-pipelines.yml",
+            "--bump",
+            "minor",
+        ])
+        .assert()
+        .success()
+        .stdout(contains("3.1.4 → 3.2.0"));
+
+    let updated = fs::read_to_string(path.join("azure-pipelines.yml")).unwrap();
+    assert!(updated.contains("  value: '3.2.0' # kept in sync by semver\n"));
+    assert!(updated.starts_with("# Release pipeline\n"));
+    assert!(updated.contains("  value: Release\n"));
+}
+
+#[test]
+fn test_lockfile_serializes_concurrent_bumps() {
+    let path = make_test_dir("lockfile-concurrent");
+
+    write_file(&path, "VERSION", "1.0.0");
+
+    let bin = assert_cmd::cargo::cargo_bin("semver");
+    let children: Vec<_> = (0..2)
+        .map(|_| {
+            std::process::Command::new(&bin)
+                .current_dir(&path)
+                .args(["--lockfile", "--bump", "patch"])
+                .stdout(std::process::Stdio::null())
+                .spawn()
+                .unwrap()
+        })
+        .collect();
+
+    for mut child in children {
+        assert!(child.wait().unwrap().success());
+    }
+
+    let updated = fs::read_to_string(path.join("VERSION")).unwrap();
+    assert_eq!(updated.trim(), "1.0.2");
+}
+
+#[test]
+fn test_lock_timeout_exits_with_code_12() {
+    use fs2::FileExt;
+
+    let path = make_test_dir("lockfile-timeout");
+
+    write_file(&path, "VERSION", "1.0.0");
+    let held = fs::File::create(path.join("release.lock")).unwrap();
+    held.lock_exclusive().unwrap();
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args(["--lockfile", "release.lock", "--lock-timeout", "1"])
+        .args(["--bump", "patch"])
+        .assert()
+        .code(12)
+        .stderr(contains("Could not acquire lock release.lock"));
+
+    let unchanged = fs::read_to_string(path.join("VERSION")).unwrap();
+    assert_eq!(unchanged, "1.0.0");
+}
+
+#[test]
+fn test_pre_release_starts_and_continues_cycle() {
+    let path = make_test_dir("pre-release-cycle");
+
+    write_file(&path, "VERSION", "1.2.3");
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args(["--bump", "minor", "--pre-release", "alpha"])
+        .assert()
+        .success()
+        .stdout(contains("1.2.3 → 1.3.0-alpha.1"));
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args(["--bump", "minor", "--pre-release", "alpha"])
+        .assert()
+        .success()
+        .stdout(contains("1.3.0-alpha.1 → 1.3.0-alpha.2"));
+}
+
+#[test]
+fn test_pre_release_format_controls_counter_layout() {
+    let path = make_test_dir("pre-release-format");
+
+    write_file(&path, "VERSION", "2.0.0-rc-009");
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args(["--bump", "patch", "--pre-release", "rc"])
+        .args(["--pre-release-format", "{id}-{n:03}"])
+        .assert()
+        .success()
+        .stdout(contains("2.0.0-rc-009 → 2.0.0-rc-010"));
+}
+
+#[test]
+fn test_pre_release_separator_continues_any_style() {
+    let path = make_test_dir("pre-release-separator");
+
+    write_file(&path, "VERSION", "2.0.0-alpha.4");
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args(["--bump", "patch", "--pre-release", "alpha"])
+        .args(["--pre-release-separator", ""])
+        .assert()
+        .success()
+        .stdout(contains("2.0.0-alpha.4 → 2.0.0-alpha5"));
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args(["--bump", "patch", "--pre-release", "alpha"])
+        .args(["--pre-release-separator", "_"])
+        .assert()
+        .code(2)
+        .stderr(contains("invalid pre-release separator '_'"));
+}
+
+#[test]
+fn test_structured_version_json_is_detected_and_updated() {
+    let path = make_test_dir("structured-version-json");
+
+    write_file(
+        &path,
+        "VERSION.json",
+        r#"{ "name": "demo", "major": 1, "minor": 4, "patch": 9 }"#,
+    );
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args(["--bump", "minor"])
+        .assert()
+        .success()
+        .stdout(contains("1.4.9 → 1.5.0"));
+
+    let updated: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(path.join("VERSION.json")).unwrap()).unwrap();
+    assert_eq!(
+        updated,
+        serde_json::json!({ "name": "demo", "major": 1, "minor": 5, "patch": 0 })
+    );
+}
+
+#[test]
+fn test_version_json_structured_explicit_path() {
+    let path = make_test_dir("structured-version-json-explicit");
+
+    fs::create_dir_all(path.join("meta")).unwrap();
+    write_file(&path, "VERSION", "2.3.4");
+    write_file(
+        &path,
+        "meta/version.json",
+        "{\n  \"major\": 2,\n  \"minor\": 3,\n  \"patch\": 4\n}",
+    );
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args([
+            "--bump",
+            "major",
+            "--version-json-structured",
+            "meta/version.json",
+        ])
+        .assert()
+        .success();
+
+    assert_eq!(fs::read_to_string(path.join("VERSION")).unwrap(), "3.0.0");
+    assert_eq!(
+        fs::read_to_string(path.join("meta/version.json")).unwrap(),
+        "{\n  \"major\": 3,\n  \"minor\": 0,\n  \"patch\": 0\n}"
+    );
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args(["--bump", "patch", "--pre-release", "rc"])
+        .args(["--version-json-structured", "meta/version.json"])
+        .assert()
+        .success()
+        .stderr(contains("only holds major, minor and patch"));
+
+    assert_eq!(
+        fs::read_to_string(path.join("VERSION")).unwrap(),
+        "3.0.1-rc.1"
+    );
+    assert!(
+        fs::read_to_string(path.join("meta/version.json"))
+            .unwrap()
+            .contains("\"major\": 3")
+    );
+}
+
+#[test]
+fn test_warn_major_bump_requires_typing_yes() {
+    let path = make_test_dir("warn-major-bump");
+
+    write_file(&path, "VERSION", "1.4.2");
+
+    for declined in ["", "y\n"] {
+        let mut cmd = Command::cargo_bin("semver").unwrap();
+        cmd.current_dir(&path)
+            .args(["--bump", "major", "--warn-major-bump"])
+            .write_stdin(declined)
+            .assert()
+            .success()
+            .stderr(contains(
+                "WARNING: You are about to bump the MAJOR version from 1.x.x to 2.0.0",
+            ))
+            .stdout(contains("Major bump cancelled"));
+        assert_eq!(fs::read_to_string(path.join("VERSION")).unwrap(), "1.4.2");
+    }
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args(["--bump", "major", "--warn-major-bump"])
+        .write_stdin("yes\n")
+        .assert()
+        .success();
+    assert_eq!(fs::read_to_string(path.join("VERSION")).unwrap(), "2.0.0");
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args(["--bump", "minor", "--warn-major-bump"])
+        .assert()
+        .success()
+        .stderr(contains("WARNING").not());
+    assert_eq!(fs::read_to_string(path.join("VERSION")).unwrap(), "2.1.0");
+}
+
+#[test]
+fn test_warn_major_bump_in_ci() {
+    let path = make_test_dir("warn-major-bump-ci");
+
+    write_file(&path, "VERSION", "1.4.2");
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args(["--bump", "major", "--warn-major-bump", "--ci"])
+        .write_stdin("yes\n")
+        .assert()
+        .code(20)
+        .stderr(contains("pass --allow-major-bump to override"));
+    assert_eq!(fs::read_to_string(path.join("VERSION")).unwrap(), "1.4.2");
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args(["--bump", "major", "--warn-major-bump", "--ci"])
+        .arg("--allow-major-bump")
+        .assert()
+        .success()
+        .stderr(contains("WARNING: You are about to bump the MAJOR version"));
+    assert_eq!(fs::read_to_string(path.join("VERSION")).unwrap(), "2.0.0");
+}
+
+#[test]
+fn test_invalid_pre_release_format_is_rejected() {
+    let path = make_test_dir("pre-release-format-invalid");
+
+    write_file(&path, "VERSION", "1.0.0");
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args(["--bump", "patch", "--pre-release", "alpha"])
+        .args(["--pre-release-format", "{id}.{count}"])
+        .assert()
+        .failure()
+        .stderr(contains("unknown placeholder"));
+
+    let unchanged = fs::read_to_string(path.join("VERSION")).unwrap();
+    assert_eq!(unchanged, "1.0.0");
+}
+
+const DEMO_GEMSPEC: &str = r#"# frozen_string_literal: true
+
+require_relative "lib/demo/version"
+
+Gem::Specification.new do |spec|
+  spec.name = "demo"
+  spec.version = "0.9.2"
+  spec.authors = ["Jane Doe"]
+  spec.summary = "A demo gem"
+  spec.required_ruby_version = ">= 3.0.0"
+
+  spec.add_dependency "rake", "~> 13.0"
+end
+"#;
+
+#[test]
+fn test_bumps_explicit_gemspec() {
+    let path = make_test_dir("gemspec-explicit");
+
+    fs::create_dir_all(path.join("gems")).unwrap();
+    write_file(&path, "gems/demo.gemspec", DEMO_GEMSPEC);
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args(["--gemspec", "gems/demo.gemspec", "--bump", "minor"])
+        .assert()
+        .success()
+        .stdout(contains("0.9.2 → 0.10.0"));
+
+    let updated = fs::read_to_string(path.join("gems/demo.gemspec")).unwrap();
+    assert_eq!(updated, DEMO_GEMSPEC.replace("0.9.2", "0.10.0"));
+}
+
+#[test]
+fn test_autodetects_single_gemspec() {
+    let path = make_test_dir("gemspec-autodetect");
+
+    write_file(&path, "demo.gemspec", DEMO_GEMSPEC);
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args(["--bump", "patch", "-v"])
+        .assert()
+        .success()
+        .stdout(contains("0.9.2 → 0.9.3"))
+        .stderr(contains("Updated: demo.gemspec"));
+}
+
+const DEMO_PODSPEC: &str = r#"Pod::Spec.new do |s|
+  s.name         = 'Demo'
+  s.version      = '2.4.0'
+  s.summary      = 'A demo pod'
+  s.swift_version = '5.9'
+  s.source       = { :git => 'https://example.com/demo.git', :tag => s.version.to_s }
+end
+"#;
+
+const DEMO_PODFILE_LOCK: &str = r#"PODS:
+  - Demo (2.4.0):
+    - Demo/Core (= 2.4.0)
+  - Demo/Core (2.4.0)
+  - Alamofire (5.8.1)
+
+DEPENDENCIES:
+  - Demo (from `.`)
+  - Alamofire (~> 5.8)
+
+COCOAPODS: 1.15.2
+"#;
+
+#[test]
+fn test_bumps_podspec_and_podfile_lock() {
+    let path = make_test_dir("podspec-autodetect");
+
+    write_file(&path, "Demo.podspec", DEMO_PODSPEC);
+    write_file(&path, "Podfile.lock", DEMO_PODFILE_LOCK);
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args(["--bump", "minor", "-v"])
+        .assert()
+        .success()
+        .stdout(contains("2.4.0 → 2.5.0"))
+        .stderr(contains("Updated: Demo.podspec"))
+        .stderr(contains("Updated: Podfile.lock"));
+
+    let podspec = fs::read_to_string(path.join("Demo.podspec")).unwrap();
+    assert_eq!(podspec, DEMO_PODSPEC.replace("2.4.0", "2.5.0"));
+    let lock = fs::read_to_string(path.join("Podfile.lock")).unwrap();
+    assert_eq!(
+        lock,
+        DEMO_PODFILE_LOCK
+            .replace("- Demo (2.4.0)", "- Demo (2.5.0)")
+            .replace("- Demo/Core (2.4.0)", "- Demo/Core (2.5.0)")
+    );
+}
+
+const DEMO_PODSPEC_JSON: &str = r#"{
+  "name": "Demo",
+  "version": "2.4.0",
+  "summary": "A demo pod"
+}
+"#;
+
+#[test]
+fn test_bumps_podspec_and_podspec_json_together() {
+    let path = make_test_dir("podspec-json-both");
+
+    write_file(&path, "Demo.podspec", DEMO_PODSPEC);
+    write_file(&path, "Demo.podspec.json", DEMO_PODSPEC_JSON);
+    write_file(&path, "Podfile.lock", DEMO_PODFILE_LOCK);
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args(["--bump", "minor", "-v"])
+        .assert()
+        .success()
+        .stdout(contains("2.4.0 → 2.5.0"))
+        .stderr(contains("Updated: Demo.podspec\n"))
+        .stderr(contains("Updated: Demo.podspec.json"));
+
+    let podspec = fs::read_to_string(path.join("Demo.podspec")).unwrap();
+    assert_eq!(podspec, DEMO_PODSPEC.replace("2.4.0", "2.5.0"));
+    let json = fs::read_to_string(path.join("Demo.podspec.json")).unwrap();
+    assert_eq!(
+        json.trim_end(),
+        DEMO_PODSPEC_JSON.replace("2.4.0", "2.5.0").trim_end()
+    );
+    let lock = fs::read_to_string(path.join("Podfile.lock")).unwrap();
+    assert!(lock.contains("- Demo (2.5.0):"));
+}
+
+#[test]
+fn test_bumps_podspec_json_alone() {
+    let path = make_test_dir("podspec-json-only");
+
+    write_file(&path, "Demo.podspec.json", DEMO_PODSPEC_JSON);
+    write_file(&path, "Podfile.lock", DEMO_PODFILE_LOCK);
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args(["--bump", "patch"])
+        .assert()
+        .success()
+        .stdout(contains("2.4.0 → 2.4.1"));
+
+    let json = fs::read_to_string(path.join("Demo.podspec.json")).unwrap();
+    assert_eq!(
+        json.trim_end(),
+        DEMO_PODSPEC_JSON.replace("2.4.0", "2.4.1").trim_end()
+    );
+    let lock = fs::read_to_string(path.join("Podfile.lock")).unwrap();
+    assert!(lock.contains("- Demo/Core (2.4.1)"));
+}
+
+#[test]
+fn test_bumps_explicit_podspec_among_several() {
+    let path = make_test_dir("podspec-explicit");
+
+    write_file(&path, "Demo.podspec", DEMO_PODSPEC);
+    write_file(
+        &path,
+        "Other.podspec",
+        &DEMO_PODSPEC.replace("Demo", "Other"),
+    );
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args(["--bump", "patch", "--podspec", "Demo.podspec"])
+        .assert()
+        .success()
+        .stdout(contains("2.4.0 → 2.4.1"));
+
+    let updated = fs::read_to_string(path.join("Demo.podspec")).unwrap();
+    assert_eq!(updated, DEMO_PODSPEC.replace("2.4.0", "2.4.1"));
+    let other = fs::read_to_string(path.join("Other.podspec")).unwrap();
+    assert!(other.contains("s.version      = '2.4.0'"));
+}
+
+#[test]
+fn test_warns_on_several_podspecs() {
+    let path = make_test_dir("podspec-ambiguous");
+
+    write_file(&path, "VERSION", "1.0.0");
+    write_file(&path, "Demo.podspec", DEMO_PODSPEC);
+    write_file(
+        &path,
+        "Other.podspec",
+        &DEMO_PODSPEC.replace("Demo", "Other"),
+    );
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args(["--bump", "patch"])
+        .assert()
+        .success()
+        .stderr(contains(
+            "Warning: found 2 podspecs; pass --podspec to choose one.",
+        ));
+
+    let unchanged = fs::read_to_string(path.join("Demo.podspec")).unwrap();
+    assert_eq!(unchanged, DEMO_PODSPEC);
+}
+
+const DEMO_CSPROJ: &str = r#"<Project Sdk="Microsoft.NET.Sdk">
+
+  <PropertyGroup>
+    <TargetFramework>net8.0</TargetFramework>
+    <PackageId>Demo.Library</PackageId>
+    <Version>3.1.4</Version>
+    <Authors>Jane Doe</Authors>
+  </PropertyGroup>
+
+  <ItemGroup>
+    <PackageReference Include="Newtonsoft.Json" Version="13.0.3" />
+  </ItemGroup>
+
+</Project>
+"#;
+
+#[test]
+fn test_autodetects_csproj() {
+    let path = make_test_dir("csproj-autodetect");
+
+    write_file(&path, "Demo.csproj", DEMO_CSPROJ);
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args(["--bump", "minor", "-v"])
+        .assert()
+        .success()
+        .stdout(contains("3.1.4 → 3.2.0"))
+        .stderr(contains("Updated: Demo.csproj"));
+
+    let updated = fs::read_to_string(path.join("Demo.csproj")).unwrap();
+    assert_eq!(updated, DEMO_CSPROJ.replace("3.1.4", "3.2.0"));
+}
+
+#[test]
+fn test_bumps_explicit_csproj() {
+    let path = make_test_dir("csproj-explicit");
+
+    fs::create_dir_all(path.join("src/Demo")).unwrap();
+    write_file(&path, "src/Demo/Demo.csproj", DEMO_CSPROJ);
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args(["--csproj", "src/Demo/Demo.csproj", "--bump", "patch"])
+        .assert()
+        .success()
+        .stdout(contains("3.1.4 → 3.1.5"));
+
+    let updated = fs::read_to_string(path.join("src/Demo/Demo.csproj")).unwrap();
+    assert_eq!(updated, DEMO_CSPROJ.replace("3.1.4", "3.1.5"));
+}
+
+#[test]
+fn test_falls_back_to_directory_build_props() {
+    let path = make_test_dir("csproj-build-props");
+
+    let props = "<Project>\n  <PropertyGroup>\n    <VersionPrefix>1.4.0</VersionPrefix>\n    <VersionSuffix>preview.2</VersionSuffix>\n  </PropertyGroup>\n</Project>\n";
+    write_file(
+        &path,
+        "Demo.csproj",
+        "<Project Sdk=\"Microsoft.NET.Sdk\">\n  <PropertyGroup>\n    <TargetFramework>net8.0</TargetFramework>\n  </PropertyGroup>\n</Project>\n",
+    );
+    write_file(&path, "Directory.Build.props", props);
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args(["--bump", "minor"])
+        .assert()
+        .success()
+        .stdout(contains("1.4.0-preview.2 → 1.5.0"));
+
+    let updated = fs::read_to_string(path.join("Directory.Build.props")).unwrap();
+    assert_eq!(
+        updated,
+        props
+            .replace("1.4.0", "1.5.0")
+            .replace("<VersionSuffix>preview.2<", "<VersionSuffix><")
+    );
+}
+
+const DEMO_CHART: &str = r#"apiVersion: v2
+name: demo
+description: A demo chart
+# Chart packaging version
+version: 0.3.2
+appVersion: "1.4.0"
+dependencies:
+  - name: redis
+    version: 17.0.0
+"#;
+
+fn helm_chart(chart: &str, app: &str) -> String {
+    DEMO_CHART
+        .replace("version: 0.3.2", &format!("version: {}", chart))
+        .replace("\"1.4.0\"", &format!("\"{}\"", app))
+}
+
+fn bump_helm_chart(name: &str, args: &[&str]) -> String {
+    let path = make_test_dir(name);
+
+    write_file(&path, "VERSION", "1.4.0");
+    write_file(&path, "Chart.yaml", DEMO_CHART);
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path).args(args).assert().success();
+
+    assert_eq!(fs::read_to_string(path.join("VERSION")).unwrap(), "1.4.1");
+    fs::read_to_string(path.join("Chart.yaml")).unwrap()
+}
+
+#[test]
+fn test_helm_chart_bumps_both_fields() {
+    let chart = bump_helm_chart("helm-both", &["--bump", "patch"]);
+
+    assert_eq!(chart, helm_chart("0.3.3", "1.4.1"));
+}
+
+#[test]
+fn test_helm_chart_version_only() {
+    let chart = bump_helm_chart(
+        "helm-chart-only",
+        &["--bump", "patch", "--helm-chart-version-only"],
+    );
+
+    assert_eq!(chart, helm_chart("0.3.3", "1.4.0"));
+}
+
+#[test]
+fn test_helm_app_version_only() {
+    let chart = bump_helm_chart(
+        "helm-app-only",
+        &["--bump", "patch", "--helm-app-version-only"],
+    );
+
+    assert_eq!(chart, helm_chart("0.3.2", "1.4.1"));
+}
+
+#[test]
+fn test_helm_bumps_differ_per_field() {
+    let chart = bump_helm_chart(
+        "helm-per-field",
+        &[
+            "--bump",
+            "patch",
+            "--helm-chart-bump",
+            "minor",
+            "--helm-app-bump",
+            "patch",
+        ],
+    );
+
+    assert_eq!(chart, helm_chart("0.4.0", "1.4.1"));
+}
+
+#[test]
+fn test_helm_chart_fails_together() {
+    let path = make_test_dir("helm-missing-app-version");
+
+    let chart = DEMO_CHART.replace("appVersion: \"1.4.0\"\n", "");
+    write_file(&path, "VERSION", "1.4.0");
+    write_file(&path, "Chart.yaml", &chart);
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args(["--bump", "patch", "--helm-chart-bump", "major"])
+        .assert()
+        .code(1)
+        .stderr(contains("Chart.yaml has no appVersion field"));
+
+    assert_eq!(fs::read_to_string(path.join("Chart.yaml")).unwrap(), chart);
+    assert_eq!(fs::read_to_string(path.join("VERSION")).unwrap(), "1.4.0");
+}
+
+#[test]
+fn test_report_appends_one_json_line_per_bump() {
+    let path = make_test_dir("report-ndjson");
+
+    write_file(&path, "package.json", r#"{ "version": "1.2.3" }"#);
+    write_file(&path, "VERSION", "1.2.3");
+
+    for bump in ["patch", "minor"] {
+        let mut cmd = Command::cargo_bin("semver").unwrap();
+        cmd.current_dir(&path)
+            .env("USER", "release-bot")
+            .args(["--bump", bump, "--report", "bumps.ndjson"])
+            .assert()
+            .success();
+    }
+
+    let report = fs::read_to_string(path.join("bumps.ndjson")).unwrap();
+    let entries: Vec<serde_json::Value> = report
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[0]["old"], "1.2.3");
+    assert_eq!(entries[0]["new"], "1.2.4");
+    assert_eq!(entries[0]["bump_type"], "patch");
+    assert_eq!(entries[1]["old"], "1.2.4");
+    assert_eq!(entries[1]["new"], "1.3.0");
+    assert_eq!(
+        entries[1]["files_updated"],
+        serde_json::json!(["package.json", "VERSION"])
+    );
+    assert!(entries[1]["timestamp"].as_str().unwrap().ends_with('Z'));
+    assert!(!entries[1]["user"].as_str().unwrap().is_empty());
+}
+
+fn git(dir: &Path, args: &[&str]) -> String {
+    let output = std::process::Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .env("GIT_AUTHOR_NAME", "Test")
+        .env("GIT_AUTHOR_EMAIL", "test@example.com")
+        .env("GIT_COMMITTER_NAME", "Test")
+        .env("GIT_COMMITTER_EMAIL", "test@example.com")
+        .output()
+        .expect("Failed to run git");
+    assert!(
+        output.status.success(),
+        "git {:?} failed: {}",
+        args,
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8_lossy(&output.stdout).trim().to_string()
+}
+
+fn init_git_repo(path: &Path) {
+    git(path, &["init", "-q", "-b", "main"]);
+    git(path, &["add", "-A"]);
+    git(path, &["commit", "-q", "--allow-empty", "-m", "initial"]);
+}
+
+#[test]
+fn test_check_git_tag_exists_fails_on_local_tag() {
+    let path = make_test_dir("tag-exists-local");
+
+    write_file(&path, "VERSION", "1.0.0");
+    init_git_repo(&path);
+    git(&path, &["tag", "v1.0.1"]);
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args(["--bump", "patch", "--check-git-tag-exists"])
+        .assert()
+        .code(13)
+        .stderr(contains("Git tag v1.0.1 already exists"));
+
+    let unchanged = fs::read_to_string(path.join("VERSION")).unwrap();
+    assert_eq!(unchanged, "1.0.0");
+}
+
+#[test]
+fn test_check_git_tag_exists_consults_origin() {
+    let upstream = make_test_dir("tag-exists-upstream");
+    init_git_repo(&upstream);
+    git(&upstream, &["tag", "release-2.0.0"]);
+
+    let path = make_test_dir("tag-exists-remote");
+    write_file(&path, "VERSION", "1.4.2");
+    init_git_repo(&path);
+    let upstream_url = fs::canonicalize(&upstream).unwrap();
+    git(
+        &path,
+        &["remote", "add", "origin", upstream_url.to_str().unwrap()],
+    );
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args(["--bump", "major", "--check-git-tag-exists"])
+        .args(["--tag-prefix", "release-"])
+        .assert()
+        .code(13);
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args(["--bump", "minor", "--check-git-tag-exists"])
+        .args(["--tag-prefix", "release-"])
+        .assert()
+        .success()
+        .stdout(contains("1.4.2 → 1.5.0"));
+}
+
+#[test]
+fn test_color_never_disables_ansi_codes() {
+    let path = make_test_dir("color-never");
+
+    write_file(&path, "VERSION", "1.0.0");
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args(["--bump", "patch", "--color", "never"])
+        .assert()
+        .success()
+        .stdout(contains("1.0.0 → 1.0.1"))
+        .stdout(contains("\x1b[").not());
+}
+
+#[test]
+fn test_color_always_highlights_transition() {
+    let path = make_test_dir("color-always");
+
+    write_file(&path, "VERSION", "1.0.0");
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .env("NO_COLOR", "1")
+        .args(["--bump", "patch", "--color", "always"])
+        .assert()
+        .success()
+        .stdout(contains("\x1b[32m1.0.1"));
+}
+
+#[test]
+fn test_staged_only_updates_and_restages_staged_files() {
+    let path = make_test_dir("staged-only");
+
+    write_file(&path, "package.json", r#"{ "version": "1.0.0" }"#);
+    write_file(&path, "VERSION", "1.0.0");
+    init_git_repo(&path);
+
+    write_file(
+        &path,
+        "package.json",
+        r#"{ "version": "1.0.0", "description": "staged change" }"#,
+    );
+    git(&path, &["add", "package.json"]);
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args(["--bump", "minor", "--staged-only", "--verbose"])
+        .assert()
+        .success()
+        .stderr(contains("Updated: package.json"))
+        .stderr(contains("Skipped (not staged): VERSION"));
+
+    assert_eq!(fs::read_to_string(path.join("VERSION")).unwrap(), "1.0.0");
+    assert_eq!(
+        git(&path, &["diff", "--cached", "--name-only"]),
+        "package.json"
+    );
+    assert!(git(&path, &["show", ":package.json"]).contains("\"version\": \"1.1.0\""));
+}
+
+#[test]
+fn test_assert_clean_tree_after_passes_when_only_version_files_change() {
+    let path = make_test_dir("clean-tree-ok");
+
+    write_file(&path, "package.json", r#"{ "version": "2.1.0" }"#);
+    write_file(&path, "notes.txt", "untouched");
+    init_git_repo(&path);
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args(["--bump", "patch", "--assert-clean-tree-after"])
+        .assert()
+        .success();
+}
+
+#[test]
+fn test_assert_clean_tree_after_reports_unexpected_files() {
+    let path = make_test_dir("clean-tree-dirty");
+
+    write_file(&path, "VERSION", "2.1.0");
+    write_file(&path, "notes.txt", "original");
+    init_git_repo(&path);
+    write_file(&path, "notes.txt", "edited by a hook");
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args(["--bump", "patch", "--assert-clean-tree-after"])
+        .assert()
+        .code(14)
+        .stderr(contains("Unexpected files modified:\n  notes.txt"));
+}
+
+#[test]
+fn test_bumps_version_json_and_keeps_codename() {
+    let path = make_test_dir("version-json");
+
+    write_file(
+        &path,
+        ".version.json",
+        r#"{ "version": "4.0.1", "codename": "iris" }"#,
+    );
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args(["--bump", "minor"])
+        .assert()
+        .success()
+        .stdout(contains("4.0.1 → 4.1.0"));
+
+    let updated = fs::read_to_string(path.join(".version.json")).unwrap();
+    assert!(updated.contains("\"version\": \"4.1.0\""));
+    assert!(updated.contains("\"codename\": \"iris\""));
+}
+
+#[test]
+fn test_package_json_outranks_version_json() {
+    let path = make_test_dir("version-json-priority");
+
+    write_file(&path, "package.json", r#"{ "version": "1.0.0" }"#);
+    fs::create_dir_all(path.join("meta")).unwrap();
+    write_file(&path, "meta/.version.json", r#"{ "version": "0.5.0" }"#);
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args(["--bump", "patch", "--version-json", "meta/.version.json"])
+        .assert()
+        .success()
+        .stdout(contains("1.0.0 → 1.0.1"));
+
+    let meta = fs::read_to_string(path.join("meta/.version.json")).unwrap();
+    assert!(meta.contains("\"version\": \"1.0.1\""));
+}
+
+#[test]
+fn test_append_version_history_across_bumps() {
+    let path = make_test_dir("version-history");
+
+    write_file(&path, "VERSION", "0.1.0");
+
+    for bump in ["patch", "minor", "major"] {
+        let mut cmd = Command::cargo_bin("semver").unwrap();
+        cmd.current_dir(&path)
+            .args(["--bump", bump, "--append-version-history"])
+            .assert()
+            .success();
+    }
+
+    let history = fs::read_to_string(path.join("VERSION.history")).unwrap();
+    let lines: Vec<_> = history.lines().collect();
+    assert_eq!(lines.len(), 3);
+    assert!(lines[0].ends_with(" 0.1.0 → 0.1.1 (patch)"));
+    assert!(lines[1].ends_with(" 0.1.1 → 0.2.0 (minor)"));
+    assert!(lines[2].ends_with(" 0.2.0 → 1.0.0 (major)"));
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .arg("history")
+        .assert()
+        .success()
+        .stdout(contains("0.1.1 → 0.2.0  minor"));
+}
+
+#[test]
+fn test_audit_trail_chain_verifies_and_detects_tampering() {
+    let path = make_test_dir("audit-trail");
+
+    write_file(&path, "VERSION", "0.1.0");
+
+    for bump in ["patch", "minor"] {
+        let mut cmd = Command::cargo_bin("semver").unwrap();
+        cmd.current_dir(&path)
+            .args(["--bump", bump, "--audit-trail"])
+            .assert()
+            .success();
+    }
+
+    let trail = fs::read_to_string(path.join(".semver-audit")).unwrap();
+    let entries: Vec<serde_json::Value> = trail
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[1]["old"], "0.1.1");
+    assert_eq!(entries[1]["new"], "0.2.0");
+    assert_eq!(entries[1]["bump"], "minor");
+    assert_eq!(entries[1]["prev"], entries[0]["hash"]);
+    assert_ne!(
+        entries[1]["before"]["VERSION"],
+        entries[1]["after"]["VERSION"]
+    );
+    assert_eq!(
+        entries[1]["before"]["VERSION"],
+        entries[0]["after"]["VERSION"]
+    );
+    assert!(
+        entries[1]["user"]
+            .as_str()
+            .is_some_and(|user| !user.is_empty())
+    );
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args(["audit", "--verify"])
+        .assert()
+        .success()
+        .stdout(contains("Audit trail .semver-audit is intact."));
+
+    write_file(
+        &path,
+        ".semver-audit",
+        &trail.replacen("\"new\":\"0.1.1\"", "\"new\":\"0.1.2\"", 1),
+    );
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args(["audit", "--verify"])
+        .assert()
+        .code(25)
+        .stderr(contains(
+            "is broken at line 1: entry does not match its hash",
+        ));
+}
+
+#[test]
+fn test_simulate_prints_unified_diff_without_writing() {
+    let path = make_test_dir("simulate-diff");
+
+    let original = "{\n  \"name\": \"demo\",\n  \"version\": \"1.4.0\"\n}";
+    write_file(&path, "package.json", original);
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args(["--bump", "minor", "--simulate", "--color", "never"])
+        .assert()
+        .success()
+        .stdout(contains(
+            "--- a/package.json\n\
+             +++ b/package.json\n\
+             @@ -1,4 +1,4 @@\n \
+             {\n   \
+             \"name\": \"demo\",\n\
+             -  \"version\": \"1.4.0\"\n\
+             +  \"version\": \"1.5.0\"\n \
+             }",
+        ));
+
+    assert_eq!(
+        fs::read_to_string(path.join("package.json")).unwrap(),
+        original
+    );
+}
+
+#[test]
+fn test_max_files_aborts_before_writing() {
+    let path = make_test_dir("max-files");
+
+    write_file(&path, "composer.json", r#"{ "version": "1.0.0" }"#);
+    write_file(&path, "package.json", r#"{ "version": "1.0.0" }"#);
+    write_file(&path, "VERSION", "1.0.0");
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args(["--bump", "patch", "--max-files", "2"])
+        .assert()
+        .code(15)
+        .stderr(contains(
+            "3 files would be updated, more than the limit of 2",
+        ));
+
+    assert_eq!(fs::read_to_string(path.join("VERSION")).unwrap(), "1.0.0");
+    assert_eq!(
+        fs::read_to_string(path.join("package.json")).unwrap(),
+        r#"{ "version": "1.0.0" }"#
+    );
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args(["--bump", "patch", "--max-files", "3"])
+        .assert()
+        .success();
+
+    assert_eq!(fs::read_to_string(path.join("VERSION")).unwrap(), "1.0.1");
+}
+
+const DEMO_MIX_EXS: &str = r#"defmodule Demo.MixProject do
+  use Mix.Project
+
+  @version "1.7.2"
+  @source_url "https://example.com/demo"
+
+  def project do
+    [
+      app: :demo,
+      version: @version,
+      elixir: "~> 1.14",
+      deps: deps()
+    ]
+  end
+
+  defp deps do
+    [{:jason, "~> 1.4"}]
+  end
+end
+"#;
+
+#[test]
+fn test_bumps_mix_exs_in_project_root() {
+    let path = make_test_dir("mix-exs");
+
+    write_file(&path, "mix.exs", DEMO_MIX_EXS);
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args(["--bump", "patch"])
+        .assert()
+        .success()
+        .stdout(contains("1.7.2 → 1.7.3"));
+
+    let updated = fs::read_to_string(path.join("mix.exs")).unwrap();
+    assert_eq!(updated, DEMO_MIX_EXS.replace("1.7.2", "1.7.3"));
+}
+
+#[test]
+fn test_bumps_explicit_mix_exs_path() {
+    let path = make_test_dir("mix-exs-explicit");
+
+    fs::create_dir_all(path.join("apps/demo")).unwrap();
+    write_file(&path, "apps/demo/mix.exs", DEMO_MIX_EXS);
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args(["--bump", "major", "--mix-exs", "apps/demo/mix.exs"])
+        .assert()
+        .success()
+        .stdout(contains("1.7.2 → 2.0.0"));
+}
+
+#[test]
+fn test_dry_run_exit_code_is_used_for_dry_runs_only() {
+    let path = make_test_dir("dry-run-exit-code");
+
+    write_file(&path, "VERSION", "1.0.0");
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args(["--bump", "patch", "--dry-run", "--dry-run-exit-code", "42"])
+        .assert()
+        .code(42)
+        .stdout(contains("1.0.0 → 1.0.1"));
+
+    assert_eq!(fs::read_to_string(path.join("VERSION")).unwrap(), "1.0.0");
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args(["--bump", "patch", "--dry-run-exit-code", "42"])
+        .assert()
+        .code(0);
+}
+
+#[test]
+fn test_print_version_newline_handling() {
+    let path = make_test_dir("print-version-newline");
+
+    write_file(&path, "VERSION", "3.2.1\n");
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    let output = cmd
+        .current_dir(&path)
+        .arg("--print-version")
+        .output()
+        .unwrap();
+    assert_eq!(output.stdout, b"3.2.1\n");
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    let output = cmd
+        .current_dir(&path)
+        .args(["--print-version", "--no-newline"])
+        .output()
+        .unwrap();
+    assert_eq!(output.stdout, b"3.2.1");
+}
+
+#[test]
+fn test_no_newline_with_bump_only_warns() {
+    let path = make_test_dir("no-newline-bump");
+
+    write_file(&path, "VERSION", "3.2.1");
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args(["--bump", "patch", "--no-newline"])
+        .assert()
+        .success()
+        .stderr(contains("--no-newline only applies to --print-version"));
+
+    assert_eq!(fs::read_to_string(path.join("VERSION")).unwrap(), "3.2.2");
+}
+
+/// Serve a single HTTP request, replying with `status` and `body`.
+/// Returns the raw request text once it has been handled.
+fn serve_once(status: &str, body: &str) -> (String, std::thread::JoinHandle<String>) {
+    use std::io::{BufRead, BufReader, Read, Write};
+
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    );
+
+    let handle = std::thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        let mut reader = BufReader::new(stream);
+        let mut request = String::new();
+        let mut content_length = 0;
+        loop {
+            let mut line = String::new();
+            reader.read_line(&mut line).unwrap();
+            if let Some(len) = line.to_ascii_lowercase().strip_prefix("content-length:") {
+                content_length = len.trim().parse().unwrap();
+            }
+            request.push_str(&line);
+            if line == "\r\n" {
+                break;
+            }
+        }
+        let mut payload = vec![0; content_length];
+        reader.read_exact(&mut payload).unwrap();
+        request.push_str(&String::from_utf8_lossy(&payload));
+        reader.get_mut().write_all(response.as_bytes()).unwrap();
+        request
+    });
+
+    (url, handle)
+}
+
+#[test]
+fn test_github_release_posts_to_releases_api() {
+    let path = make_test_dir("github-release");
+
+    write_file(&path, "VERSION", "0.8.0");
+    init_git_repo(&path);
+    git(
+        &path,
+        &["remote", "add", "origin", "git@github.com:acme/widgets.git"],
+    );
+
+    let (api_url, server) = serve_once(
+        "201 Created",
+        r#"{"html_url":"https://github.com/acme/widgets/releases/tag/v0.9.0"}"#,
+    );
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .env("GITHUB_API_URL", &api_url)
+        .env("GITHUB_TOKEN", "test-token")
+        .args([
+            "--bump",
+            "minor",
+            "--github-release",
+            "--github-release-draft",
+        ])
+        .assert()
+        .success()
+        .stdout(contains(
+            "Created GitHub release: https://github.com/acme/widgets/releases/tag/v0.9.0",
+        ));
+
+    let request = server.join().unwrap();
+    assert!(request.starts_with("POST /repos/acme/widgets/releases "));
+    assert!(request.contains("Authorization: Bearer test-token"));
+    let payload: serde_json::Value =
+        serde_json::from_str(request.split("\r\n\r\n").nth(1).unwrap()).unwrap();
+    assert_eq!(payload["tag_name"], "v0.9.0");
+    assert_eq!(payload["draft"], true);
+    assert_eq!(payload["prerelease"], false);
+}
+
+#[test]
+fn test_github_release_reports_api_errors() {
+    let path = make_test_dir("github-release-error");
+
+    write_file(&path, "VERSION", "0.8.0");
+    init_git_repo(&path);
+    git(
+        &path,
+        &[
+            "remote",
+            "add",
+            "origin",
+            "https://github.com/acme/widgets.git",
+        ],
+    );
+
+    let (api_url, server) = serve_once(
+        "422 Unprocessable Entity",
+        r#"{"message":"Validation Failed"}"#,
+    );
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .env("GITHUB_API_URL", &api_url)
+        .env("GITHUB_TOKEN", "test-token")
+        .args(["--bump", "patch", "--github-release"])
+        .assert()
+        .failure()
+        .stderr(contains("GitHub responded 422"));
+
+    server.join().unwrap();
+}
+
+#[test]
+fn test_malformed_json_is_reported_while_other_sources_are_used() {
+    let cases = [
+        (
+            "missing-brace",
+            r#"{ "version": "1.0.0""#,
+            "EOF while parsing an object",
+        ),
+        (
+            "trailing-comma",
+            r#"{ "version": "1.0.0", }"#,
+            "trailing comma",
+        ),
+        (
+            "bare-word",
+            r#"{ version: "1.0.0" }"#,
+            "key must be a string",
+        ),
+    ];
+
+    for (name, json, message) in cases {
+        let path = make_test_dir(&format!("malformed-json-{}", name));
+
+        write_file(&path, "package.json", json);
+        write_file(&path, "VERSION", "0.4.0");
+
+        let mut cmd = Command::cargo_bin("semver").unwrap();
+        cmd.current_dir(&path)
+            .args(["--bump", "patch"])
+            .assert()
+            .success()
+            .stdout(contains("0.4.0 → 0.4.1"))
+            .stderr(contains(format!(
+                "Invalid JSON in package.json: {}",
+                message
+            )));
+
+        assert_eq!(fs::read_to_string(path.join("package.json")).unwrap(), json);
+    }
+}
+
+#[test]
+fn test_workspace_file_bumps_members_with_overrides() {
+    let path = make_test_dir("workspace-file");
+
+    write_file(&path, "VERSION", "1.0.0");
+    for member in ["a", "b", "legacy"] {
+        fs::create_dir_all(path.join("packages").join(member)).unwrap();
+    }
+    write_file(
+        &path,
+        "packages/a/package.json",
+        r#"{ "version": "0.1.0" }"#,
+    );
+    write_file(&path, "packages/b/VERSION", "2.3.4");
+    write_file(
+        &path,
+        "packages/legacy/package.json",
+        r#"{ "version": "0.0.9" }"#,
+    );
+    write_file(
+        &path,
+        "semver-workspace.toml",
+        r#"members = [
+    "packages/a",
+    { path = "packages/b", bump_type = "major" },
+    { path = "packages/legacy", enabled = false },
+]
+"#,
+    );
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args([
+            "--bump",
+            "patch",
+            "--workspace-file",
+            "semver-workspace.toml",
+        ])
+        .assert()
+        .success()
+        .stdout(contains("1.0.0 → 1.0.1"))
+        .stdout(contains("Bumping packages/a 0.1.0 → 0.1.1"))
+        .stdout(contains("Bumping packages/b 2.3.4 → 3.0.0"));
+
+    assert_eq!(fs::read_to_string(path.join("VERSION")).unwrap(), "1.0.1");
+    assert!(
+        fs::read_to_string(path.join("packages/a/package.json"))
+            .unwrap()
+            .contains("\"version\": \"0.1.1\"")
+    );
+    assert_eq!(
+        fs::read_to_string(path.join("packages/b/VERSION")).unwrap(),
+        "3.0.0"
+    );
+    assert_eq!(
+        fs::read_to_string(path.join("packages/legacy/package.json")).unwrap(),
+        r#"{ "version": "0.0.9" }"#
+    );
+}
+
+#[test]
+fn test_interactive_workspace_bump_prompts_each_member() {
+    let path = make_test_dir("workspace-interactive");
+
+    write_file(&path, "VERSION", "1.0.0");
+    for member in ["a", "b"] {
+        fs::create_dir_all(path.join("packages").join(member)).unwrap();
+    }
+    write_file(&path, "packages/a/VERSION", "0.1.0");
+    write_file(&path, "packages/b/VERSION", "2.3.4");
+    write_file(
+        &path,
+        "semver-workspace.toml",
+        "members = [\"packages/a\", { path = \"packages/b\", bump_type = \"major\" }]\n",
+    );
+    let args = [
+        "--bump",
+        "patch",
+        "--workspace-file",
+        "semver-workspace.toml",
+        "--interactive-workspace-bump",
+    ];
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args(args)
+        .write_stdin("minor\nskip\nn\n")
+        .assert()
+        .success()
+        .stdout(contains("What would you like to bump in packages/a?"))
+        .stdout(contains("  packages/b: skip"))
+        .stdout(contains("No files were changed."));
+    assert_eq!(fs::read_to_string(path.join("VERSION")).unwrap(), "1.0.0");
+    assert_eq!(
+        fs::read_to_string(path.join("packages/a/VERSION")).unwrap(),
+        "0.1.0"
+    );
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args(args)
+        .write_stdin("minor\nskip\ny\n")
+        .assert()
+        .success()
+        .stdout(contains("  packages/a: minor"))
+        .stdout(contains("Bumping packages/a 0.1.0 → 0.2.0"))
+        .stdout(contains("Skipping packages/b at 2.3.4"));
+    assert_eq!(fs::read_to_string(path.join("VERSION")).unwrap(), "1.0.1");
+    assert_eq!(
+        fs::read_to_string(path.join("packages/a/VERSION")).unwrap(),
+        "0.2.0"
+    );
+    assert_eq!(
+        fs::read_to_string(path.join("packages/b/VERSION")).unwrap(),
+        "2.3.4"
+    );
+}
+
+#[test]
+fn test_file_hooks_run_around_updates() {
+    let path = make_test_dir("file-hooks");
+
+    write_file(&path, "package.json", r#"{ "version": "1.0.0" }"#);
+    write_file(&path, "VERSION", "1.0.0");
+    write_file(
+        &path,
+        ".semverrc.toml",
+        r#"[[file_hooks]]
+path = "package.json"
+pre_update = "grep -o '[0-9][0-9.]*' package.json > before.txt"
+post_update = "grep -o '[0-9][0-9.]*' package.json > after.txt"
+
+[[file_hooks]]
+path = "missing.json"
+post_update = "touch never.txt"
+"#,
+    );
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args(["--bump", "patch"])
+        .assert()
+        .success();
+
+    assert_eq!(
+        fs::read_to_string(path.join("before.txt")).unwrap(),
+        "1.0.0\n"
+    );
+    assert_eq!(
+        fs::read_to_string(path.join("after.txt")).unwrap(),
+        "1.0.1\n"
+    );
+    assert!(!path.join("never.txt").exists());
+}
+
+#[test]
+fn test_failing_file_hook_exits_non_zero() {
+    let path = make_test_dir("file-hooks-failing");
+
+    write_file(&path, "VERSION", "1.0.0");
+    write_file(
+        &path,
+        ".semverrc.toml",
+        "[[file_hooks]]\npath = \"VERSION\"\npre_update = \"exit 3\"\n",
+    );
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args(["--bump", "patch"])
+        .assert()
+        .code(1)
+        .stderr(contains("Hook `exit 3` for VERSION failed: exit status: 3"));
+
+    assert_eq!(fs::read_to_string(path.join("VERSION")).unwrap(), "1.0.0");
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args(["--bump", "patch", "--dry-run"])
+        .assert()
+        .success();
+}
+
+#[test]
+fn test_workspace_root_only_leaves_members_untouched() {
+    let path = make_test_dir("workspace-root-only");
+
+    write_file(&path, "package.json", r#"{ "version": "1.0.0" }"#);
+    for member in ["a", "b"] {
+        fs::create_dir_all(path.join("packages").join(member)).unwrap();
+    }
+    write_file(
+        &path,
+        "packages/a/package.json",
+        r#"{ "version": "0.1.0" }"#,
+    );
+    write_file(&path, "packages/b/VERSION", "2.3.4");
+    write_file(
+        &path,
+        "semver-workspace.toml",
+        "members = [\"packages/a\", \"packages/b\"]\n",
+    );
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args([
+            "--bump",
+            "minor",
+            "--workspace-file",
+            "semver-workspace.toml",
+            "--workspace-root-only",
+        ])
+        .assert()
+        .success()
+        .stdout(contains("1.0.0 → 1.1.0"))
+        .stdout(contains("Keeping packages/a at 0.1.0"))
+        .stdout(contains("Keeping packages/b at 2.3.4"));
+
+    assert!(
+        fs::read_to_string(path.join("package.json"))
+            .unwrap()
+            .contains("\"version\": \"1.1.0\"")
+    );
+    assert_eq!(
+        fs::read_to_string(path.join("packages/a/package.json")).unwrap(),
+        r#"{ "version": "0.1.0" }"#
+    );
+    assert_eq!(
+        fs::read_to_string(path.join("packages/b/VERSION")).unwrap(),
+        "2.3.4"
+    );
+}
+
+#[test]
+fn test_ignore_files_skips_workspace_member() {
+    let path = make_test_dir("ignore-files-workspace");
+
+    write_file(&path, "VERSION", "1.0.0");
+    for member in ["app", "legacy"] {
+        fs::create_dir_all(path.join("packages").join(member)).unwrap();
+        write_file(
+            &path,
+            &format!("packages/{}/package.json", member),
+            r#"{ "version": "0.1.0" }"#,
+        );
+    }
+    write_file(
+        &path,
+        "semver-workspace.toml",
+        "members = [\"packages/app\", \"packages/legacy\"]\n",
+    );
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args([
+            "--bump",
+            "patch",
+            "--workspace-file",
+            "semver-workspace.toml",
+            "--ignore-files",
+            "packages/legacy/*",
+            "--verbose",
+        ])
+        .assert()
+        .success()
+        .stderr(contains("Skipped (ignored): packages/legacy/package.json"));
+
+    assert_eq!(fs::read_to_string(path.join("VERSION")).unwrap(), "1.0.1");
+    assert!(
+        fs::read_to_string(path.join("packages/app/package.json"))
+            .unwrap()
+            .contains("\"version\": \"0.1.1\"")
+    );
+    assert_eq!(
+        fs::read_to_string(path.join("packages/legacy/package.json")).unwrap(),
+        r#"{ "version": "0.1.0" }"#
+    );
+}
+
+#[test]
+fn test_ignore_files_from_project_config() {
+    let path = make_test_dir("ignore-files-config");
+
+    write_file(&path, "package.json", r#"{ "version": "1.0.0" }"#);
+    write_file(&path, "package.pinned.json", r#"{ "version": "0.9.0" }"#);
+    write_file(&path, "package.web.json", r#"{ "version": "1.0.0" }"#);
+    write_file(
+        &path,
+        ".semverrc.toml",
+        "ignore_files = [\"package.pinned.json\"]\n",
+    );
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args(["--bump", "minor", "--json-files", "package*.json"])
+        .assert()
+        .success()
+        .stdout(contains("JSON files skipped: package.pinned.json"));
+
+    for updated in ["package.json", "package.web.json"] {
+        assert!(
+            fs::read_to_string(path.join(updated))
+                .unwrap()
+                .contains("\"version\": \"1.1.0\""),
+            "{updated}"
+        );
+    }
+    assert_eq!(
+        fs::read_to_string(path.join("package.pinned.json")).unwrap(),
+        r#"{ "version": "0.9.0" }"#
+    );
+}
+
+#[test]
+fn test_workspace_file_rejects_unknown_bump_type() {
+    let path = make_test_dir("workspace-file-invalid");
+
+    write_file(&path, "VERSION", "1.0.0");
+    write_file(
+        &path,
+        "semver-workspace.toml",
+        r#"members = [{ path = "a", bump_type = "huge" }]"#,
+    );
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args([
+            "--bump",
+            "patch",
+            "--workspace-file",
+            "semver-workspace.toml",
+        ])
+        .assert()
+        .failure()
+        .stderr(contains("invalid bump_type 'huge'"));
+
+    assert_eq!(fs::read_to_string(path.join("VERSION")).unwrap(), "1.0.0");
+}
+
+const DEMO_FASTFILE: &str = r#"# Customise this file, documentation can be found here:
+# https://docs.fastlane.tools/
+default_platform(:android)
+
+version = "3.0.2"
+
+platform :android do
+  desc "Deploy a new beta"
+  lane :beta do
+    version = "3.0.2-beta"
+    gradle(task: "assembleRelease", properties: { "versionName" => version })
+  end
+
+  lane :release do |options|
+    version = '3.0.2'
+    upload_to_play_store
+  end
+end
+"#;
+
+#[test]
+fn test_fastfile_updates_only_top_level_version() {
+    let path = make_test_dir("fastfile-top-level");
+
+    fs::create_dir_all(path.join("fastlane")).unwrap();
+    write_file(&path, "fastlane/Fastfile", DEMO_FASTFILE);
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args(["--fastfile", "fastlane/Fastfile", "--bump", "minor"])
+        .assert()
+        .success()
+        .stdout(contains("3.0.2 → 3.1.0"));
+
+    let updated = fs::read_to_string(path.join("fastlane/Fastfile")).unwrap();
+    assert!(updated.contains("\nversion = \"3.1.0\"\n"));
+    assert!(updated.contains("version = \"3.0.2-beta\""));
+    assert!(updated.contains("version = '3.0.2'"));
+}
+
+#[test]
+fn test_fastfile_all_occurrences() {
+    let path = make_test_dir("fastfile-all");
+
+    write_file(&path, "Fastfile", DEMO_FASTFILE);
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args([
+            "--fastfile",
+            "Fastfile",
+            "--all-occurrences",
+            "--bump",
+            "patch",
+        ])
+        .assert()
+        .success();
+
+    let updated = fs::read_to_string(path.join("Fastfile")).unwrap();
+    assert!(updated.contains("\nversion = \"3.0.3\"\n"));
+    assert!(updated.contains("    version = \"3.0.3\"\n"));
+    assert!(updated.contains("    version = '3.0.3'\n"));
+}
+
+#[cfg(feature = "calver")]
+#[test]
+fn test_bump_date_rejects_major_and_minor() {
+    let path = make_test_dir("calver-conflict");
+
+    write_file(&path, "VERSION", "2024.3.5");
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args(["--bump-date", "--bump", "minor"])
+        .assert()
+        .failure()
+        .stderr(contains("cannot be combined with --bump minor"));
+
+    assert_eq!(
+        fs::read_to_string(path.join("VERSION")).unwrap(),
+        "2024.3.5"
+    );
+}
+
+#[cfg(feature = "calver")]
+#[test]
+fn test_bump_date_uses_two_digit_year() {
+    let path = make_test_dir("calver-two-digits");
+
+    write_file(&path, "VERSION", "1.2.3");
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args(["--bump-date", "--calver-year-digits", "2", "--calver-micro"])
+        .assert()
+        .success();
+
+    let updated = fs::read_to_string(path.join("VERSION")).unwrap();
+    let parts: Vec<u64> = updated
+        .trim()
+        .split('.')
+        .map(|n| n.parse().unwrap())
+        .collect();
+    assert!(parts[0] < 100);
+    assert!((1..=12).contains(&parts[1]));
+    assert_eq!(parts[2], 0);
+}
+
+#[test]
+fn test_require_tag_accepts_tagged_version() {
+    let path = make_test_dir("require-tag-present");
+
+    write_file(&path, "VERSION", "1.0.0");
+    init_git_repo(&path);
+    git(&path, &["tag", "v1.0.0"]);
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args(["--bump", "patch", "--require-tag"])
+        .assert()
+        .success();
+
+    assert_eq!(fs::read_to_string(path.join("VERSION")).unwrap(), "1.0.1");
+}
+
+#[test]
+fn test_require_tag_rejects_untagged_version() {
+    let path = make_test_dir("require-tag-missing");
+
+    write_file(&path, "VERSION", "1.0.0");
+    init_git_repo(&path);
+    git(&path, &["tag", "v0.9.0"]);
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args(["--bump", "patch", "--require-tag"])
+        .assert()
+        .code(19)
+        .stderr(contains(
+            "Current version 1.0.0 is not tagged: no git tag matches v1.0.0",
+        ))
+        .stderr(contains("--git-tag-annotated"));
+
+    assert_eq!(fs::read_to_string(path.join("VERSION")).unwrap(), "1.0.0");
+}
+
+#[test]
+fn test_require_tag_with_pattern() {
+    let path = make_test_dir("require-tag-pattern");
+
+    write_file(&path, "VERSION", "1.0.0");
+    init_git_repo(&path);
+    git(&path, &["tag", "release-1.0.0-final"]);
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args(["--bump", "patch", "--require-tag", "release-{version}*"])
+        .assert()
+        .success();
+
+    assert_eq!(fs::read_to_string(path.join("VERSION")).unwrap(), "1.0.1");
+}
+
+#[test]
+fn test_git_tag_annotated_reads_message_file() {
+    let path = make_test_dir("tag-message-file");
+
+    write_file(&path, "VERSION", "1.0.0");
+    init_git_repo(&path);
+    write_file(
+        &path,
+        "NOTES.md",
+        "Release notes\n\n- first line\n- second line\n",
+    );
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .env("GIT_COMMITTER_NAME", "Test")
+        .env("GIT_COMMITTER_EMAIL", "test@example.com")
+        .args([
+            "--bump",
+            "minor",
+            "--git-tag-annotated",
+            "--tag-message-file",
+            "NOTES.md",
+        ])
+        .assert()
+        .success()
+        .stdout(contains("Created git tag v1.1.0"));
+
+    assert_eq!(git(&path, &["cat-file", "-t", "v1.1.0"]), "tag");
+    let message = git(&path, &["tag", "-l", "--format=%(contents)", "v1.1.0"]);
+    assert_eq!(message, "Release notes\n\n- first line\n- second line");
+}
+
+#[test]
+fn test_git_tag_annotated_default_message() {
+    let path = make_test_dir("tag-annotated-default");
+
+    write_file(&path, "VERSION", "1.0.0");
+    init_git_repo(&path);
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .env("GIT_COMMITTER_NAME", "Test")
+        .env("GIT_COMMITTER_EMAIL", "test@example.com")
+        .args(["--bump", "patch", "--git-tag-annotated"])
+        .assert()
+        .success();
+
+    let message = git(&path, &["tag", "-l", "--format=%(contents)", "v1.0.1"]);
+    assert_eq!(message, "Release v1.0.1");
+}
+
+#[test]
+fn test_git_tag_annotated_fails_on_existing_tag() {
+    let path = make_test_dir("tag-force-required");
+
+    write_file(&path, "VERSION", "1.0.0");
+    init_git_repo(&path);
+    git(&path, &["tag", "v1.0.1"]);
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .env("GIT_COMMITTER_NAME", "Test")
+        .env("GIT_COMMITTER_EMAIL", "test@example.com")
+        .args(["--bump", "patch", "--git-tag-annotated"])
+        .assert()
+        .code(1)
+        .stderr(contains("Failed to create git tag v1.0.1"));
+}
+
+#[test]
+fn test_tag_force_moves_existing_tag() {
+    let path = make_test_dir("tag-force");
+
+    write_file(&path, "VERSION", "1.0.0");
+    init_git_repo(&path);
+    git(&path, &["tag", "-a", "v1.0.1", "-m", "old"]);
+    let initial = git(&path, &["rev-parse", "HEAD"]);
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .env("GIT_AUTHOR_NAME", "Test")
+        .env("GIT_AUTHOR_EMAIL", "test@example.com")
+        .env("GIT_COMMITTER_NAME", "Test")
+        .env("GIT_COMMITTER_EMAIL", "test@example.com")
+        .args([
+            "--bump",
+            "patch",
+            "--git-commit",
+            "--git-tag-annotated",
+            "--tag-force",
+        ])
+        .assert()
+        .success()
+        .stderr(contains("Warning: --tag-force moves git tag v1.0.1"))
+        .stdout(contains("Created git tag v1.0.1"));
+
+    let head = git(&path, &["rev-parse", "HEAD"]);
+    assert_ne!(head, initial);
+    assert_eq!(git(&path, &["rev-parse", "v1.0.1^{commit}"]), head);
+    let message = git(&path, &["tag", "-l", "--format=%(contents)", "v1.0.1"]);
+    assert_eq!(message, "Release v1.0.1");
+}
+
+#[test]
+fn test_tag_message_file_missing() {
+    let path = make_test_dir("tag-message-missing");
+
+    write_file(&path, "VERSION", "1.0.0");
+    init_git_repo(&path);
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args([
+            "--bump",
+            "patch",
+            "--git-tag-annotated",
+            "--tag-message-file",
+            "missing.md",
+        ])
+        .assert()
+        .code(1)
+        .stderr(contains("Tag message file missing.md does not exist"));
+
+    let unchanged = fs::read_to_string(path.join("VERSION")).unwrap();
+    assert_eq!(unchanged, "1.0.0");
+    assert_eq!(git(&path, &["tag", "--list"]), "");
+}
+
+#[test]
+fn test_strict_semver_rejects_leading_zeros() {
+    let path = make_test_dir("strict-semver-leading-zero");
+
+    write_file(&path, "VERSION", "01.2.3");
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args(["--bump", "patch", "--strict-semver"])
+        .assert()
+        .code(3)
+        .stderr(contains(
+            "Version \"01.2.3\" in VERSION is not a strict semver",
+        ));
+
+    let unchanged = fs::read_to_string(path.join("VERSION")).unwrap();
+    assert_eq!(unchanged, "01.2.3");
+}
+
+#[test]
+fn test_strict_semver_rejects_two_part_version() {
+    let path = make_test_dir("strict-semver-two-part");
+
+    write_file(&path, "package.json", r#"{"version": "1.2"}"#);
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args(["--bump", "patch", "--strict-semver"])
+        .assert()
+        .code(3)
+        .stderr(contains("in package.json"));
+}
+
+#[test]
+fn test_strict_semver_accepts_valid_version() {
+    let path = make_test_dir("strict-semver-valid");
+
+    write_file(&path, "VERSION", "1.2.3-rc.1+build.5");
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args(["--bump", "patch", "--strict-semver"])
+        .assert()
+        .success();
+
+    let updated = fs::read_to_string(path.join("VERSION")).unwrap();
+    assert_eq!(updated, "1.2.4");
+}
+
+#[test]
+fn test_interactive_diff_confirm_applies_on_yes() {
+    let path = make_test_dir("diff-confirm-yes");
+
+    write_file(&path, "VERSION", "1.0.0");
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args(["--bump", "patch", "--interactive-diff-confirm"])
+        .write_stdin("y\n")
+        .assert()
+        .success()
+        .stdout(contains("+1.0.1"))
+        .stdout(contains("Apply these changes? [y/N]"));
+
+    let updated = fs::read_to_string(path.join("VERSION")).unwrap();
+    assert_eq!(updated, "1.0.1");
+}
+
+#[test]
+fn test_interactive_diff_confirm_declines_on_no() {
+    let path = make_test_dir("diff-confirm-no");
+
+    write_file(&path, "VERSION", "1.0.0");
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args(["--bump", "patch", "--interactive-diff-confirm"])
+        .write_stdin("n\n")
+        .assert()
+        .code(0)
+        .stdout(contains("-1.0.0"))
+        .stdout(contains("No files were changed."));
+
+    let unchanged = fs::read_to_string(path.join("VERSION")).unwrap();
+    assert_eq!(unchanged, "1.0.0");
+}
+
+#[test]
+fn test_interactive_bump_preview_shows_next_version_first() {
+    let path = make_test_dir("bump-preview");
+
+    write_file(&path, "VERSION", "1.2.3");
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    let output = cmd
+        .current_dir(&path)
+        .arg("--interactive-bump-preview")
+        .write_stdin("minor\ny\n")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let preview = stdout.find("→ Next version will be: 1.3.0").unwrap();
+    let bumping = stdout.find("Bumping version 1.2.3 → 1.3.0").unwrap();
+    assert!(preview < bumping, "{stdout}");
+    assert_eq!(fs::read_to_string(path.join("VERSION")).unwrap(), "1.3.0");
+}
+
+#[test]
+fn test_interactive_bump_preview_declines_on_no() {
+    let path = make_test_dir("bump-preview-no");
+
+    write_file(&path, "VERSION", "1.2.3");
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .arg("--interactive-bump-preview")
+        .write_stdin("major\nn\n")
+        .assert()
+        .code(0)
+        .stdout(contains("→ Next version will be: 2.0.0"))
+        .stdout(contains("No files were changed."));
+
+    assert_eq!(fs::read_to_string(path.join("VERSION")).unwrap(), "1.2.3");
+}
+
+#[test]
+fn test_json_update_preserves_crlf() {
+    let path = make_test_dir("json-crlf");
+
+    write_file(
+        &path,
+        "package.json",
+        "{\r\n  \"name\": \"demo\",\r\n  \"version\": \"1.0.0\"\r\n}",
+    );
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args(["--bump", "patch"])
+        .assert()
+        .success();
+
+    let updated = fs::read_to_string(path.join("package.json")).unwrap();
+    assert_eq!(
+        updated,
+        "{\r\n  \"name\": \"demo\",\r\n  \"version\": \"1.0.1\"\r\n}"
+    );
+}
+
+#[test]
+fn test_json_update_keeps_lf() {
+    let path = make_test_dir("json-lf");
+
+    write_file(&path, "package.json", "{\n  \"version\": \"1.0.0\"\n}");
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args(["--bump", "patch"])
+        .assert()
+        .success();
+
+    let updated = fs::read_to_string(path.join("package.json")).unwrap();
+    assert!(!updated.contains('\r'));
+}
+
+#[test]
+fn test_json_indent_sets_indentation() {
+    let path = make_test_dir("json-indent");
+
+    for (indent, prefix) in [("0", ""), ("4", "    "), ("8", "        "), ("tab", "\t")] {
+        write_file(
+            &path,
+            "package.json",
+            r#"{"name": "demo", "version": "1.0.0"}"#,
+        );
+
+        let mut cmd = Command::cargo_bin("semver").unwrap();
+        cmd.current_dir(&path)
+            .args(["--bump", "patch", "--json-indent", indent])
+            .assert()
+            .success();
+
+        let updated = fs::read_to_string(path.join("package.json")).unwrap();
+        assert_eq!(
+            updated,
+            format!(
+                "{{\n{0}\"name\": \"demo\",\n{0}\"version\": \"1.0.1\"\n}}",
+                prefix
+            )
+        );
+    }
+}
+
+#[test]
+fn test_json_indent_rejects_nine() {
+    let path = make_test_dir("json-indent-invalid");
+
+    write_file(&path, "package.json", r#"{"version": "1.0.0"}"#);
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args(["--bump", "patch", "--json-indent", "9"])
+        .assert()
+        .code(2)
+        .stderr(contains("expected 0-8 spaces or 'tab'"));
+
+    let unchanged = fs::read_to_string(path.join("package.json")).unwrap();
+    assert_eq!(unchanged, r#"{"version": "1.0.0"}"#);
+}
+
+#[test]
+fn test_json_files_updates_every_match() {
+    let path = make_test_dir("json-files");
+
+    write_file(
+        &path,
+        "package.json",
+        r#"{"name": "app", "version": "1.0.0"}"#,
+    );
+    write_file(
+        &path,
+        "package.main.json",
+        r#"{"name": "app-main", "version": "1.0.0"}"#,
+    );
+    write_file(
+        &path,
+        "package.renderer.json",
+        r#"{"name": "app-renderer", "version": "1.0.0"}"#,
+    );
+    write_file(&path, "package.shared.json", r#"{"name": "shared"}"#);
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args(["--bump", "minor", "--json-files", "package.*.json"])
+        .assert()
+        .success()
+        .stdout(contains(
+            "JSON files updated: package.main.json, package.renderer.json",
+        ))
+        .stdout(contains("JSON files skipped: package.shared.json"));
+
+    for name in ["package.main.json", "package.renderer.json"] {
+        let updated = fs::read_to_string(path.join(name)).unwrap();
+        assert!(updated.contains(r#""version": "1.1.0""#), "{name}");
+    }
+    let untouched = fs::read_to_string(path.join("package.json")).unwrap();
+    assert_eq!(untouched, r#"{"name": "app", "version": "1.0.0"}"#);
+    let skipped = fs::read_to_string(path.join("package.shared.json")).unwrap();
+    assert_eq!(skipped, r#"{"name": "shared"}"#);
+}
+
+#[test]
+fn test_json_files_updates_package_json_once() {
+    let path = make_test_dir("json-files-package");
+
+    write_file(&path, "package.json", r#"{"version": "1.0.0"}"#);
+    write_file(&path, "package.renderer.json", r#"{"version": "1.0.0"}"#);
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args(["--bump", "patch", "--json-files", "package*.json", "-v"])
+        .assert()
+        .success()
+        .stdout(contains(
+            "JSON files updated: package.json, package.renderer.json",
+        ))
+        .stderr(contains("Updated: package.json").count(1));
+
+    let updated = fs::read_to_string(path.join("package.json")).unwrap();
+    assert_eq!(updated, "{\n  \"version\": \"1.0.1\"\n}");
+}
+
+#[test]
+fn test_version_file_formats() {
+    let path = make_test_dir("version-file-format");
+
+    for (format, existing, expected) in [
+        ("plain", "1.2.3\n", "1.2.4"),
+        ("semver", "1.2.3", "1.2.4\n"),
+        ("auto", "v1.2.3\n", "v1.2.4\n"),
+        ("auto", "1.2.3", "1.2.4"),
+    ] {
+        write_file(&path, "VERSION", existing);
+
+        let mut cmd = Command::cargo_bin("semver").unwrap();
+        cmd.current_dir(&path)
+            .args(["--bump", "patch", "--version-file-format", format])
+            .assert()
+            .success()
+            .stdout(contains("1.2.3 → 1.2.4"));
+
+        let updated = fs::read_to_string(path.join("VERSION")).unwrap();
+        assert_eq!(updated, expected, "{format} {existing:?}");
+    }
+}
+
+#[test]
+fn test_version_file_format_rejects_unknown_value() {
+    let path = make_test_dir("version-file-format-invalid");
+
+    write_file(&path, "VERSION", "1.2.3");
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args(["--bump", "patch", "--version-file-format", "yaml"])
+        .assert()
+        .code(2)
+        .stderr(contains("expected auto, plain or semver"));
+}
+
+fn semver_in_git(path: &Path) -> Command {
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(path)
+        .env("GIT_AUTHOR_NAME", "Test")
+        .env("GIT_AUTHOR_EMAIL", "test@example.com")
+        .env("GIT_COMMITTER_NAME", "Test")
+        .env("GIT_COMMITTER_EMAIL", "test@example.com");
+    cmd
+}
+
+#[test]
+fn test_git_commit_stages_only_version_files() {
+    let path = make_test_dir("git-commit-version-files");
+
+    write_file(&path, "VERSION", "1.0.0");
+    write_file(&path, "README.md", "readme");
+    init_git_repo(&path);
+    write_file(&path, "README.md", "edited");
+
+    semver_in_git(&path)
+        .args(["--bump", "patch", "--git-commit"])
+        .assert()
+        .success();
+
+    assert_eq!(
+        git(&path, &["log", "-1", "--format=%s"]),
+        "chore: bump version to 1.0.1"
+    );
+    assert_eq!(
+        git(&path, &["show", "--name-only", "--format=", "HEAD"]),
+        "VERSION"
+    );
+    assert_eq!(git(&path, &["diff", "--name-only"]), "README.md");
+}
+
+#[test]
+fn test_git_commit_message_scope() {
+    let path = make_test_dir("git-commit-scope");
+
+    write_file(&path, "VERSION", "1.2.3");
+    init_git_repo(&path);
+
+    semver_in_git(&path)
+        .args([
+            "--bump",
+            "patch",
+            "--git-commit",
+            "--message-scope",
+            "release",
+        ])
+        .assert()
+        .success();
+
+    assert_eq!(
+        git(&path, &["log", "-1", "--format=%s"]),
+        "chore(release): bump version to 1.2.4"
+    );
+}
+
+#[test]
+fn test_git_commit_message_scope_rejects_spaces() {
+    let path = make_test_dir("git-commit-scope-invalid");
+
+    write_file(&path, "VERSION", "1.2.3");
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args([
+            "--bump",
+            "patch",
+            "--git-commit",
+            "--message-scope",
+            "my scope",
+        ])
+        .assert()
+        .code(2)
+        .stderr(contains("invalid commit scope 'my scope'"));
+
+    assert_eq!(fs::read_to_string(path.join("VERSION")).unwrap(), "1.2.3");
+}
+
+#[test]
+fn test_template_file_renders_commit_and_tag_messages() {
+    let path = make_test_dir("template-file");
+
+    write_file(&path, "VERSION", "1.2.3");
+    init_git_repo(&path);
+    let sha = git(&path, &["rev-parse", "HEAD"]);
+    let branch = git(&path, &["rev-parse", "--abbrev-ref", "HEAD"]);
+    write_file(
+        &path,
+        "message.hbs",
+        "release: {{old_version}} -> {{new_version}} ({{bump_type}})\n\n\
+         {{#each files_updated}}* {{this}}\n{{/each}}\
+         date={{date}}\nbranch={{branch}}\nsha={{git_sha}}\n",
+    );
+
+    semver_in_git(&path)
+        .args([
+            "--bump",
+            "minor",
+            "--git-commit",
+            "--git-tag-annotated",
+            "--template-file",
+            "message.hbs",
+        ])
+        .assert()
+        .success()
+        .stderr(contains("unknown variable").not());
+
+    let message = git(&path, &["log", "-1", "--format=%B"]);
+    let lines: Vec<_> = message.lines().collect();
+    assert_eq!(
+        lines[..3],
+        ["release: 1.2.3 -> 1.3.0 (minor)", "", "* VERSION"]
+    );
+    let date = lines[3].strip_prefix("date=").unwrap();
+    assert!(
+        regex::Regex::new(r"^\d{4}-\d{2}-\d{2}$")
+            .unwrap()
+            .is_match(date),
+        "{date}"
+    );
+    assert_eq!(lines[4], format!("branch={}", branch));
+    assert_eq!(lines[5], format!("sha={}", sha));
+
+    let tag_message = git(&path, &["tag", "-l", "--format=%(contents)", "v1.3.0"]);
+    assert_eq!(tag_message.trim(), message.trim());
+}
+
+#[test]
+fn test_git_log_format_renders_commits_since_the_last_tag() {
+    let path = make_test_dir("git-log-format");
+
+    write_file(&path, "VERSION", "1.2.3");
+    init_git_repo(&path);
+    git(&path, &["tag", "v1.2.3"]);
+    for subject in ["Fix parser", "Add flag"] {
+        git(&path, &["commit", "-q", "--allow-empty", "-m", subject]);
+    }
+    let author = git(&path, &["log", "-1", "--format=%an"]);
+    write_file(
+        &path,
+        "message.hbs",
+        "release {{new_version}}\n\n{{#each commits}}- {{log_line}}\n{{/each}}",
+    );
+
+    semver_in_git(&path)
+        .args([
+            "--bump",
+            "patch",
+            "--git-commit",
+            "--template-file",
+            "message.hbs",
+            "--git-log-format",
+            "%an: %s",
+        ])
+        .assert()
+        .success()
+        .stderr(contains("unknown variable").not());
+
+    assert_eq!(
+        git(&path, &["log", "-1", "--format=%B"]),
+        format!(
+            "release 1.2.4\n\n- {author}: Add flag\n- {author}: Fix parser",
+            author = author
+        )
+    );
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args([
+            "--bump",
+            "patch",
+            "--template-file",
+            "message.hbs",
+            "--git-log-format",
+            "%s; rm -rf /",
+        ])
+        .assert()
+        .failure()
+        .stderr(contains("contains disallowed character ';'"));
+    assert_eq!(fs::read_to_string(path.join("VERSION")).unwrap(), "1.2.4");
+}
+
+#[test]
+fn test_template_file_warns_about_unknown_variables() {
+    let path = make_test_dir("template-file-unknown");
+
+    write_file(&path, "VERSION", "1.0.0");
+    init_git_repo(&path);
+    write_file(&path, "message.hbs", "Bump to {{new_version}}{{ticket}}");
+
+    semver_in_git(&path)
+        .args([
+            "--bump",
+            "patch",
+            "--git-commit",
+            "--template-file",
+            "message.hbs",
+        ])
+        .assert()
+        .success()
+        .stderr(contains("uses unknown variable 'ticket'"));
+
+    assert_eq!(git(&path, &["log", "-1", "--format=%s"]), "Bump to 1.0.1");
+}
+
+#[test]
+fn test_template_file_invalid_fails_before_writing() {
+    let path = make_test_dir("template-file-invalid");
+
+    write_file(&path, "VERSION", "1.0.0");
+    write_file(&path, "message.hbs", "{{#if branch}}unclosed");
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args([
+            "--bump",
+            "patch",
+            "--git-commit",
+            "--template-file",
+            "message.hbs",
+        ])
+        .assert()
+        .code(1)
+        .stderr(contains("Invalid template message.hbs"));
+
+    assert_eq!(fs::read_to_string(path.join("VERSION")).unwrap(), "1.0.0");
+}
+
+#[test]
+fn test_bump_if_changed_skips_without_changes() {
+    let path = make_test_dir("bump-if-changed-unchanged");
+
+    write_file(&path, "VERSION", "1.0.0");
+    init_git_repo(&path);
+    git(&path, &["tag", "v1.0.0"]);
+    git(&path, &["commit", "-q", "--allow-empty", "-m", "CI rerun"]);
+
+    semver_in_git(&path)
+        .args(["--bump", "patch", "--bump-if-changed"])
+        .assert()
+        .code(0)
+        .stdout(contains("No changes since v1.0.0; skipping bump"));
+
+    assert_eq!(fs::read_to_string(path.join("VERSION")).unwrap(), "1.0.0");
+}
+
+#[test]
+fn test_bump_if_changed_bumps_after_changes() {
+    let path = make_test_dir("bump-if-changed-changed");
+
+    write_file(&path, "VERSION", "1.0.0");
+    write_file(&path, "main.rs", "fn main() {}");
+    init_git_repo(&path);
+    git(&path, &["tag", "v1.0.0"]);
+    commit_file(&path, "main.rs", "fn main() { run() }");
+
+    semver_in_git(&path)
+        .args(["--bump", "patch", "--bump-if-changed"])
+        .assert()
+        .success()
+        .stdout(contains("1.0.0 → 1.0.1"));
+
+    assert_eq!(fs::read_to_string(path.join("VERSION")).unwrap(), "1.0.1");
+}
+
+#[test]
+fn test_commit_trailers_are_appended_in_order() {
+    let path = make_test_dir("commit-trailer");
+
+    write_file(&path, "VERSION", "1.0.0");
+    init_git_repo(&path);
+
+    semver_in_git(&path)
+        .args([
+            "--bump",
+            "patch",
+            "--git-commit",
+            "--commit-trailer",
+            "Signed-off-by: Jane <jane@example.com>",
+            "--commit-trailer",
+            "Refs: #42",
+        ])
+        .assert()
+        .success();
+
+    assert_eq!(
+        git(&path, &["log", "-1", "--format=%B"]),
+        "chore: bump version to 1.0.1\n\nSigned-off-by: Jane <jane@example.com>\nRefs: #42"
+    );
+    assert_eq!(
+        git(&path, &["log", "-1", "--format=%(trailers:only,unfold)"]),
+        "Signed-off-by: Jane <jane@example.com>\nRefs: #42"
+    );
+}
+
+#[test]
+fn test_commit_trailer_rejects_malformed_values() {
+    let path = make_test_dir("commit-trailer-invalid");
+
+    write_file(&path, "VERSION", "1.0.0");
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args([
+            "--bump",
+            "patch",
+            "--git-commit",
+            "--commit-trailer",
+            "oops",
+        ])
+        .assert()
+        .code(2)
+        .stderr(contains("invalid commit trailer 'oops'"));
+}
+
+#[test]
+fn test_tag_description_attaches_git_note() {
+    let path = make_test_dir("tag-description");
+
+    write_file(&path, "VERSION", "1.0.0");
+    init_git_repo(&path);
+
+    semver_in_git(&path)
+        .args([
+            "--bump",
+            "patch",
+            "--git-commit",
+            "--tag-description",
+            "Hotfix for login timeouts",
+        ])
+        .assert()
+        .success()
+        .stdout(contains("Created git tag v1.0.1 with a note"))
+        .stderr(contains("git notes are not pushed automatically"));
+
+    assert_eq!(git(&path, &["cat-file", "-t", "v1.0.1"]), "commit");
+    assert_eq!(
+        git(&path, &["notes", "show", "v1.0.1"]),
+        "Hotfix for login timeouts"
+    );
+}
+
+#[test]
+fn test_push_notes_sends_notes_to_origin() {
+    let path = make_test_dir("tag-description-push");
+    let remote = make_test_dir("tag-description-push-remote");
+
+    git(&remote, &["init", "-q", "--bare"]);
+    write_file(&path, "VERSION", "1.0.0");
+    init_git_repo(&path);
+    let remote = fs::canonicalize(&remote).unwrap();
+    git(
+        &path,
+        &["remote", "add", "origin", remote.to_str().unwrap()],
+    );
+
+    semver_in_git(&path)
+        .args([
+            "--bump",
+            "minor",
+            "--git-commit",
+            "--tag-prefix",
+            "release-",
+            "--tag-description",
+            "Spring release",
+            "--push-notes",
+        ])
+        .assert()
+        .success()
+        .stdout(contains("Pushed git notes to origin"))
+        .stderr(contains("not pushed automatically").not());
+
+    assert_eq!(
+        git(&path, &["notes", "show", "release-1.1.0"]),
+        "Spring release"
+    );
+    assert_eq!(
+        git(&remote, &["rev-parse", "refs/notes/commits"]),
+        git(&path, &["rev-parse", "refs/notes/commits"])
+    );
+}
+
+#[test]
+fn test_git_commit_include_all_modified() {
+    let path = make_test_dir("git-commit-all-modified");
+
+    write_file(&path, "VERSION", "1.0.0");
+    write_file(&path, "README.md", "readme");
+    init_git_repo(&path);
+    write_file(&path, "README.md", "edited");
+    write_file(&path, "untracked.txt", "new");
+
+    semver_in_git(&path)
+        .args(["--bump", "patch", "--git-commit", "--include-all-modified"])
+        .assert()
+        .success()
+        .stderr(contains("Warning: --include-all-modified"));
+
+    let files = git(&path, &["show", "--name-only", "--format=", "HEAD"]);
+    assert_eq!(files.lines().collect::<Vec<_>>(), ["README.md", "VERSION"]);
+    assert_eq!(git(&path, &["diff", "--name-only"]), "");
+    assert_eq!(git(&path, &["status", "--porcelain"]), "?? untracked.txt");
+}
+
+#[test]
+fn test_git_commit_only_version_files_leaves_staged_changes() {
+    for flag in [None, Some("--commit-only-version-files")] {
+        let path = make_test_dir("git-commit-only-version-files");
+
+        write_file(&path, "VERSION", "1.0.0");
+        write_file(&path, "README.md", "readme");
+        init_git_repo(&path);
+        write_file(&path, "README.md", "staged");
+        git(&path, &["add", "README.md"]);
+
+        let mut args = vec!["--bump", "patch", "--git-commit"];
+        args.extend(flag);
+        semver_in_git(&path).args(&args).assert().success();
+
+        let files = git(&path, &["show", "--name-only", "--format=", "HEAD"]);
+        assert_eq!(files, "VERSION", "{flag:?}");
+        assert_eq!(git(&path, &["status", "--porcelain"]), "M  README.md");
+    }
+}
+
+#[test]
+fn test_git_commit_everything() {
+    let path = make_test_dir("git-commit-everything");
+
+    write_file(&path, "VERSION", "1.0.0");
+    write_file(&path, "README.md", "readme");
+    init_git_repo(&path);
+    write_file(&path, "README.md", "edited");
+
+    semver_in_git(&path)
+        .args(["--bump", "patch", "--git-commit", "--commit-everything"])
+        .assert()
+        .success();
+
+    let files = git(&path, &["show", "--name-only", "--format=", "HEAD"]);
+    assert_eq!(files.lines().collect::<Vec<_>>(), ["README.md", "VERSION"]);
+    assert_eq!(git(&path, &["status", "--porcelain"]), "");
+}
+
+#[test]
+fn test_commit_only_version_files_conflicts_with_commit_everything() {
+    let path = make_test_dir("git-commit-only-conflict");
+
+    write_file(&path, "VERSION", "1.0.0");
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args([
+            "--bump",
+            "patch",
+            "--git-commit",
+            "--commit-only-version-files",
+            "--commit-everything",
+        ])
+        .assert()
+        .code(2)
+        .stderr(contains("cannot be used with"));
+}
+
+#[test]
+fn test_branch_matches_glob() {
+    let path = make_test_dir("branch-glob");
+
+    write_file(&path, "VERSION", "1.0.0");
+    init_git_repo(&path);
+    git(&path, &["checkout", "-q", "-b", "release/1.x"]);
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args(["--bump", "patch", "--branch", "release/*"])
+        .assert()
+        .success();
+
+    let updated = fs::read_to_string(path.join("VERSION")).unwrap();
+    assert_eq!(updated, "1.0.1");
+}
+
+#[test]
+fn test_branch_mismatch_fails() {
+    let path = make_test_dir("branch-mismatch");
+
+    write_file(&path, "VERSION", "1.0.0");
+    init_git_repo(&path);
+    git(&path, &["checkout", "-q", "-b", "feature/login"]);
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args(["--bump", "patch", "--branch", "main"])
+        .assert()
+        .code(24)
+        .stderr(contains(
+            "Refusing to bump on branch feature/login; expected main",
+        ));
+
+    let unchanged = fs::read_to_string(path.join("VERSION")).unwrap();
+    assert_eq!(unchanged, "1.0.0");
+}
+
+#[test]
+fn test_branch_skipped_outside_git() {
+    let path = make_test_dir("branch-no-git");
+
+    write_file(&path, "VERSION", "1.0.0");
+    let ceiling = fs::canonicalize("./tmp-test").unwrap();
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .env("GIT_CEILING_DIRECTORIES", ceiling)
+        .args(["--bump", "patch", "--branch", "main"])
+        .assert()
+        .success()
+        .stderr(contains("not a git repository; skipping --branch check"));
+
+    let updated = fs::read_to_string(path.join("VERSION")).unwrap();
+    assert_eq!(updated, "1.0.1");
+}
+
+#[test]
+fn test_interactive_source_priority_reorders_sources() {
+    let path = make_test_dir("source-priority");
+
+    write_file(&path, "package.json", r#"{"version": "1.0.0"}"#);
+    write_file(&path, "VERSION", "2.0.0");
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args(["--bump", "patch", "--interactive-source-priority"])
+        .write_stdin("2 1\n")
+        .assert()
+        .success()
+        .stdout(contains("1. package.json (1.0.0)"))
+        .stdout(contains("2. VERSION (2.0.0)"))
+        .stdout(contains("Bumping version 2.0.0 → 2.0.1"));
+
+    let version = fs::read_to_string(path.join("VERSION")).unwrap();
+    assert_eq!(version, "2.0.1");
+    let package = fs::read_to_string(path.join("package.json")).unwrap();
+    assert!(package.contains("\"version\": \"2.0.1\""));
+}
+
+#[test]
+fn test_interactive_source_priority_keeps_default_order() {
+    let path = make_test_dir("source-priority-default");
+
+    write_file(&path, "package.json", r#"{"version": "1.0.0"}"#);
+    write_file(&path, "VERSION", "2.0.0");
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args(["--bump", "patch", "--interactive-source-priority"])
+        .write_stdin("\n")
+        .assert()
+        .success()
+        .stdout(contains("Bumping version 1.0.0 → 1.0.1"));
+}
+
+#[test]
+fn test_from_stdin_reads_version_in_each_input_format() {
+    let inputs = [
+        (
+            "json",
+            "--json-key",
+            "app.version",
+            r#"{"app": {"version": "1.4.0"}}"#,
+        ),
+        (
+            "toml",
+            "--toml-key",
+            "package.version",
+            "[package]\nversion = \"1.4.0\"\n",
+        ),
+        (
+            "yaml",
+            "--yaml-key",
+            "version",
+            "name: demo\nversion: \"1.4.0\"\n",
+        ),
+        ("raw", "--json-key", "ignored", "v1.4.0\n"),
+    ];
+    for (format, key_flag, key, input) in inputs {
+        let path = make_test_dir(&format!("from-stdin-{}", format));
+        write_file(&path, "VERSION", "0.0.1");
+
+        let mut cmd = Command::cargo_bin("semver").unwrap();
+        cmd.current_dir(&path)
+            .args(["--bump", "minor", "--from", "-", "--input-format", format])
+            .args([key_flag, key])
+            .write_stdin(input)
+            .assert()
+            .success()
+            .stdout(contains("Bumping version 1.4.0 → 1.5.0"));
+
+        assert_eq!(fs::read_to_string(path.join("VERSION")).unwrap(), "1.5.0");
+    }
+}
+
+#[test]
+fn test_version_in_env_var_is_the_bump_base() {
+    let path = make_test_dir("version-env-var");
+
+    write_file(&path, "VERSION", "0.0.1");
+    write_file(&path, "package.json", r#"{ "version": "0.0.1" }"#);
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .env("BUILD_VERSION", "v2.3.4")
+        .args(["--bump", "minor", "--version-in-env-var", "BUILD_VERSION"])
+        .assert()
+        .success()
+        .stdout(contains("Current version: 2.3.4"))
+        .stdout(contains("Bumping version 2.3.4 → 2.4.0"));
+
+    assert_eq!(fs::read_to_string(path.join("VERSION")).unwrap(), "2.4.0");
+    assert!(
+        fs::read_to_string(path.join("package.json"))
+            .unwrap()
+            .contains("\"version\": \"2.4.0\"")
+    );
+}
+
+#[test]
+fn test_version_in_unset_env_var_fails() {
+    let path = make_test_dir("version-env-var-unset");
+
+    write_file(&path, "VERSION", "0.0.1");
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .env_remove("SEMVER_TEST_UNSET_VERSION")
+        .args(["--bump", "patch"])
+        .args(["--version-in-env-var", "SEMVER_TEST_UNSET_VERSION"])
+        .assert()
+        .code(1)
+        .stderr(contains(
+            "Environment variable SEMVER_TEST_UNSET_VERSION is not set or empty.",
+        ));
+
+    assert_eq!(fs::read_to_string(path.join("VERSION")).unwrap(), "0.0.1");
+}
+
+#[test]
+fn test_version_in_env_var_rejects_invalid_version() {
+    let path = make_test_dir("version-env-var-invalid");
+
+    write_file(&path, "VERSION", "0.0.1");
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .env("BUILD_VERSION", "not-a-version")
+        .args(["--bump", "patch", "--version-in-env-var", "BUILD_VERSION"])
+        .assert()
+        .code(1)
+        .stderr(contains(
+            "Invalid semantic version in $BUILD_VERSION: not-a-version",
+        ))
+        .stderr(contains("panicked").not());
+
+    assert_eq!(fs::read_to_string(path.join("VERSION")).unwrap(), "0.0.1");
+}
+
+#[test]
+fn test_from_stdin_without_version_key_fails() {
+    let path = make_test_dir("from-stdin-missing-key");
+    write_file(&path, "VERSION", "0.0.1");
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args(["--bump", "patch", "--from", "-", "--input-format", "json"])
+        .write_stdin(r#"{"name": "demo"}"#)
+        .assert()
+        .code(1)
+        .stderr(contains(
+            "Could not read a version from stdin: no string at key `version`",
+        ));
+
+    assert_eq!(fs::read_to_string(path.join("VERSION")).unwrap(), "0.0.1");
+}
+
+#[test]
+fn test_diff_files_lists_agreeing_files_as_ok() {
+    let path = make_test_dir("diff-files-agree");
+
+    write_file(&path, "package.json", r#"{"version": "1.2.3"}"#);
+    write_file(&path, "VERSION", "1.2.3");
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .arg("--diff-files")
+        .assert()
+        .success()
+        .stdout("package.json: OK\nVERSION: OK\n");
+}
+
+#[test]
+fn test_diff_files_reports_diverging_files() {
+    let path = make_test_dir("diff-files-diverge");
+
+    write_file(&path, "composer.json", r#"{"version": "1.2.3"}"#);
+    write_file(&path, "package.json", r#"{"version": "1.2.2"}"#);
+    write_file(&path, "VERSION", "1.2.3");
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .arg("--diff-files")
+        .assert()
+        .code(7)
+        .stdout("composer.json: OK\npackage.json: expected 1.2.3, found 1.2.2\nVERSION: OK\n");
+
+    let package = fs::read_to_string(path.join("package.json")).unwrap();
+    assert!(package.contains("1.2.2"));
+}
+
+#[test]
+fn test_verify_after_keeps_changes_on_success() {
+    let path = make_test_dir("verify-after-ok");
+
+    write_file(&path, "VERSION", "1.0.0");
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args(["--bump", "patch", "--verify-after", "grep -q 1.0.1 VERSION"])
+        .assert()
+        .success();
+
+    let updated = fs::read_to_string(path.join("VERSION")).unwrap();
+    assert_eq!(updated, "1.0.1");
+}
+
+#[test]
+fn test_verify_after_rolls_back_on_failure() {
+    let path = make_test_dir("verify-after-fail");
+
+    write_file(&path, "VERSION", "1.0.0");
+    write_file(&path, "package.json", "{\n  \"version\": \"1.0.0\"\n}");
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args(["--bump", "minor", "--verify-after", "grep -q 9.9.9 VERSION"])
+        .assert()
+        .code(16)
+        .stderr(contains("Verification command failed"));
+
+    assert_eq!(fs::read_to_string(path.join("VERSION")).unwrap(), "1.0.0");
+    assert_eq!(
+        fs::read_to_string(path.join("package.json")).unwrap(),
+        "{\n  \"version\": \"1.0.0\"\n}"
+    );
+}
+
+#[test]
+fn test_patch_file_rewrites_listed_files() {
+    let path = make_test_dir("patch-file");
+
+    write_file(&path, "VERSION", "1.2.3");
+    write_file(
+        &path,
+        "Dockerfile",
+        "FROM alpine\nLABEL version=\"1.2.3\"\n",
+    );
+    fs::create_dir_all(path.join("docs")).unwrap();
+    write_file(
+        &path,
+        "docs/install.md",
+        "npm i mytool@1.2.3\nSee 1.2.3 notes.\n",
+    );
+    write_file(
+        &path,
+        "versions.yaml",
+        concat!(
+            "- file: Dockerfile\n",
+            "  pattern: 'LABEL version=\"{old}\"'\n",
+            "  replacement: 'LABEL version=\"{new}\"'\n",
+            "- file: docs/install.md\n",
+            "  pattern: 'mytool@{old}'\n",
+            "  replacement: 'mytool@{new}'\n",
+            "- file: README.md\n",
+            "  pattern: 'v{old}'\n",
+            "  replacement: 'v{new}'\n",
+        ),
+    );
+    write_file(&path, "README.md", "No version here.\n");
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args(["--bump", "minor", "--patch-file", "versions.yaml"])
+        .assert()
+        .success()
+        .stdout(contains("Patching Dockerfile (1 replacement)"))
+        .stderr(contains("no patch patterns matched in README.md"));
+
+    assert_eq!(
+        fs::read_to_string(path.join("Dockerfile")).unwrap(),
+        "FROM alpine\nLABEL version=\"1.3.0\"\n"
+    );
+    assert_eq!(
+        fs::read_to_string(path.join("docs/install.md")).unwrap(),
+        "npm i mytool@1.3.0\nSee 1.2.3 notes.\n"
+    );
+    assert_eq!(fs::read_to_string(path.join("VERSION")).unwrap(), "1.3.0");
+}
+
+#[test]
+fn test_patch_file_missing_target() {
+    let path = make_test_dir("patch-file-missing");
+
+    write_file(&path, "VERSION", "1.2.3");
+    write_file(
+        &path,
+        "versions.yaml",
+        "- {file: nope.txt, pattern: '{old}', replacement: '{new}'}\n",
+    );
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args(["--bump", "patch", "--patch-file", "versions.yaml"])
+        .assert()
+        .code(1)
+        .stderr(contains("Invalid config versions.yaml: nope.txt"));
+
+    assert_eq!(fs::read_to_string(path.join("VERSION")).unwrap(), "1.2.3");
+}
+
+#[test]
+fn test_version_placeholder_is_replaced_in_files() {
+    let path = make_test_dir("version-placeholder");
+
+    write_file(&path, "VERSION", "1.2.3");
+    write_file(
+        &path,
+        "about.txt",
+        "Demo %%VERSION%%\nDownload demo-%%VERSION%%.tar.gz\n",
+    );
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args(["--bump", "minor", "--file", "about.txt"])
+        .args(["--version-placeholder", "%%VERSION%%"])
+        .assert()
+        .success()
+        .stdout(contains(
+            "Replacing %%VERSION%% in about.txt (2 replacements)",
+        ));
+
+    assert_eq!(
+        fs::read_to_string(path.join("about.txt")).unwrap(),
+        "Demo 1.3.0\nDownload demo-1.3.0.tar.gz\n"
+    );
+    assert_eq!(fs::read_to_string(path.join("VERSION")).unwrap(), "1.3.0");
+}
+
+#[test]
+fn test_replace_old_version_in_files() {
+    let path = make_test_dir("replace-old-version");
+
+    write_file(&path, "VERSION", "1.2.3");
+    write_file(&path, "install.md", "npm i demo@1.2.3\n");
+    write_file(&path, "notes.md", "nothing versioned\n");
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args(["--bump", "patch", "--replace-old-version"])
+        .args(["--file", "install.md", "--file", "notes.md"])
+        .assert()
+        .success()
+        .stdout(contains("Replacing 1.2.3 in install.md (1 replacement)"))
+        .stderr(contains("Warning: notes.md does not contain 1.2.3"));
+
+    assert_eq!(
+        fs::read_to_string(path.join("install.md")).unwrap(),
+        "npm i demo@1.2.4\n"
+    );
+    assert_eq!(
+        fs::read_to_string(path.join("notes.md")).unwrap(),
+        "nothing versioned\n"
+    );
+}
+
+#[test]
+fn test_file_requires_a_replacement_mode() {
+    let path = make_test_dir("file-without-placeholder");
+
+    write_file(&path, "VERSION", "1.2.3");
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args(["--bump", "patch", "--file", "VERSION"])
+        .assert()
+        .code(2);
+
+    assert_eq!(fs::read_to_string(path.join("VERSION")).unwrap(), "1.2.3");
+}
+
+#[test]
+fn test_sync_wasm_pkg_updates_generated_manifest() {
+    let path = make_test_dir("sync-wasm-pkg");
+
+    write_file(&path, "VERSION", "0.3.0");
+    fs::create_dir_all(path.join("pkg")).unwrap();
+    write_file(
+        &path,
+        "pkg/package.json",
+        r#"{ "name": "demo-wasm", "version": "0.3.0", "files": ["demo_bg.wasm"] }"#,
+    );
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args(["--bump", "minor"])
+        .assert()
+        .success();
+    assert!(
+        fs::read_to_string(path.join("pkg/package.json"))
+            .unwrap()
+            .contains("\"version\": \"0.3.0\"")
+    );
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args(["--bump", "patch", "--sync-wasm-pkg"])
+        .assert()
+        .success();
+
+    assert_eq!(fs::read_to_string(path.join("VERSION")).unwrap(), "0.4.1");
+    assert!(
+        fs::read_to_string(path.join("pkg/package.json"))
+            .unwrap()
+            .contains("\"version\": \"0.4.1\"")
+    );
+}
+
+#[test]
+fn test_sync_wasm_pkg_without_pkg_dir() {
+    let path = make_test_dir("sync-wasm-pkg-missing");
+
+    write_file(&path, "VERSION", "0.3.0");
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args(["--bump", "patch", "--sync-wasm-pkg"])
+        .assert()
+        .success();
+
+    assert_eq!(fs::read_to_string(path.join("VERSION")).unwrap(), "0.3.1");
+    assert!(!path.join("pkg").exists());
+}
+
+#[test]
+fn test_makefile_operator_styles() {
+    for (name, op) in [("Makefile", "="), ("makefile", ":="), ("GNUmakefile", "?=")] {
+        let path = make_test_dir(&format!("makefile-{}", name));
+
+        let contents = format!(
+            "VERSION {} 1.2.3\n\nbuild:\n\tgo build -ldflags \"-X main.version=$(VERSION)\"\n",
+            op
+        );
+        write_file(&path, name, &contents);
+
+        let mut cmd = Command::cargo_bin("semver").unwrap();
+        cmd.current_dir(&path)
+            .args(["--bump", "minor"])
+            .assert()
+            .success()
+            .stdout(contains("Bumping version 1.2.3 → 1.3.0"));
+
+        let updated = fs::read_to_string(path.join(name)).unwrap();
+        assert_eq!(updated, contents.replace("1.2.3", "1.3.0"));
+    }
+}
+
+#[test]
+fn test_explicit_makefile_path() {
+    let path = make_test_dir("makefile-explicit");
+
+    fs::create_dir_all(path.join("build")).unwrap();
+    write_file(&path, "build/release.mk", "NAME = app\nVERSION := 0.9.0\n");
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args(["--makefile", "build/release.mk", "--bump", "major"])
+        .assert()
+        .success();
+
+    let updated = fs::read_to_string(path.join("build/release.mk")).unwrap();
+    assert_eq!(updated, "NAME = app\nVERSION := 1.0.0\n");
+}
+
+#[test]
+fn test_tags_lists_semver_tags_latest_first() {
+    let path = make_test_dir("tags-list");
+
+    init_git_repo(&path);
+    for tag in [
+        "v1.0.0",
+        "v1.10.0",
+        "v1.2.0",
+        "v2.0.0-rc.1",
+        "latest",
+        "v1.x",
+        "1.5.0",
+    ] {
+        git(&path, &["tag", tag]);
+    }
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .arg("tags")
+        .assert()
+        .success()
+        .stdout("v2.0.0-rc.1\nv1.10.0\nv1.2.0\nv1.0.0\n");
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args(["tags", "--limit", "2", "--json"])
+        .assert()
+        .success()
+        .stdout(
+            "[{\"tag\":\"v2.0.0-rc.1\",\"version\":\"2.0.0-rc.1\"},{\"tag\":\"v1.10.0\",\"version\":\"1.10.0\"}]\n",
+        );
+}
+
+#[test]
+fn test_tags_with_empty_prefix() {
+    let path = make_test_dir("tags-no-prefix");
+
+    init_git_repo(&path);
+    for tag in ["v1.0.0", "1.5.0", "0.9.0"] {
+        git(&path, &["tag", tag]);
+    }
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args(["tags", "--tag-prefix", ""])
+        .assert()
+        .success()
+        .stdout("1.5.0\n0.9.0\n");
+}
+
+#[test]
+fn test_concurrent_writes_update_all_files() {
+    let path = make_test_dir("concurrent-writes");
+
+    write_file(&path, "VERSION", "1.0.0");
+    write_file(&path, "package.json", r#"{"version": "1.0.0"}"#);
+    write_file(&path, "composer.json", r#"{"version": "1.0.0"}"#);
+    write_file(&path, "semver-workspace.toml", r#"members = ["a", "b"]"#);
+    for member in ["a", "b"] {
+        fs::create_dir_all(path.join(member)).unwrap();
+        write_file(&path.join(member), "VERSION", "0.1.0");
+    }
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args([
+            "--bump",
+            "patch",
+            "--workspace-file",
+            "semver-workspace.toml",
+            "--concurrent-writes",
+            "--verbose",
+        ])
+        .assert()
+        .success()
+        .stderr(contains("Updated: a/VERSION"));
+
+    assert_eq!(fs::read_to_string(path.join("VERSION")).unwrap(), "1.0.1");
+    assert!(
+        fs::read_to_string(path.join("package.json"))
+            .unwrap()
+            .contains("1.0.1")
+    );
+    assert!(
+        fs::read_to_string(path.join("composer.json"))
+            .unwrap()
+            .contains("1.0.1")
+    );
+    assert_eq!(fs::read_to_string(path.join("a/VERSION")).unwrap(), "0.1.1");
+    assert_eq!(fs::read_to_string(path.join("b/VERSION")).unwrap(), "0.1.1");
+}
+
+#[test]
+fn test_bump_fails_when_a_file_cannot_be_written() {
+    for concurrent in [false, true] {
+        let path = make_test_dir(if concurrent {
+            "write-failure-concurrent"
+        } else {
+            "write-failure"
+        });
+
+        write_file(&path, "VERSION", "1.0.0");
+        write_file(&path, "package.json", r#"{"version": "1.0.0"}"#);
+        let mut permissions = fs::metadata(path.join("package.json"))
+            .unwrap()
+            .permissions();
+        permissions.set_readonly(true);
+        fs::set_permissions(path.join("package.json"), permissions).unwrap();
+
+        let mut cmd = Command::cargo_bin("semver").unwrap();
+        cmd.current_dir(&path).args(["--bump", "patch"]);
+        if concurrent {
+            cmd.arg("--concurrent-writes");
+        }
+        cmd.assert()
+            .code(1)
+            .stderr(contains("Failed to write package.json: file is read-only"));
+
+        assert_eq!(
+            fs::read_to_string(path.join("package.json")).unwrap(),
+            r#"{"version": "1.0.0"}"#
+        );
+    }
+}
+
+#[test]
+fn test_check_fails_when_sources_disagree() {
+    let path = make_test_dir("check-mismatch");
+
+    write_file(&path, "package.json", r#"{"version": "1.2.3"}"#);
+    write_file(&path, "VERSION", "1.2.2");
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .arg("--check")
+        .assert()
+        .code(1)
+        .stderr(contains(
+            "VERSION has version 1.2.2 but package.json has 1.2.3",
+        ));
+}
+
+#[test]
+fn test_check_only_primary_ignores_stale_sources() {
+    let path = make_test_dir("check-only-primary");
+
+    write_file(&path, "package.json", r#"{"version": "1.2.3"}"#);
+    write_file(&path, "VERSION", "not-a-version");
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args(["--check", "--check-only-primary"])
+        .assert()
+        .success()
+        .stdout("package.json: 1.2.3\n");
+
+    let untouched = fs::read_to_string(path.join("VERSION")).unwrap();
+    assert_eq!(untouched, "not-a-version");
+}
+
+#[test]
+fn test_sanitize_syncs_sources_to_primary() {
+    let path = make_test_dir("sanitize");
+
+    write_file(&path, "package.json", r#"{"version": "1.2.3"}"#);
+    write_file(&path, "VERSION", "1.2.2");
+    write_file(&path, ".version.json", r#"{"version": "1.0.0"}"#);
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .arg("--sanitize")
+        .assert()
+        .success()
+        .stdout(contains("Syncing version files to 1.2.3 from package.json"))
+        .stdout(contains("Updated VERSION: 1.2.2 → 1.2.3"))
+        .stdout(contains("Updated .version.json: 1.0.0 → 1.2.3"));
+
+    assert_eq!(fs::read_to_string(path.join("VERSION")).unwrap(), "1.2.3");
+    assert!(
+        fs::read_to_string(path.join(".version.json"))
+            .unwrap()
+            .contains("\"version\": \"1.2.3\"")
+    );
+    assert!(
+        fs::read_to_string(path.join("package.json"))
+            .unwrap()
+            .contains("\"version\": \"1.2.3\"")
+    );
+}
+
+#[test]
+fn test_unchanged_files_are_skipped_unless_force_write() {
+    let path = make_test_dir("force-write");
+
+    write_file(&path, "package.json", "{\n  \"version\": \"1.0.0\"\n}");
+    write_file(&path, "VERSION", "1.0.0");
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args(["--sanitize", "-v"])
+        .assert()
+        .success()
+        .stderr(contains("Skipped (unchanged): package.json"))
+        .stderr(contains("Skipped (unchanged): VERSION"));
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args(["--sanitize", "-v", "--force-write"])
+        .assert()
+        .success()
+        .stderr(contains("Updated: package.json"))
+        .stderr(contains("Updated: VERSION"));
+
+    assert_eq!(
+        fs::read_to_string(path.join("package.json")).unwrap(),
+        "{\n  \"version\": \"1.0.0\"\n}"
+    );
+    assert_eq!(fs::read_to_string(path.join("VERSION")).unwrap(), "1.0.0");
+}
+
+#[test]
+fn test_bump_updates_every_source_not_just_the_primary() {
+    let path = make_test_dir("update-all-sources");
+
+    write_file(&path, "package.json", r#"{ "version": "1.0.0" }"#);
+    write_file(&path, "VERSION", "0.9.0");
+    write_file(&path, ".version.json", r#"{ "version": "0.8.0" }"#);
+    write_file(
+        &path,
+        "mix.exs",
+        "  def project do\n    [version: \"0.7.0\"]\n  end\n",
+    );
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args(["--bump", "minor"])
+        .assert()
+        .success()
+        .stdout(contains("Bumping version 1.0.0 → 1.1.0"));
+
+    assert_eq!(fs::read_to_string(path.join("VERSION")).unwrap(), "1.1.0");
+    for json in ["package.json", ".version.json"] {
+        assert!(
+            fs::read_to_string(path.join(json))
+                .unwrap()
+                .contains("\"version\": \"1.1.0\""),
+            "{json}"
+        );
+    }
+    assert!(
+        fs::read_to_string(path.join("mix.exs"))
+            .unwrap()
+            .contains("version: \"1.1.0\"")
+    );
+}
+
+#[test]
+fn test_sanitize_dry_run_previews() {
+    let path = make_test_dir("sanitize-dry-run");
+
+    write_file(&path, "package.json", r#"{"version": "1.2.3"}"#);
+    write_file(&path, "VERSION", "1.2.2");
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args(["--sanitize", "--dry-run"])
+        .assert()
+        .success()
+        .stdout(contains("Would update VERSION: 1.2.2 → 1.2.3"));
+
+    assert_eq!(fs::read_to_string(path.join("VERSION")).unwrap(), "1.2.2");
+}
+
+#[test]
+fn test_require_git_fails_outside_repository() {
+    let path = make_test_dir("require-git-missing");
+
+    write_file(&path, "VERSION", "1.0.0");
+    let ceiling = fs::canonicalize("./tmp-test").unwrap();
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .env("GIT_CEILING_DIRECTORIES", ceiling)
+        .args(["--bump", "patch", "--require-git"])
+        .assert()
+        .code(17)
+        .stderr(contains("is not inside a git repository"));
+
+    assert_eq!(fs::read_to_string(path.join("VERSION")).unwrap(), "1.0.0");
+}
+
+#[test]
+fn test_require_git_inside_repository() {
+    let path = make_test_dir("require-git-present");
+
+    write_file(&path, "VERSION", "1.0.0");
+    init_git_repo(&path);
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args(["--bump", "patch", "--require-git"])
+        .assert()
+        .success();
+
+    assert_eq!(fs::read_to_string(path.join("VERSION")).unwrap(), "1.0.1");
+}
+
+#[test]
+fn test_git_commit_all_includes_untracked_files() {
+    let path = make_test_dir("git-commit-all");
+
+    write_file(&path, "VERSION", "1.0.0");
+    write_file(&path, "README.md", "readme");
+    init_git_repo(&path);
+    write_file(&path, "README.md", "edited");
+    write_file(&path, "package-lock.json", "{}");
+
+    semver_in_git(&path)
+        .args(["--bump", "patch", "--git-commit", "--commit-all"])
+        .assert()
+        .success()
+        .stderr(contains("Warning: --commit-all"));
+
+    let files = git(&path, &["show", "--name-only", "--format=", "HEAD"]);
+    assert_eq!(
+        files.lines().collect::<Vec<_>>(),
+        ["README.md", "VERSION", "package-lock.json"]
+    );
+    assert_eq!(git(&path, &["status", "--porcelain"]), "");
+}
+
+fn commit_file(path: &Path, name: &str, content: &str) {
+    write_file(path, name, content);
+    git(path, &["add", name]);
+    git(path, &["commit", "-q", "-m", &format!("wip {}", name)]);
+}
+
+#[test]
+fn test_squash_commits_folds_commits_into_bump() {
+    let path = make_test_dir("squash-commits");
+
+    write_file(&path, "VERSION", "1.0.0");
+    init_git_repo(&path);
+    let initial = git(&path, &["rev-parse", "HEAD"]);
+    commit_file(&path, "a.txt", "a");
+    commit_file(&path, "b.txt", "b");
+
+    semver_in_git(&path)
+        .args(["--bump", "patch", "--git-commit", "--squash-commits", "2"])
+        .assert()
+        .success()
+        .stdout(contains("Squashed 2 commits into the version bump"));
+
+    assert_eq!(git(&path, &["rev-parse", "HEAD~1"]), initial);
+    assert_eq!(
+        git(&path, &["log", "-1", "--format=%s"]),
+        "chore: bump version to 1.0.1"
+    );
+    let files = git(&path, &["show", "--name-only", "--format=", "HEAD"]);
+    assert_eq!(
+        files.lines().collect::<Vec<_>>(),
+        ["VERSION", "a.txt", "b.txt"]
+    );
+}
+
+#[test]
+fn test_squash_commits_auto_squashes_since_last_tag() {
+    let path = make_test_dir("squash-commits-auto");
+
+    write_file(&path, "VERSION", "1.0.0");
+    init_git_repo(&path);
+    git(&path, &["tag", "v1.0.0"]);
+    commit_file(&path, "a.txt", "a");
+    commit_file(&path, "b.txt", "b");
+    commit_file(&path, "c.txt", "c");
+
+    semver_in_git(&path)
+        .args([
+            "--bump",
+            "minor",
+            "--git-commit",
+            "--squash-commits",
+            "auto",
+        ])
+        .assert()
+        .success()
+        .stdout(contains("Squashed 3 commits into the version bump"));
+
+    assert_eq!(git(&path, &["rev-list", "--count", "v1.0.0..HEAD"]), "1");
+    assert_eq!(git(&path, &["status", "--porcelain"]), "");
+}
+
+#[test]
+fn test_squash_commits_stops_at_merge_base() {
+    let path = make_test_dir("squash-commits-merge-base");
+
+    write_file(&path, "VERSION", "1.0.0");
+    init_git_repo(&path);
+    commit_file(&path, "main.txt", "main");
+    git(&path, &["checkout", "-q", "-b", "feature"]);
+    git(&path, &["branch", "-q", "--set-upstream-to=main"]);
+    commit_file(&path, "a.txt", "a");
+    commit_file(&path, "b.txt", "b");
+    let head = git(&path, &["rev-parse", "HEAD"]);
+
+    semver_in_git(&path)
+        .args(["--bump", "patch", "--git-commit", "--squash-commits", "3"])
+        .assert()
+        .code(1)
+        .stderr(contains(
+            "Cannot squash 3 commits: only 2 are above the merge base",
+        ));
+
+    assert_eq!(git(&path, &["rev-parse", "HEAD"]), head);
+    assert_eq!(fs::read_to_string(path.join("VERSION")).unwrap(), "1.0.0");
+}
+
+#[test]
+fn test_mirror_copies_updated_files() {
+    let path = make_test_dir("mirror");
+
+    write_file(
+        &path,
+        "package.json",
+        r#"{ "name": "demo", "version": "1.2.3" }"#,
+    );
+    write_file(&path, "VERSION", "1.2.3");
+    write_file(&path, "README.md", "readme");
+    fs::create_dir(path.join("dist")).unwrap();
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args(["--bump", "minor", "--mirror", "dist"])
+        .assert()
+        .success()
+        .stdout(contains("Mirrored 2 files to dist"));
+
+    for name in ["package.json", "VERSION"] {
+        assert_eq!(
+            fs::read_to_string(path.join("dist").join(name)).unwrap(),
+            fs::read_to_string(path.join(name)).unwrap()
+        );
+    }
+    assert_eq!(
+        fs::read_to_string(path.join("dist/VERSION")).unwrap(),
+        "1.3.0"
+    );
+    assert!(!path.join("dist/README.md").exists());
+}
+
+#[test]
+fn test_mirror_requires_existing_directory() {
+    let path = make_test_dir("mirror-missing");
+
+    write_file(&path, "VERSION", "1.2.3");
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args(["--bump", "patch", "--mirror", "dist"])
+        .assert()
+        .code(1)
+        .stderr(contains("Mirror directory dist does not exist."));
+
+    assert_eq!(fs::read_to_string(path.join("VERSION")).unwrap(), "1.2.3");
+}
+
+#[test]
+fn test_mirror_fails_on_read_only_destination() {
+    let path = make_test_dir("mirror-read-only");
+
+    write_file(&path, "VERSION", "1.2.3");
+    fs::create_dir(path.join("dist")).unwrap();
+    write_file(&path, "dist/VERSION", "1.2.3");
+    let mut permissions = fs::metadata(path.join("dist/VERSION"))
+        .unwrap()
+        .permissions();
+    permissions.set_readonly(true);
+    fs::set_permissions(path.join("dist/VERSION"), permissions).unwrap();
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args(["--bump", "patch", "--mirror", "dist"])
+        .assert()
+        .code(1)
+        .stderr(contains(
+            "Failed to write dist/VERSION: destination file is read-only",
+        ));
+
+    assert_eq!(
+        fs::read_to_string(path.join("dist/VERSION")).unwrap(),
+        "1.2.3"
+    );
+}
+
+/// Put a `git` on PATH that logs its arguments to `git-args.log` in `dir`
+/// before running the real git; returns the PATH to use.
+#[cfg(unix)]
+fn logging_git_path(dir: &Path) -> String {
+    use std::os::unix::fs::PermissionsExt;
+
+    let path = std::env::var("PATH").unwrap();
+    let real_git = std::env::split_paths(&path)
+        .map(|dir| dir.join("git"))
+        .find(|git| git.is_file())
+        .expect("git on PATH");
+    let bin = fs::canonicalize(dir).unwrap().join("bin");
+    fs::create_dir_all(&bin).unwrap();
+    let log = bin.parent().unwrap().join("git-args.log");
+    let wrapper = bin.join("git");
+    fs::write(
+        &wrapper,
+        format!(
+            "#!/bin/sh\necho \"$@\" >> '{}'\nexec '{}' \"$@\"\n",
+            log.display(),
+            real_git.display()
+        ),
+    )
+    .unwrap();
+    fs::set_permissions(&wrapper, fs::Permissions::from_mode(0o755)).unwrap();
+    format!("{}:{}", bin.display(), path)
+}
+
+#[cfg(all(unix, feature = "parallel-git"))]
+#[test]
+fn test_parallel_git_ops_keeps_dependency_order() {
+    let path = make_test_dir("parallel-git-ops");
+
+    write_file(&path, "VERSION", "1.0.0");
+    write_file(&path, "README.md", "readme");
+    init_git_repo(&path);
+    write_file(&path, "README.md", "staged");
+    git(&path, &["add", "README.md"]);
+    let search_path = logging_git_path(&path);
+
+    semver_in_git(&path)
+        .env("PATH", search_path)
+        .args([
+            "--bump",
+            "patch",
+            "--git-commit",
+            "--git-tag-annotated",
+            "--parallel-git-ops",
+        ])
+        .assert()
+        .success()
+        .stdout(contains("Committed version 1.0.1").and(contains("Created git tag v1.0.1")));
+
+    let log = fs::read_to_string(path.join("git-args.log")).unwrap();
+    let position = |prefix: &str| {
+        log.lines()
+            .position(|line| line.starts_with(prefix))
+            .unwrap_or_else(|| panic!("no `git {prefix}` in {log}"))
+    };
+    assert!(position("add -- VERSION") < position("commit -q -m"));
+    assert!(position("commit -q -m") < position("tag -a v1.0.1"));
+
+    assert_eq!(
+        git(&path, &["rev-parse", "v1.0.1^{commit}"]),
+        git(&path, &["rev-parse", "HEAD"])
+    );
+    let files = git(&path, &["show", "--name-only", "--format=", "HEAD"]);
+    assert_eq!(files, "VERSION");
+    assert_eq!(
+        git(&path, &["status", "--porcelain", "--untracked-files=no"]),
+        "M  README.md"
+    );
+}
+
+#[cfg(feature = "parallel-git")]
+#[test]
+fn test_parallel_git_ops_conflicts_with_squash() {
+    let path = make_test_dir("parallel-git-ops-squash");
+
+    write_file(&path, "VERSION", "1.0.0");
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args([
+            "--bump",
+            "patch",
+            "--git-commit",
+            "--squash-commits",
+            "2",
+            "--parallel-git-ops",
+        ])
+        .assert()
+        .code(2)
+        .stderr(contains("cannot be used with"));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_git_credential_helper_is_passed_to_git() {
+    let path = make_test_dir("git-credential-helper");
+
+    write_file(&path, "VERSION", "1.0.0");
+    init_git_repo(&path);
+    let search_path = logging_git_path(&path);
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .env("PATH", search_path)
+        .env_remove("GITHUB_TOKEN")
+        .env_remove("SEMVER_GITHUB_TOKEN")
+        .args([
+            "--bump",
+            "patch",
+            "--require-git",
+            "--git-credential-helper",
+            "cache",
+        ])
+        .assert()
+        .success();
+
+    let log = fs::read_to_string(path.join("git-args.log")).unwrap();
+    assert!(!log.is_empty());
+    for line in log.lines() {
+        assert!(line.starts_with("-c credential.helper=cache "), "{line}");
+    }
+}
+
+#[cfg(unix)]
+#[test]
+fn test_github_token_is_masked_and_kept_off_the_command_line() {
+    let path = make_test_dir("git-github-token");
+
+    write_file(&path, "VERSION", "1.0.0");
+    init_git_repo(&path);
+    let search_path = logging_git_path(&path);
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .env("PATH", search_path)
+        .env_remove("SEMVER_GITHUB_TOKEN")
+        .env("GITHUB_TOKEN", "ghp_secret")
+        .args(["--bump", "patch", "--require-git", "-v"])
+        .assert()
+        .success()
+        .stderr(contains("(GitHub token: ****)"))
+        .stderr(contains("ghp_secret").not());
+
+    let log = fs::read_to_string(path.join("git-args.log")).unwrap();
+    assert!(log.starts_with("-c credential.https://github.com.helper="));
+    assert!(!log.contains("ghp_secret"));
+}
+
+/// Put a fake `gpg` on PATH that logs its arguments to `gpg-args.log` in
+/// `dir`, "signs" by writing `FAKE SIGNATURE` to `--output` and only
+/// verifies signatures holding that text; returns the PATH to use.
+#[cfg(unix)]
+fn fake_gpg_path(dir: &Path) -> String {
+    use std::os::unix::fs::PermissionsExt;
+
+    let bin = fs::canonicalize(dir).unwrap().join("bin");
+    fs::create_dir_all(&bin).unwrap();
+    let log = bin.parent().unwrap().join("gpg-args.log");
+    let script = format!(
+        r#"#!/bin/sh
+echo "$@" >> '{}'
+prev=""
+for arg in "$@"; do
+  if [ "$prev" = "--output" ]; then echo "FAKE SIGNATURE" > "$arg"; fi
+  if [ "$prev" = "--verify" ]; then grep -q "FAKE SIGNATURE" "$arg" || exit 1; fi
+  prev="$arg"
+done
+"#,
+        log.display()
+    );
+    fs::write(bin.join("gpg"), script).unwrap();
+    fs::set_permissions(bin.join("gpg"), fs::Permissions::from_mode(0o755)).unwrap();
+    format!("{}:{}", bin.display(), std::env::var("PATH").unwrap())
+}
+
+#[cfg(unix)]
+#[test]
+fn test_sign_file_signs_and_commits_signature() {
+    let path = make_test_dir("sign-file");
+
+    write_file(&path, "VERSION", "1.0.0");
+    init_git_repo(&path);
+    let search_path = fake_gpg_path(&path);
+
+    semver_in_git(&path)
+        .env("PATH", search_path)
+        .args([
+            "--bump",
+            "patch",
+            "--sign-file",
+            "VERSION",
+            "--sign-key",
+            "ABCD1234",
+            "--git-commit",
+        ])
+        .assert()
+        .success()
+        .stdout(contains("Signed VERSION into VERSION.asc"));
+
+    let log = fs::read_to_string(path.join("gpg-args.log")).unwrap();
+    assert_eq!(
+        log.trim(),
+        "--batch --yes --detach-sign --armor --local-user ABCD1234 --output VERSION.asc VERSION"
+    );
+    assert_eq!(
+        fs::read_to_string(path.join("VERSION.asc")).unwrap(),
+        "FAKE SIGNATURE\n"
+    );
+    let files = git(&path, &["show", "--name-only", "--format=", "HEAD"]);
+    assert_eq!(
+        files.lines().collect::<Vec<_>>(),
+        ["VERSION", "VERSION.asc"]
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn test_sign_file_without_gpg() {
+    let path = make_test_dir("sign-file-no-gpg");
+
+    write_file(&path, "VERSION", "1.0.0");
+    fs::create_dir(path.join("empty-bin")).unwrap();
+    let search_path = fs::canonicalize(path.join("empty-bin")).unwrap();
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .env("PATH", search_path)
+        .args(["--bump", "patch", "--sign-file", "VERSION"])
+        .assert()
+        .code(1)
+        .stderr(contains("gpg is not available"));
+
+    assert!(!path.join("VERSION.asc").exists());
+}
+
+#[cfg(unix)]
+#[test]
+fn test_verify_sign_checks_signature_before_bumping() {
+    let path = make_test_dir("verify-sign");
+
+    write_file(&path, "VERSION", "1.0.0");
+    write_file(&path, "VERSION.asc", "TAMPERED\n");
+    let search_path = fake_gpg_path(&path);
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .env("PATH", &search_path)
+        .args(["--bump", "patch", "--sign-file", "VERSION", "--verify-sign"])
+        .assert()
+        .code(1)
+        .stderr(contains(
+            "Signing failed: gpg --batch --verify VERSION.asc VERSION failed",
+        ));
+    assert_eq!(fs::read_to_string(path.join("VERSION")).unwrap(), "1.0.0");
+
+    write_file(&path, "VERSION.asc", "FAKE SIGNATURE\n");
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .env("PATH", &search_path)
+        .args(["--bump", "patch", "--sign-file", "VERSION", "--verify-sign"])
+        .assert()
+        .success();
+    assert_eq!(fs::read_to_string(path.join("VERSION")).unwrap(), "1.0.1");
+}
+
+#[test]
+fn test_global_config_sets_default_bump() {
+    let path = make_test_dir("global-config");
+
+    write_file(&path, "VERSION", "1.0.0");
+    write_file(&path, "dotfiles-semver.toml", "default_bump = \"minor\"\n");
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args(["--global-config", "dotfiles-semver.toml"])
+        .assert()
+        .success();
+
+    assert_eq!(fs::read_to_string(path.join("VERSION")).unwrap(), "1.1.0");
+}
+
+#[test]
+fn test_project_config_overrides_global_config() {
+    let path = make_test_dir("global-config-override");
+
+    write_file(&path, "VERSION", "1.0.0");
+    write_file(&path, "global.toml", "default_bump = \"minor\"\n");
+    write_file(&path, ".semverrc.toml", "default_bump = \"major\"\n");
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args(["--global-config", "global.toml"])
+        .assert()
+        .success();
+
+    assert_eq!(fs::read_to_string(path.join("VERSION")).unwrap(), "2.0.0");
+}
+
+#[test]
+fn test_xdg_global_config_is_discovered() {
+    let path = make_test_dir("global-config-xdg");
+
+    write_file(&path, "VERSION", "1.0.0");
+    fs::create_dir_all(path.join("xdg/semver")).unwrap();
+    write_file(
+        &path,
+        "xdg/semver/config.toml",
+        "default_bump = \"major\"\n",
+    );
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .env(
+            "XDG_CONFIG_HOME",
+            fs::canonicalize(path.join("xdg")).unwrap(),
+        )
+        .assert()
+        .success();
+
+    assert_eq!(fs::read_to_string(path.join("VERSION")).unwrap(), "2.0.0");
+}
+
+#[test]
+fn test_missing_global_config_is_an_error() {
+    let path = make_test_dir("global-config-missing");
+
+    write_file(&path, "VERSION", "1.0.0");
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args(["--global-config", "nope.toml", "--bump", "patch"])
+        .assert()
+        .code(1)
+        .stderr(contains("Invalid config nope.toml"));
+
+    assert_eq!(fs::read_to_string(path.join("VERSION")).unwrap(), "1.0.0");
+}
+
+#[test]
+fn test_print_config_shows_merged_config_without_bumping() {
+    let path = make_test_dir("print-config");
+
+    write_file(&path, "VERSION", "1.0.0");
+    write_file(&path, "global.toml", "ignore_files = [\"legacy/*\"]\n");
+    write_file(&path, ".semverrc.toml", "default_bump = \"minor\"\n");
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args([
+            "--global-config",
+            "global.toml",
+            "--ignore-files",
+            "docs/*",
+            "--print-config",
+        ])
+        .assert()
+        .success()
+        .stdout("default_bump = \"minor\"\nignore_files = [\"legacy/*\", \"docs/*\"]\n");
+
+    assert_eq!(fs::read_to_string(path.join("VERSION")).unwrap(), "1.0.0");
+}
+
+#[test]
+fn test_no_reset_patch_keeps_patch_on_minor_bump() {
+    let path = make_test_dir("no-reset-patch");
+
+    write_file(&path, "VERSION", "1.2.3");
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args(["--bump", "minor", "--no-reset-patch"])
+        .assert()
+        .success()
+        .stderr(contains("Warning: --no-reset-patch does not follow semver"));
+
+    assert_eq!(fs::read_to_string(path.join("VERSION")).unwrap(), "1.3.3");
+}
+
+#[test]
+fn test_github_actions_summary_appends_markdown_table() {
+    let path = make_test_dir("github-step-summary");
+
+    write_file(&path, "VERSION", "1.0.0");
+    write_file(&path, "summary.md", "Earlier step\n");
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .env("GITHUB_ACTIONS", "true")
+        .env(
+            "GITHUB_STEP_SUMMARY",
+            fs::canonicalize(path.join("summary.md")).unwrap(),
+        )
+        .args(["--bump", "minor", "--github-actions-summary"])
+        .assert()
+        .success();
+
+    assert_eq!(
+        fs::read_to_string(path.join("summary.md")).unwrap(),
+        "Earlier step\n### Version bump\n\n\
+         | Old version | New version | Bump type | Files updated |\n\
+         | --- | --- | --- | --- |\n\
+         | `1.0.0` | `1.1.0` | minor | `VERSION` |\n\n"
+    );
+}
+
+#[test]
+fn test_github_actions_summary_without_summary_file_warns() {
+    let path = make_test_dir("github-step-summary-unset");
+
+    write_file(&path, "VERSION", "1.0.0");
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .env_remove("GITHUB_STEP_SUMMARY")
+        .args(["--bump", "patch", "--github-actions-summary"])
+        .assert()
+        .success()
+        .stderr(contains("GITHUB_STEP_SUMMARY is not set"));
+
+    assert_eq!(fs::read_to_string(path.join("VERSION")).unwrap(), "1.0.1");
+}
+
+#[test]
+fn test_clean_pre_release_strips_prerelease_without_bumping() {
+    let path = make_test_dir("clean-pre-release");
+
+    write_file(&path, "package.json", r#"{"version": "1.2.3-beta.1"}"#);
+    write_file(&path, "VERSION", "1.2.3-beta.1");
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .arg("--clean-pre-release")
+        .assert()
+        .success()
+        .stdout(contains("Bumping version 1.2.3-beta.1 → 1.2.3"));
+
+    assert_eq!(fs::read_to_string(path.join("VERSION")).unwrap(), "1.2.3");
+    let package = fs::read_to_string(path.join("package.json")).unwrap();
+    assert!(package.contains("\"version\": \"1.2.3\""));
+}
+
+#[test]
+fn test_clean_pre_release_on_release_version_exits_4() {
+    let path = make_test_dir("clean-pre-release-none");
+
+    write_file(&path, "VERSION", "1.2.3");
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .arg("--clean-pre-release")
+        .assert()
+        .code(4)
+        .stderr(contains("Version 1.2.3 has no pre-release"));
+
+    assert_eq!(fs::read_to_string(path.join("VERSION")).unwrap(), "1.2.3");
+}
+
+#[test]
+fn test_component_floors_block_low_versions() {
+    for (flag, floor, component) in [
+        ("--min-major", "2", "major"),
+        ("--min-minor", "5", "minor"),
+        ("--min-patch", "3", "patch"),
+    ] {
+        let path = make_test_dir(&format!("min-{}", component));
+        write_file(&path, "VERSION", "1.3.1");
+
+        let mut cmd = Command::cargo_bin("semver").unwrap();
+        cmd.current_dir(&path)
+            .args(["--bump", "patch", flag, floor])
+            .assert()
+            .code(26)
+            .stderr(contains(format!(
+                "New version 1.3.2 has a {} below the floor of {} set by {}",
+                component, floor, flag
+            )));
+
+        assert_eq!(fs::read_to_string(path.join("VERSION")).unwrap(), "1.3.1");
+    }
+}
+
+#[test]
+fn test_component_floors_allow_versions_at_the_floor() {
+    let path = make_test_dir("min-components-met");
+    write_file(&path, "VERSION", "1.3.1");
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args(["--bump", "patch", "--min-major", "1", "--min-minor", "3"])
+        .args(["--min-patch", "2"])
+        .assert()
+        .success();
+
+    assert_eq!(fs::read_to_string(path.join("VERSION")).unwrap(), "1.3.2");
+}
+
+#[test]
+fn test_no_auto_detect_only_updates_explicit_files() {
+    let path = make_test_dir("no-auto-detect");
+
+    write_file(&path, "package.json", r#"{ "version": "9.9.9" }"#);
+    write_file(&path, "VERSION", "9.9.9");
+    write_file(&path, "mix.exs", "  version: \"9.9.9\",\n");
+    write_file(&path, "release.json", r#"{ "version": "1.0.0" }"#);
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args([
+            "--bump",
+            "patch",
+            "--no-auto-detect",
+            "--version-json",
+            "release.json",
+        ])
+        .assert()
+        .success()
+        .stdout(contains("Bumping version 1.0.0 → 1.0.1"));
+
+    assert!(
+        fs::read_to_string(path.join("release.json"))
+            .unwrap()
+            .contains("\"version\": \"1.0.1\"")
+    );
+    assert_eq!(
+        fs::read_to_string(path.join("package.json")).unwrap(),
+        r#"{ "version": "9.9.9" }"#
+    );
+    assert_eq!(fs::read_to_string(path.join("VERSION")).unwrap(), "9.9.9");
+    assert_eq!(
+        fs::read_to_string(path.join("mix.exs")).unwrap(),
+        "  version: \"9.9.9\",\n"
+    );
+}
+
+#[test]
+fn test_no_auto_detect_without_explicit_files_exits_21() {
+    let path = make_test_dir("no-auto-detect-nothing");
+
+    write_file(&path, "VERSION", "1.0.0");
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args(["--bump", "patch", "--no-auto-detect"])
+        .assert()
+        .code(21)
+        .stderr(contains("--no-auto-detect needs at least one version file"));
+
+    assert_eq!(fs::read_to_string(path.join("VERSION")).unwrap(), "1.0.0");
+}
+
+fn log_entries(path: &Path) -> Vec<serde_json::Value> {
+    fs::read_to_string(path)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect()
+}
+
+#[test]
+fn test_log_file_records_successful_run() {
+    let path = make_test_dir("log-file-ok");
+
+    write_file(&path, "VERSION", "1.0.0");
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args(["--bump", "minor", "--log-file", "semver.log"])
+        .assert()
+        .success();
+
+    let entries = log_entries(&path.join("semver.log"));
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[0]["event"], "start");
+    assert_eq!(
+        entries[0]["args"].as_array().unwrap()[1..],
+        ["--bump", "minor", "--log-file", "semver.log"]
+    );
+    assert_eq!(entries[1]["event"], "end");
+    assert_eq!(entries[1]["result"], "ok");
+    assert_eq!(entries[1]["old"], "1.0.0");
+    assert_eq!(entries[1]["new"], "1.1.0");
+    assert_eq!(entries[1]["files"], serde_json::json!(["VERSION"]));
+    assert!(entries[1].get("error").is_none());
+}
+
+#[test]
+fn test_log_file_records_failed_run() {
+    let path = make_test_dir("log-file-error");
+
+    write_file(&path, "VERSION", "1.0.0");
+    write_file(&path, "semver.log", "{\"event\":\"earlier\"}\n");
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args([
+            "--bump",
+            "patch",
+            "--min-major",
+            "2",
+            "--log-file",
+            "semver.log",
+        ])
+        .assert()
+        .code(26);
+
+    let entries = log_entries(&path.join("semver.log"));
+    assert_eq!(entries.len(), 3);
+    assert_eq!(entries[1]["event"], "start");
+    assert_eq!(entries[2]["result"], "error");
+    assert_eq!(
+        entries[2]["error"],
+        "New version 1.0.1 has a major below the floor of 2 set by --min-major"
+    );
+    assert_eq!(entries[2]["files"], serde_json::json!([]));
+}
+
+#[test]
+fn test_fail_on_prerelease_blocks_prerelease_bump() {
+    let path = make_test_dir("fail-on-prerelease");
+
+    write_file(&path, "VERSION", "1.1.0-alpha.1");
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args([
+            "--bump",
+            "minor",
+            "--pre-release",
+            "beta",
+            "--fail-on-prerelease",
+        ])
+        .assert()
+        .code(18)
+        .stderr(contains("Refusing to bump pre-release 1.1.0-alpha.1"));
+    assert_eq!(
+        fs::read_to_string(path.join("VERSION")).unwrap(),
+        "1.1.0-alpha.1"
+    );
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args([
+            "--bump",
+            "minor",
+            "--pre-release",
+            "beta",
+            "--fail-on-prerelease",
+            "--force-prerelease",
+        ])
+        .assert()
+        .success();
+    assert_eq!(
+        fs::read_to_string(path.join("VERSION")).unwrap(),
+        "1.2.0-beta.1"
+    );
+}
+
+const DEMO_SNAPCRAFT_YAML: &str = r#"name: demo-cli
+base: core22
+version: '2.3.1'
+summary: Command-line client for the demo service
+description: |
+  Talks to the demo service from the terminal.
+grade: stable
+confinement: strict
+
+apps:
+  demo-cli:
+    command: bin/demo-cli
+    plugs: [network]
+
+parts:
+  demo-cli:
+    plugin: rust
+    source: .
+    # pinned helper, not the snap's version
+    version: 0.4.0
+"#;
+
+#[test]
+fn test_bumps_snapcraft_yaml_in_snap_dir() {
+    let path = make_test_dir("snapcraft-snap-dir");
+
+    fs::create_dir_all(path.join("snap")).unwrap();
+    write_file(&path, "snap/snapcraft.yaml", DEMO_SNAPCRAFT_YAML);
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args(["--bump", "minor"])
+        .assert()
+        .success()
+        .stdout(contains("2.3.1 → 2.4.0"));
+
+    let updated = fs::read_to_string(path.join("snap/snapcraft.yaml")).unwrap();
+    assert_eq!(updated, DEMO_SNAPCRAFT_YAML.replace("'2.3.1'", "'2.4.0'"));
+}
+
+#[test]
+fn test_snapcraft_adopt_info_is_skipped() {
+    let path = make_test_dir("snapcraft-adopt-info");
+
+    let snapcraft = r#"name: demo-cli
+base: core22
+adopt-info: demo-cli
+summary: Command-line client for the demo service
+
+parts:
+  demo-cli:
+    plugin: rust
+    source: .
+    override-pull: |
+      craftctl default
+      craftctl set version="$(cat VERSION)"
+"#;
+    write_file(&path, "snapcraft.yaml", snapcraft);
+    write_file(&path, "VERSION", "2.3.1");
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args(["--bump", "patch", "-v"])
+        .assert()
+        .success()
+        .stdout(contains("2.3.1 → 2.3.2"))
+        .stderr(contains(
+            "Skipped (version set by adopt-info): snapcraft.yaml",
+        ));
+
+    assert_eq!(fs::read_to_string(path.join("VERSION")).unwrap(), "2.3.2");
+    assert_eq!(
+        fs::read_to_string(path.join("snapcraft.yaml")).unwrap(),
+        snapcraft
+    );
+}
+
+#[test]
+fn test_snapcraft_rejects_versions_over_32_characters() {
+    let path = make_test_dir("snapcraft-too-long");
+
+    fs::create_dir_all(path.join("packaging")).unwrap();
+    write_file(&path, "packaging/snapcraft.yaml", DEMO_SNAPCRAFT_YAML);
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args([
+            "--snapcraft",
+            "packaging/snapcraft.yaml",
+            "--bump",
+            "patch",
+            "--pre-release",
+            "nightly-integration-build",
+        ])
+        .assert()
+        .code(1)
+        .stderr(contains("more than the limit of 32"));
+
+    assert_eq!(
+        fs::read_to_string(path.join("packaging/snapcraft.yaml")).unwrap(),
+        DEMO_SNAPCRAFT_YAML
+    );
+}
+
+fn read_json_version(dir: &Path, name: &str) -> String {
+    let contents = fs::read_to_string(dir.join(name)).unwrap();
+    let json: serde_json::Value = serde_json::from_str(&contents).unwrap();
+    json["version"].as_str().unwrap().to_string()
+}
+
+fn write_polyglot_project(path: &Path) {
+    write_file(
+        path,
+        "composer.json",
+        r#"{"name": "acme/api", "version": "1.4.0"}"#,
+    );
+    write_file(
+        path,
+        "package.json",
+        r#"{"name": "acme-web", "version": "1.4.0"}"#,
+    );
+    write_file(path, "VERSION", "1.4.0");
+}
+
+#[test]
+fn test_bump_scope_node_only_touches_node_files() {
+    let path = make_test_dir("bump-scope-node");
+    write_polyglot_project(&path);
+    write_file(
+        &path,
+        "package-lock.json",
+        r#"{"name": "acme-web", "version": "1.4.0", "lockfileVersion": 3, "packages": {"": {"name": "acme-web", "version": "1.4.0"}}}"#,
+    );
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args(["--bump", "minor", "--bump-scope", "node", "-v"])
+        .assert()
+        .success()
+        .stdout(contains("1.4.0 → 1.5.0"))
+        .stderr(contains("Updated: package-lock.json"))
+        .stderr(contains("Skipped (outside --bump-scope): VERSION"));
+
+    assert_eq!(read_json_version(&path, "package.json"), "1.5.0");
+    let lock: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(path.join("package-lock.json")).unwrap()).unwrap();
+    assert_eq!(lock["version"], "1.5.0");
+    assert_eq!(lock["packages"][""]["version"], "1.5.0");
+    assert_eq!(read_json_version(&path, "composer.json"), "1.4.0");
+    assert_eq!(fs::read_to_string(path.join("VERSION")).unwrap(), "1.4.0");
+}
+
+#[test]
+fn test_bump_scope_php_reads_and_writes_composer_json() {
+    let path = make_test_dir("bump-scope-php");
+    write_polyglot_project(&path);
+    write_file(
+        &path,
+        "composer.json",
+        r#"{"name": "acme/api", "version": "2.0.0"}"#,
+    );
+    write_file(
+        &path,
+        "package-lock.json",
+        r#"{"name": "acme-web", "version": "1.4.0", "lockfileVersion": 3}"#,
+    );
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args(["--bump", "patch", "--bump-scope", "php"])
+        .assert()
+        .success()
+        .stdout(contains("2.0.0 → 2.0.1"));
+
+    assert_eq!(read_json_version(&path, "composer.json"), "2.0.1");
+    assert_eq!(read_json_version(&path, "package.json"), "1.4.0");
+    assert_eq!(read_json_version(&path, "package-lock.json"), "1.4.0");
+    assert_eq!(fs::read_to_string(path.join("VERSION")).unwrap(), "1.4.0");
+}
+
+#[test]
+fn test_bump_scope_rust_and_python_read_their_manifests() {
+    let cargo = "[package]\nname = \"acme\"\nversion = \"0.3.0\" # keep in step\n\n[dependencies]\nserde = \"1\"\n";
+    let pyproject = "[project]\nname = \"acme\"\nversion = \"0.9.1\"\n";
+
+    for (scope, file, contents, current, bumped) in [
+        ("rust", "Cargo.toml", cargo, "0.3.0", "0.3.1"),
+        ("python", "pyproject.toml", pyproject, "0.9.1", "0.9.2"),
+    ] {
+        let path = make_test_dir(&format!("bump-scope-{}", scope));
+        write_polyglot_project(&path);
+        write_file(&path, "Cargo.toml", cargo);
+        write_file(&path, "pyproject.toml", pyproject);
+
+        let mut cmd = Command::cargo_bin("semver").unwrap();
+        cmd.current_dir(&path)
+            .args(["--bump", "patch", "--bump-scope", scope])
+            .assert()
+            .success()
+            .stdout(contains(format!("{} → {}", current, bumped)));
+
+        assert_eq!(
+            fs::read_to_string(path.join(file)).unwrap(),
+            contents.replace(current, bumped)
+        );
+        for other in ["Cargo.toml", "pyproject.toml"]
+            .into_iter()
+            .filter(|other| *other != file)
+        {
+            assert!(
+                !fs::read_to_string(path.join(other))
+                    .unwrap()
+                    .contains(bumped)
+            );
+        }
+        assert_eq!(read_json_version(&path, "package.json"), "1.4.0");
+        assert_eq!(fs::read_to_string(path.join("VERSION")).unwrap(), "1.4.0");
+    }
+}
+
+#[test]
+fn test_manifests_are_left_alone_outside_their_scope() {
+    let path = make_test_dir("manifests-outside-scope");
+    let cargo = "[package]\nname = \"acme\"\nversion = \"0.1.0\"\n";
+    let pyproject = "[project]\nname = \"acme\"\nversion = \"0.1.0\"\n";
+
+    write_file(&path, "VERSION", "1.0.0");
+    write_file(&path, "Cargo.toml", cargo);
+    write_file(&path, "pyproject.toml", pyproject);
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path).arg("--check").assert().success();
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args(["--bump", "patch", "--verbose"])
+        .assert()
+        .success()
+        .stdout(contains("1.0.0 → 1.0.1"))
+        .stderr(contains("Cargo.toml").not());
+
+    assert_eq!(fs::read_to_string(path.join("VERSION")).unwrap(), "1.0.1");
+    assert_eq!(fs::read_to_string(path.join("Cargo.toml")).unwrap(), cargo);
+    assert_eq!(
+        fs::read_to_string(path.join("pyproject.toml")).unwrap(),
+        pyproject
+    );
+}
+
+#[test]
+fn test_bump_scope_without_matching_files_fails() {
+    for scope in ["rust", "python"] {
+        let path = make_test_dir(&format!("bump-scope-{}-missing", scope));
+        write_polyglot_project(&path);
+
+        let mut cmd = Command::cargo_bin("semver").unwrap();
+        cmd.current_dir(&path)
+            .args(["--bump", "patch", "--bump-scope", scope])
+            .assert()
+            .code(1)
+            .stderr(contains(format!("No version found in {} files.", scope)));
+
+        assert_eq!(read_json_version(&path, "package.json"), "1.4.0");
+    }
+}
+
+#[test]
+fn test_bump_scope_all_updates_every_file() {
+    let path = make_test_dir("bump-scope-all");
+    write_polyglot_project(&path);
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args(["--bump", "major", "--bump-scope", "all"])
+        .assert()
+        .success();
+
+    assert_eq!(read_json_version(&path, "composer.json"), "2.0.0");
+    assert_eq!(read_json_version(&path, "package.json"), "2.0.0");
+    assert_eq!(fs::read_to_string(path.join("VERSION")).unwrap(), "2.0.0");
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args(["--bump", "patch", "--bump-scope", "go"])
+        .assert()
+        .code(2)
+        .stderr(contains("expected node, php, rust, python or all"));
+}
+
+#[test]
+fn test_batch_file_applies_steps_in_order() {
+    let path = make_test_dir("batch-file");
+
+    write_file(&path, "VERSION", "1.2.3");
+    write_file(
+        &path,
+        "release.batch",
+        "# steps generated by scripts/release.sh\nminor\n\npatch\npatch\n",
+    );
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args(["--batch-file", "release.batch"])
+        .assert()
+        .success()
+        .stdout(contains("Step 1/3: minor 1.2.3 → 1.3.0"))
+        .stdout(contains("Step 3/3: patch 1.3.1 → 1.3.2"))
+        .stdout(contains("Bumping version 1.2.3 → 1.3.2"));
+
+    assert_eq!(fs::read_to_string(path.join("VERSION")).unwrap(), "1.3.2");
+}
+
+#[test]
+fn test_invalid_batch_file_changes_nothing() {
+    let path = make_test_dir("batch-file-invalid");
+
+    write_file(&path, "VERSION", "1.2.3");
+    write_file(&path, "release.batch", "minor\n# then\nhotfix\n");
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args(["--batch-file", "release.batch"])
+        .assert()
+        .code(1)
+        .stdout(contains("Step").not())
+        .stderr(contains(
+            "Invalid batch file release.batch, line 3: invalid bump type 'hotfix'",
+        ));
+
+    assert_eq!(fs::read_to_string(path.join("VERSION")).unwrap(), "1.2.3");
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args(["--batch-file", "release.batch", "--bump", "patch"])
+        .assert()
+        .code(2);
+}
+
+#[test]
+fn test_next_and_print_next_respect_no_newline() {
+    let path = make_test_dir("next-no-newline");
+
+    write_file(&path, "VERSION", "1.4.2");
+
+    for args in [
+        &["next", "minor", "--no-newline"][..],
+        &["--print-next", "minor", "--no-newline"],
+    ] {
+        let mut cmd = Command::cargo_bin("semver").unwrap();
+        let output = cmd.current_dir(&path).args(args).output().unwrap();
+        assert!(output.status.success());
+        assert_eq!(output.stdout, b"1.5.0");
+    }
+}
+
+#[test]
+fn test_print_next_matches_next_subcommand() {
+    let path = make_test_dir("print-next");
+
+    write_file(
+        &path,
+        "package.json",
+        r#"{"name": "demo", "version": "1.4.2"}"#,
+    );
+
+    for (bump, expected) in [("major", "2.0.0"), ("minor", "1.5.0"), ("patch", "1.4.3")] {
+        let flag = Command::cargo_bin("semver")
+            .unwrap()
+            .current_dir(&path)
+            .args(["--bump", "patch", "--print-next", bump])
+            .assert()
+            .success()
+            .get_output()
+            .stdout
+            .clone();
+        let subcommand = Command::cargo_bin("semver")
+            .unwrap()
+            .current_dir(&path)
+            .args(["next", bump])
+            .assert()
+            .success()
+            .get_output()
+            .stdout
+            .clone();
+
+        assert_eq!(String::from_utf8(flag).unwrap(), format!("{}\n", expected));
+        assert_eq!(
+            String::from_utf8(subcommand).unwrap(),
+            format!("{}\n", expected)
+        );
+    }
+
+    assert_eq!(
+        fs::read_to_string(path.join("package.json")).unwrap(),
+        r#"{"name": "demo", "version": "1.4.2"}"#
+    );
+}
+
+#[test]
+fn test_update_deps_bumps_exact_requirements_in_cargo_workspace() {
+    let path = make_test_dir("update-deps");
+
+    write_file(
+        &path,
+        "Cargo.toml",
+        r#"[package]
+name = "demo-core"
+version = "1.2.3"
+
+[workspace]
+members = ["crates/*"]
+"#,
+    );
+    write_file(&path, "VERSION", "1.2.3");
+    fs::create_dir_all(path.join("crates/cli")).unwrap();
+    fs::create_dir_all(path.join("crates/server")).unwrap();
+    let cli = r#"[package]
+name = "demo-cli"
+version = "0.4.0"
+
+[dependencies]
+demo-core = { path = "../..", version = "=1.2.3" }
+clap = "4"
+
+[dev-dependencies]
+demo-core = { path = "../..", version = "=1.2.3", features = ["test-util"] }
+"#;
+    let server = r#"[package]
+name = "demo-server"
+version = "0.2.0"
+
+[dependencies]
+demo-core = { path = "../..", version = "1.2" }
+"#;
+    write_file(&path, "crates/cli/Cargo.toml", cli);
+    write_file(&path, "crates/server/Cargo.toml", server);
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args(["--bump", "minor", "--update-deps"])
+        .assert()
+        .success()
+        .stdout(contains(
+            "Updating demo-core =1.2.3 → =1.3.0 in crates/cli/Cargo.toml (2 references)",
+        ));
+
+    assert_eq!(
+        fs::read_to_string(path.join("crates/cli/Cargo.toml")).unwrap(),
+        cli.replace("=1.2.3", "=1.3.0")
+    );
+    assert_eq!(
+        fs::read_to_string(path.join("crates/server/Cargo.toml")).unwrap(),
+        server
+    );
+    assert!(
+        fs::read_to_string(path.join("Cargo.toml"))
+            .unwrap()
+            .contains("version = \"1.3.0\"")
+    );
+    assert_eq!(fs::read_to_string(path.join("VERSION")).unwrap(), "1.3.0");
+}
+
+#[test]
+fn test_update_deps_refuses_when_the_package_is_not_bumped() {
+    let path = make_test_dir("update-deps-not-bumped");
+
+    let manifest = "[package]\nname = \"demo-core\"\nversion = \"1.2.3\"\n\n[workspace]\nmembers = [\"cli\"]\n";
+    let cli = "[package]\nname = \"demo-cli\"\nversion = \"0.1.0\"\n\n[dependencies]\ndemo-core = { path = \"..\", version = \"=1.2.3\" }\n";
+    write_file(&path, "Cargo.toml", manifest);
+    write_file(&path, "VERSION", "1.2.3");
+    fs::create_dir_all(path.join("cli")).unwrap();
+    write_file(&path, "cli/Cargo.toml", cli);
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args(["--bump", "patch", "--update-deps", "--ignore-files", "Cargo.toml"])
+        .assert()
+        .code(1)
+        .stderr(contains(
+            "--update-deps would require demo-core =1.2.4, but the [package] version in Cargo.toml is not being bumped.",
+        ));
+
+    assert_eq!(
+        fs::read_to_string(path.join("Cargo.toml")).unwrap(),
+        manifest
+    );
+    assert_eq!(
+        fs::read_to_string(path.join("cli/Cargo.toml")).unwrap(),
+        cli
+    );
+    assert_eq!(fs::read_to_string(path.join("VERSION")).unwrap(), "1.2.3");
+}
+
+#[test]
+fn test_update_deps_without_cargo_package_warns() {
+    let path = make_test_dir("update-deps-no-package");
+
+    write_file(&path, "VERSION", "1.2.3");
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args(["--bump", "patch", "--update-deps"])
+        .assert()
+        .success()
+        .stderr(contains(
+            "Warning: --update-deps needs a Cargo.toml with a [package] name.",
+        ));
+
+    assert_eq!(fs::read_to_string(path.join("VERSION")).unwrap(), "1.2.4");
+}
+
+/// A throwaway GPG home in `dir` holding one signing key for
+/// `test@example.com`, without a passphrase.
+fn temp_gpg_keyring(dir: &Path) -> PathBuf {
+    use std::os::unix::fs::PermissionsExt;
+
+    let home = dir.join("gnupg");
+    fs::create_dir_all(&home).unwrap();
+    fs::set_permissions(&home, fs::Permissions::from_mode(0o700)).unwrap();
+    // git and semver run inside `dir`, so the path must not be relative.
+    let home = home.canonicalize().unwrap();
+    let status = std::process::Command::new("gpg")
+        .env("GNUPGHOME", &home)
+        .args([
+            "--batch",
+            "--pinentry-mode",
+            "loopback",
+            "--passphrase",
+            "",
+            "--quick-gen-key",
+            "Test <test@example.com>",
+            "ed25519",
+            "sign",
+            "never",
+        ])
+        .output()
+        .expect("Failed to run gpg")
+        .status;
+    assert!(status.success(), "gpg key generation failed");
+    home
+}
+
+#[test]
+fn test_tag_verify_accepts_signed_tag() {
+    let path = make_test_dir("tag-verify-signed");
+
+    write_file(&path, "VERSION", "1.0.0");
+    init_git_repo(&path);
+    let gnupg = temp_gpg_keyring(&path);
+    let output = std::process::Command::new("git")
+        .current_dir(&path)
+        .env("GNUPGHOME", &gnupg)
+        .env("GIT_COMMITTER_NAME", "Test")
+        .env("GIT_COMMITTER_EMAIL", "test@example.com")
+        .args([
+            "-c",
+            "user.signingkey=test@example.com",
+            "tag",
+            "-s",
+            "v1.0.1",
+            "-m",
+            "signed release",
+        ])
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{:?}", output);
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .env("GNUPGHOME", &gnupg)
+        .env("GIT_COMMITTER_NAME", "Test")
+        .env("GIT_COMMITTER_EMAIL", "test@example.com")
+        .args([
+            "--bump",
+            "patch",
+            "--git-tag-annotated",
+            "--tag-force",
+            "--tag-verify",
+        ])
+        .assert()
+        .success()
+        .stdout(contains("Verified the signature of git tag v1.0.1"))
+        .stdout(contains("Created git tag v1.0.1"));
+
+    let _ = std::process::Command::new("gpgconf")
+        .env("GNUPGHOME", &gnupg)
+        .args(["--kill", "gpg-agent"])
+        .output();
+}
+
+#[test]
+fn test_tag_verify_rejects_unsigned_tag() {
+    let path = make_test_dir("tag-verify-unsigned");
+
+    write_file(&path, "VERSION", "1.0.0");
+    init_git_repo(&path);
+    git(&path, &["tag", "-a", "v1.0.1", "-m", "unsigned"]);
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .env("GIT_COMMITTER_NAME", "Test")
+        .env("GIT_COMMITTER_EMAIL", "test@example.com")
+        .args([
+            "--bump",
+            "patch",
+            "--git-tag-annotated",
+            "--tag-force",
+            "--tag-verify",
+        ])
+        .assert()
+        .code(22)
+        .stderr(contains("Could not verify the signature of git tag v1.0.1"));
+
+    assert_eq!(fs::read_to_string(path.join("VERSION")).unwrap(), "1.0.0");
+    assert_eq!(
+        git(
+            &path,
+            &["tag", "-l", "--format=%(contents:subject)", "v1.0.1"]
+        )
+        .trim(),
+        "unsigned"
+    );
+}
+
+#[test]
+fn test_bump_pre_then_release_commits_both_steps() {
+    let path = make_test_dir("pre-then-release");
+
+    write_file(&path, "VERSION", "1.0.0");
+    write_file(
+        &path,
+        "package.json",
+        r#"{"name": "demo", "version": "1.0.0"}"#,
+    );
+    init_git_repo(&path);
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .env("GIT_AUTHOR_NAME", "Test")
+        .env("GIT_AUTHOR_EMAIL", "test@example.com")
+        .env("GIT_COMMITTER_NAME", "Test")
+        .env("GIT_COMMITTER_EMAIL", "test@example.com")
+        .args(["--bump", "minor", "--bump-pre-then-release", "--git-commit"])
+        .assert()
+        .success()
+        .stdout(contains("Committed version 1.1.0-rc.1"))
+        .stdout(contains("Committed version 1.1.0"));
+
+    assert_eq!(
+        git(&path, &["log", "--format=%s"]),
+        "chore: bump version to 1.1.0\nchore: bump version to 1.1.0-rc.1\ninitial"
+    );
+    assert_eq!(git(&path, &["show", "HEAD~1:VERSION"]), "1.1.0-rc.1");
+    assert_eq!(read_json_version(&path, "package.json"), "1.1.0");
+    assert_eq!(git(&path, &["status", "--porcelain"]), "");
+}
+
+#[test]
+fn test_bump_pre_then_release_rolls_back_both_steps() {
+    let path = make_test_dir("pre-then-release-rollback");
+
+    write_file(&path, "VERSION", "1.0.0");
+    init_git_repo(&path);
+    // Accept the pre-release commit, reject the release one.
+    let hook = path.join(".git/hooks/commit-msg");
+    fs::write(&hook, "#!/bin/sh\ngrep -q -- '-rc' \"$1\"\n").unwrap();
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&hook, fs::Permissions::from_mode(0o755)).unwrap();
+    }
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .env("GIT_AUTHOR_NAME", "Test")
+        .env("GIT_AUTHOR_EMAIL", "test@example.com")
+        .env("GIT_COMMITTER_NAME", "Test")
+        .env("GIT_COMMITTER_EMAIL", "test@example.com")
+        .args(["--bump", "patch", "--bump-pre-then-release", "--git-commit"])
+        .assert()
+        .code(1)
+        .stdout(contains("Committed version 1.0.1-rc.1"))
+        .stderr(contains(
+            "Rolled back both steps of --bump-pre-then-release.",
+        ));
+
+    assert_eq!(git(&path, &["log", "--format=%s"]), "initial");
+    assert_eq!(fs::read_to_string(path.join("VERSION")).unwrap(), "1.0.0");
+    assert_eq!(git(&path, &["status", "--porcelain"]), "");
+}
+
+#[test]
+fn test_bump_pre_then_release_without_git() {
+    let path = make_test_dir("pre-then-release-no-git");
+
+    write_file(&path, "VERSION", "1.4.2");
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args(["--bump", "major", "--bump-pre-then-release", "beta"])
+        .assert()
+        .success()
+        .stdout(contains("Pre-release step: 1.4.2 → 2.0.0-beta.1"))
+        .stdout(contains("Bumping version 1.4.2 → 2.0.0"));
+
+    assert_eq!(fs::read_to_string(path.join("VERSION")).unwrap(), "2.0.0");
+}
+
+#[test]
+fn test_require_changelog_entry_passes_with_entry() {
+    let path = make_test_dir("changelog-entry-present");
+
+    write_file(&path, "VERSION", "1.2.3");
+    write_file(
+        &path,
+        "CHANGELOG.md",
+        "# Changelog\n\n## [1.3.0] - 2026-10-15\n### feat\n- Snapcraft support\n\n## [1.2.3] - 2026-09-01\n",
+    );
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args(["--bump", "minor", "--require-changelog-entry"])
+        .assert()
+        .success();
+
+    assert_eq!(fs::read_to_string(path.join("VERSION")).unwrap(), "1.3.0");
+}
+
+#[test]
+fn test_require_changelog_entry_fails_without_entry() {
+    let path = make_test_dir("changelog-entry-absent");
+
+    write_file(&path, "VERSION", "1.2.3");
+    write_file(
+        &path,
+        "CHANGELOG.md",
+        "# Changelog\n\n## [Unreleased]\n- Snapcraft support\n\n## [1.2.3] - 2026-09-01\n",
+    );
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args(["--bump", "minor", "--require-changelog-entry"])
+        .assert()
+        .code(23)
+        .stderr(contains(
+            "CHANGELOG.md has no entry for the new version; add a `## [1.3.0]` section before bumping",
+        ));
+    assert_eq!(fs::read_to_string(path.join("VERSION")).unwrap(), "1.2.3");
+
+    fs::remove_file(path.join("CHANGELOG.md")).unwrap();
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args(["--bump", "minor", "--require-changelog-entry"])
+        .assert()
+        .code(23);
+}
+
+#[test]
+fn test_changelog_header_level_sets_the_entry_heading() {
+    let path = make_test_dir("changelog-header-level");
+
+    write_file(&path, "VERSION", "1.2.3");
+    write_file(
+        &path,
+        "CHANGELOG.md",
+        "# Changelog\n\n## 2026\n\n### [1.3.0] - 2026-10-15\n#### feat\n- Snapcraft support\n",
+    );
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args(["--bump", "minor", "--require-changelog-entry"])
+        .assert()
+        .code(23)
+        .stderr(contains("add a `## [1.3.0]` section"));
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args([
+            "--bump",
+            "minor",
+            "--require-changelog-entry",
+            "--changelog-header-level",
+            "3",
+            "--changelog-subheader-level",
+            "4",
+        ])
+        .assert()
+        .success();
+    assert_eq!(fs::read_to_string(path.join("VERSION")).unwrap(), "1.3.0");
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args(["--bump", "minor", "--changelog-header-level", "3"])
+        .assert()
+        .code(1)
+        .stderr(contains(
+            "Invalid changelog heading levels: changelog subheader level 3 must be greater than header level 3",
+        ));
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args(["--bump", "minor", "--changelog-subheader-level", "7"])
+        .assert()
+        .code(2)
+        .stderr(contains("expected a number from 1 to 6"));
+    assert_eq!(fs::read_to_string(path.join("VERSION")).unwrap(), "1.3.0");
+}
+
+#[test]
+fn test_pre_release_from_branch_uses_last_component() {
+    let path = make_test_dir("pre-release-from-branch");
+
+    write_file(&path, "VERSION", "1.2.3");
+    init_git_repo(&path);
+    git(&path, &["checkout", "-q", "-b", "release/beta_2"]);
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args(["--bump", "minor", "--pre-release-from-branch"])
+        .assert()
+        .success()
+        .stdout(contains("1.2.3 → 1.3.0-beta-2.1"));
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args(["--bump", "minor", "--pre-release-from-branch"])
+        .assert()
+        .success()
+        .stdout(contains("1.3.0-beta-2.1 → 1.3.0-beta-2.2"));
+}
+
+#[test]
+fn test_pre_release_from_branch_falls_back_on_mainline() {
+    let path = make_test_dir("pre-release-from-main");
+
+    write_file(&path, "VERSION", "1.2.3");
+    init_git_repo(&path);
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args(["--bump", "patch", "--pre-release-from-branch"])
+        .assert()
+        .code(1)
+        .stderr(contains("Branch main does not name a pre-release"));
+    assert_eq!(fs::read_to_string(path.join("VERSION")).unwrap(), "1.2.3");
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .args([
+            "--bump",
+            "patch",
+            "--pre-release-from-branch",
+            "--pre-release",
+            "rc",
+        ])
+        .assert()
+        .success()
+        .stdout(contains("1.2.3 → 1.2.4-rc.1"));
+}
+
+#[test]
+fn test_build_metadata_from_env() {
+    let path = make_test_dir("build-metadata-env");
+
+    write_file(&path, "VERSION", "1.2.3");
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .env("BUILD_NUMBER", "42")
+        .args([
+            "--bump",
+            "patch",
+            "--build-metadata-from-env",
+            "BUILD_NUMBER",
+            "--build-metadata-prefix",
+            "build.",
+        ])
+        .assert()
+        .success()
+        .stdout(contains("1.2.3 → 1.2.4+build.42"));
+    assert_eq!(
+        fs::read_to_string(path.join("VERSION")).unwrap(),
+        "1.2.4+build.42"
+    );
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .env("CI_PIPELINE_IID", "7 (retry)")
+        .args([
+            "--bump",
+            "patch",
+            "--build-metadata-from-env",
+            "CI_PIPELINE_IID",
+        ])
+        .assert()
+        .code(1)
+        .stderr(contains(
+            "Invalid build metadata \"7 (retry)\" from environment variable CI_PIPELINE_IID",
+        ));
+}
+
+#[test]
+fn test_build_metadata_from_unset_env_is_skipped() {
+    let path = make_test_dir("build-metadata-env-unset");
+
+    write_file(&path, "VERSION", "1.2.3");
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .env_remove("BUILD_NUMBER")
+        .args([
+            "--bump",
+            "minor",
+            "--build-metadata-from-env",
+            "BUILD_NUMBER",
+        ])
+        .assert()
+        .success()
+        .stdout(contains("1.2.3 → 1.3.0\n"));
+
+    let mut cmd = Command::cargo_bin("semver").unwrap();
+    cmd.current_dir(&path)
+        .env("BUILD_NUMBER", "")
+        .args([
+            "--bump",
+            "minor",
+            "--build-metadata-from-env",
+            "BUILD_NUMBER",
+        ])
+        .assert()
+        .success()
+        .stdout(contains("1.3.0 → 1.4.0\n"));
+    assert_eq!(fs::read_to_string(path.join("VERSION")).unwrap(), "1.4.0");
+}